@@ -0,0 +1,4 @@
+drop trigger slots_check_slot_status on slots;
+drop function check_slot_status;
+drop table slots;
+drop type slot_status;
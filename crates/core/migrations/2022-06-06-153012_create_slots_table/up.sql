@@ -0,0 +1,27 @@
+create type slot_status as enum ('Processed', 'Confirmed', 'Rooted');
+
+create table slots (
+  slot bigint primary key,
+  parent_slot bigint,
+  status slot_status not null
+);
+
+create function check_slot_status() returns trigger
+  language plpgsql
+  as $EOF$
+begin
+  new.parent_slot := coalesce(new.parent_slot, old.parent_slot);
+
+  if old.status > new.status then
+    new.status := old.status;
+  end if;
+
+  return new;
+end
+$EOF$;
+
+create trigger slots_check_slot_status
+before update on slots for row
+execute function check_slot_status();
+
+create index if not exists slots_status_idx on slots (status, slot);
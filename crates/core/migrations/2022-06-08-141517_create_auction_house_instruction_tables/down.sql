@@ -0,0 +1,4 @@
+drop table buy_instructions;
+drop table sell_instructions;
+drop table cancel_instructions;
+drop table execute_sale_instructions;
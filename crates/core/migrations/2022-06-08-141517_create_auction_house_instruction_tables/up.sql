@@ -0,0 +1,107 @@
+create table buy_instructions (
+  id uuid primary key default gen_random_uuid(),
+  wallet varchar(48) not null,
+  payment_account varchar(48) not null,
+  transfer_authority varchar(48) not null,
+  treasury_mint varchar(48) not null,
+  token_account varchar(48) not null,
+  metadata varchar(48) not null,
+  escrow_payment_account varchar(48) not null,
+  authority varchar(48) not null,
+  auction_house varchar(48) not null,
+  auction_house_fee_account varchar(48) not null,
+  buyer_trade_state varchar(48) not null,
+  trade_state_bump smallint not null,
+  escrow_payment_bump smallint not null,
+  buyer_price bigint not null,
+  token_size bigint not null,
+  slot bigint not null,
+  tx_signature varchar(88) not null,
+  instruction_index integer not null,
+  created_at timestamp not null default now(),
+  unique (tx_signature, instruction_index)
+);
+
+create table sell_instructions (
+  id uuid primary key default gen_random_uuid(),
+  wallet varchar(48) not null,
+  token_account varchar(48) not null,
+  metadata varchar(48) not null,
+  authority varchar(48) not null,
+  auction_house varchar(48) not null,
+  auction_house_fee_account varchar(48) not null,
+  seller_trade_state varchar(48) not null,
+  free_seller_trade_state varchar(48) not null,
+  program_as_signer varchar(48) not null,
+  trade_state_bump smallint not null,
+  free_trade_state_bump smallint not null,
+  program_as_signer_bump smallint not null,
+  buyer_price bigint not null,
+  token_size bigint not null,
+  slot bigint not null,
+  tx_signature varchar(88) not null,
+  instruction_index integer not null,
+  created_at timestamp not null default now(),
+  unique (tx_signature, instruction_index)
+);
+
+create table cancel_instructions (
+  id uuid primary key default gen_random_uuid(),
+  wallet varchar(48) not null,
+  token_account varchar(48) not null,
+  token_mint varchar(48) not null,
+  authority varchar(48) not null,
+  auction_house varchar(48) not null,
+  auction_house_fee_account varchar(48) not null,
+  trade_state varchar(48) not null,
+  buyer_price bigint not null,
+  token_size bigint not null,
+  slot bigint not null,
+  tx_signature varchar(88) not null,
+  instruction_index integer not null,
+  created_at timestamp not null default now(),
+  unique (tx_signature, instruction_index)
+);
+
+create table execute_sale_instructions (
+  id uuid primary key default gen_random_uuid(),
+  buyer varchar(48) not null,
+  seller varchar(48) not null,
+  token_account varchar(48) not null,
+  token_mint varchar(48) not null,
+  metadata varchar(48) not null,
+  treasury_mint varchar(48) not null,
+  escrow_payment_account varchar(48) not null,
+  seller_payment_receipt_account varchar(48) not null,
+  buyer_receipt_token_account varchar(48) not null,
+  authority varchar(48) not null,
+  auction_house varchar(48) not null,
+  auction_house_fee_account varchar(48) not null,
+  auction_house_treasury varchar(48) not null,
+  buyer_trade_state varchar(48) not null,
+  seller_trade_state varchar(48) not null,
+  free_trade_state varchar(48) not null,
+  program_as_signer varchar(48) not null,
+  escrow_payment_bump smallint not null,
+  free_trade_state_bump smallint not null,
+  program_as_signer_bump smallint not null,
+  buyer_price bigint not null,
+  token_size bigint not null,
+  slot bigint not null,
+  tx_signature varchar(88) not null,
+  instruction_index integer not null,
+  created_at timestamp not null default now(),
+  unique (tx_signature, instruction_index)
+);
+
+create index if not exists buy_instructions_metadata_idx on
+  buy_instructions using hash (metadata);
+
+create index if not exists sell_instructions_metadata_idx on
+  sell_instructions using hash (metadata);
+
+create index if not exists cancel_instructions_token_mint_idx on
+  cancel_instructions using hash (token_mint);
+
+create index if not exists execute_sale_instructions_metadata_idx on
+  execute_sale_instructions using hash (metadata);
@@ -0,0 +1,103 @@
+do $EOF$
+declare
+  unversioned text[] := array[
+    'auction_caches',
+    'auction_datas',
+    'auction_datas_ext',
+    'auction_houses',
+    'bid_receipts',
+    'bids',
+    'candy_machine_collection_pdas',
+    'candy_machine_config_lines',
+    'candy_machine_creators',
+    'candy_machine_datas',
+    'candy_machine_end_settings',
+    'candy_machine_gate_keeper_configs',
+    'candy_machine_hidden_settings',
+    'candy_machine_whitelist_mint_settings',
+    'candy_machines',
+    'cardinal_paid_claim_approvers',
+    'cardinal_time_invalidators',
+    'cardinal_token_manager_invalidators',
+    'cardinal_token_managers',
+    'cardinal_use_invalidators',
+    'escrows',
+    'governance_parameters',
+    'governors',
+    'graph_connections',
+    'ins_buffer_bundle_ins_keys',
+    'ins_buffer_bundle_instructions',
+    'ins_buffer_bundles',
+    'instruction_buffers',
+    'listing_metadatas',
+    'listing_receipts',
+    'locker_params',
+    'locker_whitelist_entries',
+    'lockers',
+    'metadata_collection_keys',
+    'metadata_creators',
+    'proposal_account_metas',
+    'proposal_instructions',
+    'proposal_metas',
+    'proposals',
+    'purchase_receipts',
+    'smart_wallet_owners',
+    'smart_wallets',
+    'store_configs',
+    'stores',
+    'sub_account_infos',
+    'transactions',
+    'tx_instruction_keys',
+    'tx_instructions',
+    'votes',
+    'whitelisted_creators'
+  ];
+
+  versioned text[] := array[
+    'current_metadata_owners',
+    'editions',
+    'master_editions',
+    'metadatas',
+    'twitter_handle_name_services'
+  ];
+
+  legacy text[] := array[
+    'attributes',
+    'files',
+    'metadata_collections',
+    'metadata_jsons'
+  ];
+
+  t text;
+begin
+  foreach t in array unversioned || versioned || legacy loop
+    execute format('drop trigger %I on %I', t || '_check_slot', t);
+  end loop;
+
+  foreach t in array legacy loop
+    execute format('create trigger %I
+      before update on %I for row
+      execute function check_slot_wv()', t || '_check_slot_wv', t);
+  end loop;
+
+  foreach t in array unversioned loop
+    execute format('alter table %I
+      drop column slot,
+      drop column write_version', t);
+  end loop;
+end
+$EOF$;
+
+alter table current_metadata_owners
+drop column write_version;
+
+alter table editions
+drop column write_version;
+
+alter table master_editions
+drop column write_version;
+
+alter table metadatas
+drop column write_version;
+
+drop function check_slot;
@@ -0,0 +1,127 @@
+-- Account rows are ordered by slot alone.  Write versions are assigned
+-- independently by each validator, so they cannot order updates published by
+-- redundant Geyser plugins, and are stored for reference only.  Updates from
+-- the same slot are applied in the order they arrive.
+create function check_slot() returns trigger
+  language plpgsql
+  as $EOF$
+begin
+  if old.slot > new.slot then
+    return old;
+  end if;
+
+  return new;
+end
+$EOF$;
+
+alter table metadatas
+add column write_version bigint null;
+
+alter table master_editions
+add column write_version bigint null;
+
+alter table editions
+add column write_version bigint null;
+
+alter table current_metadata_owners
+add column write_version bigint not null default 0;
+
+alter table current_metadata_owners
+alter column write_version drop default;
+
+do $EOF$
+declare
+  -- Tables which do not yet record the update they were written from
+  unversioned text[] := array[
+    'auction_caches',
+    'auction_datas',
+    'auction_datas_ext',
+    'auction_houses',
+    'bid_receipts',
+    'bids',
+    'candy_machine_collection_pdas',
+    'candy_machine_config_lines',
+    'candy_machine_creators',
+    'candy_machine_datas',
+    'candy_machine_end_settings',
+    'candy_machine_gate_keeper_configs',
+    'candy_machine_hidden_settings',
+    'candy_machine_whitelist_mint_settings',
+    'candy_machines',
+    'cardinal_paid_claim_approvers',
+    'cardinal_time_invalidators',
+    'cardinal_token_manager_invalidators',
+    'cardinal_token_managers',
+    'cardinal_use_invalidators',
+    'escrows',
+    'governance_parameters',
+    'governors',
+    'graph_connections',
+    'ins_buffer_bundle_ins_keys',
+    'ins_buffer_bundle_instructions',
+    'ins_buffer_bundles',
+    'instruction_buffers',
+    'listing_metadatas',
+    'listing_receipts',
+    'locker_params',
+    'locker_whitelist_entries',
+    'lockers',
+    'metadata_collection_keys',
+    'metadata_creators',
+    'proposal_account_metas',
+    'proposal_instructions',
+    'proposal_metas',
+    'proposals',
+    'purchase_receipts',
+    'smart_wallet_owners',
+    'smart_wallets',
+    'store_configs',
+    'stores',
+    'sub_account_infos',
+    'transactions',
+    'tx_instruction_keys',
+    'tx_instructions',
+    'votes',
+    'whitelisted_creators'
+  ];
+
+  -- Tables which already have a slot column
+  versioned text[] := array[
+    'current_metadata_owners',
+    'editions',
+    'master_editions',
+    'metadatas',
+    'twitter_handle_name_services'
+  ];
+
+  -- Tables guarded by check_slot_wv, which are moved to the same rule
+  legacy text[] := array[
+    'attributes',
+    'files',
+    'metadata_collections',
+    'metadata_jsons'
+  ];
+
+  t text;
+begin
+  foreach t in array unversioned loop
+    execute format('alter table %I
+      add column slot          bigint not null default 0,
+      add column write_version bigint not null default 0', t);
+
+    execute format('alter table %I
+      alter column slot          drop default,
+      alter column write_version drop default', t);
+  end loop;
+
+  foreach t in array legacy loop
+    execute format('drop trigger %I on %I', t || '_check_slot_wv', t);
+  end loop;
+
+  foreach t in array unversioned || versioned || legacy loop
+    execute format('create trigger %I
+      before update on %I for row
+      execute function check_slot()', t || '_check_slot', t);
+  end loop;
+end
+$EOF$;
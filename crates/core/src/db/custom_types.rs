@@ -185,3 +185,45 @@ impl FromSql<ListingEventLifecycle, Pg> for ListingEventLifecycleEnum {
         from_bytes(bytes)
     }
 }
+
+/// A slot commitment status
+#[derive(SqlType, Debug, Clone, Copy)]
+#[postgres(type_name = "slot_status")]
+/// Represents database `slot_status` type
+pub struct SlotStatus;
+
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    FromSqlRow,
+    AsExpression,
+    Clone,
+    Copy,
+    strum::EnumString,
+    strum::Display,
+)]
+#[sql_type = "SlotStatus"]
+/// `SlotStatus` enum in `Slot` struct
+pub enum SlotStatusEnum {
+    /// The slot was processed but may still be abandoned
+    Processed,
+    /// The slot was confirmed by a supermajority of the cluster
+    Confirmed,
+    /// The slot was rooted and is final
+    Rooted,
+}
+
+impl ToSql<SlotStatus, Pg> for SlotStatusEnum {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        to_bytes(self, out, |_| false)
+    }
+}
+
+impl FromSql<SlotStatus, Pg> for SlotStatusEnum {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        from_bytes(bytes)
+    }
+}
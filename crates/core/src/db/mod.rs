@@ -80,6 +80,50 @@ pub struct ConnectArgs {
         required_unless_present_any(["database-read-url", "database-write-url"])
     )]
     database_url: Option<String>,
+
+    /// Maximum number of connections in the database pool.  Defaults to the
+    /// number of available cores.
+    #[clap(long, env)]
+    database_pool_size: Option<u32>,
+}
+
+impl ConnectArgs {
+    /// Select the connection string to use for the given mode, returning it
+    /// with a hint indicating if the database is writable
+    ///
+    /// # Errors
+    /// This function fails if no connection string suitable for the given
+    /// mode was provided.
+    pub fn url(&self, mode: ConnectMode) -> Result<(ConnectionType, &str)> {
+        let mode_url = match mode {
+            ConnectMode::Read => self.database_read_url.as_deref(),
+            ConnectMode::Write => self.database_write_url.as_deref(),
+        };
+
+        mode_url
+            .map(|u| (mode.into(), u))
+            .or_else(|| {
+                self.database_url
+                    .as_deref()
+                    .map(|u| (ConnectionType::Default, u))
+            })
+            .ok_or_else(|| {
+                anyhow!(
+                    "Invalid database URL, expected a {} connection string",
+                    match mode {
+                        ConnectMode::Read => "read-only",
+                        ConnectMode::Write => "writable",
+                    }
+                )
+            })
+    }
+
+    /// Get the maximum number of connections to open to the database
+    #[must_use]
+    pub fn pool_size(&self) -> u32 {
+        self.database_pool_size
+            .unwrap_or_else(|| num_cpus::get().try_into().unwrap_or(u32::MAX))
+    }
 }
 
 impl From<ConnectMode> for ConnectionType {
@@ -97,36 +141,14 @@ impl From<ConnectMode> for ConnectionType {
 /// # Errors
 /// This function fails if Diesel fails to construct a connection pool or if any
 /// pending database migrations fail to run.
-pub fn connect(args: ConnectArgs, mode: ConnectMode) -> Result<(Pool, ConnectionType)> {
-    let ConnectArgs {
-        database_read_url,
-        database_write_url,
-        database_url,
-    } = args;
-
-    let mode_url = match mode {
-        ConnectMode::Read => database_read_url,
-        ConnectMode::Write => database_write_url,
-    };
-
-    let (ty, url) = mode_url
-        .map(|u| (mode.into(), u))
-        .or_else(|| database_url.map(|u| (ConnectionType::Default, u)))
-        .ok_or_else(|| {
-            anyhow!(
-                "Invalid database URL, expected a {} connection string",
-                match mode {
-                    ConnectMode::Read => "read-only",
-                    ConnectMode::Write => "writable",
-                }
-            )
-        })?;
+pub fn connect(args: &ConnectArgs, mode: ConnectMode) -> Result<(Pool, ConnectionType)> {
+    let (ty, url) = args.url(mode)?;
 
     debug!("Connecting to db: {:?}", url);
 
     let man = ConnectionManager::new(url);
     let pool = Pool::builder()
-        .max_size(num_cpus::get().try_into().unwrap_or(u32::MAX))
+        .max_size(args.pool_size())
         .min_idle(Some(1))
         .idle_timeout(Some(std::time::Duration::from_secs(60)))
         .build(man)
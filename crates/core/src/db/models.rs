@@ -11,8 +11,8 @@ use uuid::Uuid;
 #[allow(clippy::wildcard_imports)]
 use super::schema::*;
 use crate::db::custom_types::{
-    EndSettingType, ListingEventLifecycleEnum, OfferEventLifecycleEnum, TokenStandardEnum,
-    WhitelistMintMode,
+    EndSettingType, ListingEventLifecycleEnum, OfferEventLifecycleEnum, SlotStatusEnum,
+    TokenStandardEnum, WhitelistMintMode,
 };
 
 /// A row in the `bids` table
@@ -30,6 +30,10 @@ pub struct Bid<'a> {
     pub last_bid_amount: i64,
     /// Whether the bid has been cancelled or redeemed
     pub cancelled: bool,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `editions` table
@@ -45,6 +49,8 @@ pub struct Edition<'a> {
     pub edition: i64,
     /// Solana slot number
     pub slot: Option<i64>,
+    /// The write version of the account update this row was written from
+    pub write_version: Option<i64>,
 }
 
 /// A row in the `listing_metadatas` table.  This is a join on `listings` and
@@ -60,6 +66,10 @@ pub struct ListingMetadata<'a> {
     pub metadata_address: Cow<'a, str>,
     /// The index of the metadata in the array of items for the listing
     pub metadata_index: i32,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `auction_caches` table
@@ -80,6 +90,10 @@ pub struct AuctionCache<'a> {
     pub vault: Cow<'a, str>,
     /// The manager of the cached auction
     pub auction_manager: Cow<'a, str>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `auction_datas` table
@@ -104,6 +118,10 @@ pub struct AuctionData<'a> {
     pub total_uncancelled_bids: Option<i32>,
     /// The timestamp of the last bid, if applicable and the auction has bids
     pub last_bid_time: Option<NaiveDateTime>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `auction_datas_ext` table
@@ -120,6 +138,10 @@ pub struct AuctionDataExt<'a> {
     pub instant_sale_price: Option<i64>,
     /// The name of the listing
     pub name: Cow<'a, str>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `master_editions` table
@@ -135,6 +157,8 @@ pub struct MasterEdition<'a> {
     pub max_supply: Option<i64>,
     /// Solana slot number
     pub slot: Option<i64>,
+    /// The write version of the account update this row was written from
+    pub write_version: Option<i64>,
 }
 
 /// A row in the `metadata_creators` table.  This is a join on `metadatas` and
@@ -153,6 +177,10 @@ pub struct MetadataCreator<'a> {
     pub verified: bool,
     /// position of creator in metadata creator array
     pub position: Option<i32>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `metadatas` table
@@ -186,6 +214,8 @@ pub struct Metadata<'a> {
     pub token_standard: Option<TokenStandardEnum>,
     /// Solana slot number
     pub slot: Option<i64>,
+    /// The write version of the account update this row was written from
+    pub write_version: Option<i64>,
 }
 
 /// A row in the `storefronts` table
@@ -524,6 +554,10 @@ pub struct StoreConfig<'a> {
     pub address: Cow<'a, str>,
     /// Store settings URI
     pub settings_uri: Option<Cow<'a, str>>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `whitelisted_creators` table
@@ -536,6 +570,10 @@ pub struct WhitelistedCreator<'a> {
     pub creator_address: Cow<'a, str>,
     /// Whether or not the specified creator is actually whitelisted
     pub activated: bool,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `stores` table
@@ -551,6 +589,10 @@ pub struct Store<'a> {
     pub public: bool,
     /// The derived address of this store's StoreConfig account
     pub config_address: Cow<'a, str>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `settings_uri_jsons` table
@@ -618,6 +660,10 @@ pub struct AuctionHouse<'a> {
 
     /// Auction House fee account address
     pub auction_house_fee_account: Cow<'a, str>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `bid_reciepts` table
@@ -652,6 +698,10 @@ pub struct BidReceipt<'a> {
     pub created_at: NaiveDateTime,
     /// Canceled_at timestamp
     pub canceled_at: Option<NaiveDateTime>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `listing_receipts` table
@@ -685,6 +735,10 @@ pub struct ListingReceipt<'a> {
     pub created_at: NaiveDateTime,
     /// Canceled_at timestamp
     pub canceled_at: Option<NaiveDateTime>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `purchase_receipts` table
@@ -711,6 +765,10 @@ pub struct PurchaseReceipt<'a> {
     pub bump: i16,
     /// Created at
     pub created_at: NaiveDateTime,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `store_creators` table
@@ -738,6 +796,10 @@ pub struct GraphConnection<'a> {
     pub connected_at: NaiveDateTime,
     /// Graph Connection 'disconnected_at'
     pub disconnected_at: Option<NaiveDateTime>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `candy_machines` table
@@ -754,6 +816,10 @@ pub struct CandyMachine<'a> {
     pub token_mint: Option<Cow<'a, str>>,
     /// Items redeemed
     pub items_redeemed: i64,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `candy_machine_datas` table
@@ -782,6 +848,10 @@ pub struct CandyMachineData<'a> {
     pub go_live_date: Option<i64>,
     /// Number of items available
     pub items_available: i64,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `candy_machine_config_lines` table
@@ -795,6 +865,10 @@ pub struct CMConfigLine<'a> {
     pub name: Cow<'a, str>,
     /// URI pointing to JSON representing the asset
     pub uri: Cow<'a, str>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `candy_machine_creators` table
@@ -810,6 +884,10 @@ pub struct CMCreator<'a> {
     pub verified: bool,
     /// In percentages, NOT basis points
     pub share: i16,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `candy_machine_collection_pdas` table
@@ -823,6 +901,10 @@ pub struct CMCollectionPDA<'a> {
     pub mint: Cow<'a, str>,
     /// CandyMachine account address
     pub candy_machine: Cow<'a, str>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `candy_machine_hidden_settings` table
@@ -841,6 +923,10 @@ pub struct CMHiddenSetting<'a> {
     /// in most cases this is the hash of the cache file with the mapping between
     /// mint number and metadata so that the order can be verified when the mint is complete
     pub hash: Vec<u8>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `candy_machine_whitelist_mint_settings` table
@@ -860,6 +946,10 @@ pub struct CMWhitelistMintSetting<'a> {
     pub presale: bool,
     /// Price for whitelist token holders
     pub discount_price: Option<i64>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `candy_machine_gate_keeper_configs` table
@@ -873,6 +963,10 @@ pub struct CMGateKeeperConfig<'a> {
     pub gatekeeper_network: Cow<'a, str>,
     /// Requires a new gateway challenge after a use
     pub expire_on_use: bool,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `candy_machine_end_settings` table
@@ -892,6 +986,10 @@ pub struct CMEndSetting<'a> {
     /// This will be either a date (if date is set to true)
     /// or a integer amount value (if amount is set to true)
     pub number: i64,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in a `mint_stats` query, representing stats for a single token type
@@ -1001,6 +1099,10 @@ pub struct MetadataCollectionKey<'a> {
     pub collection_address: Cow<'a, str>,
     /// Whether the collection is verified or not.
     pub verified: bool,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// Joint table from querying a `token_manager` and related plugins
@@ -1165,6 +1267,10 @@ pub struct CardinalTokenManager<'a> {
     pub claim_approver: Option<Cow<'a, str>>,
     /// Optional authority that can approve transfers (defaults to self)
     pub transfer_authority: Option<Cow<'a, str>>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `cardinal_token_manager_invalidators` table
@@ -1176,6 +1282,10 @@ pub struct CardinalTokenManagerInvalidator<'a> {
     pub token_manager_address: Cow<'a, str>,
     /// Address of an active invalidator for this token_manager
     pub invalidator: Cow<'a, str>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `cardinal_time_invalidators` table
@@ -1207,6 +1317,10 @@ pub struct CardinalTimeInvalidator<'a> {
     pub time_invalidator_max_expiration: Option<NaiveDateTime>,
     /// Whether extension can be in partial increments
     pub time_invalidator_disable_partial_extension: Option<bool>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `cardinal_use_invalidators` table
@@ -1238,6 +1352,10 @@ pub struct CardinalUseInvalidator<'a> {
     pub use_invalidator_extension_usages: Option<i64>,
     /// Optional max this can ever be extended until
     pub use_invalidator_max_usages: Option<i64>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `cardinal_token_manager_invalidators` table
@@ -1259,6 +1377,10 @@ pub struct CardinalPaidClaimApprover<'a> {
     pub paid_claim_approver_payment_amount: i64,
     /// Mint that extension is denominated in
     pub paid_claim_approver_payment_mint: Cow<'a, str>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `cardinal_claim_events` table
@@ -1367,6 +1489,10 @@ pub struct Locker<'a> {
     pub locked_supply: i64,
     /// Governor associated with the [Locker].
     pub governor: Cow<'a, str>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `locker_params` table
@@ -1385,6 +1511,10 @@ pub struct LockerParam<'a> {
     pub max_stake_duration: i64,
     /// Minimum number of votes required to activate a proposal.
     pub proposal_activation_min_votes: i64,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// `Tribeca` Locked-Voter program account
@@ -1403,6 +1533,10 @@ pub struct LockerWhitelistEntry<'a> {
     pub program_id: Cow<'a, str>,
     /// The account authorized to be the [Escrow::owner] with this CPI.
     pub owner: Cow<'a, str>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// `Tribeca` Locked-Voter program account
@@ -1430,6 +1564,10 @@ pub struct Escrow<'a> {
     /// Account that is authorized to vote on behalf of this [Escrow].
     /// Defaults to the [Escrow::owner].
     pub vote_delegate: Cow<'a, str>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// `Tribeca` Govern program account
@@ -1454,6 +1592,10 @@ pub struct Governor<'a> {
     /// The public key of the `smart_wallet::SmartWallet` account.
     /// This smart wallet executes proposals.
     pub smart_wallet: Cow<'a, str>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `governor_parameters` table
@@ -1470,6 +1612,10 @@ pub struct GovernanceParameter<'a> {
     pub quorum_votes: i64,
     /// The timelock delay of the DAO's created proposals.
     pub timelock_delay_seconds: i64,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// `Tribeca` Govern program account
@@ -1510,6 +1656,10 @@ pub struct Proposal<'a> {
     pub queued_at: i64,
     /// If the transaction was queued, this is the associated Goki Smart Wallet transaction.
     pub queued_transaction: Cow<'a, str>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `proposal_instructions` table
@@ -1522,6 +1672,10 @@ pub struct ProposalInstruction<'a> {
     pub program_id: Cow<'a, str>,
     /// Opaque data passed to the instruction processor
     pub data: Vec<u8>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `proposal_account_metas` table
@@ -1539,6 +1693,10 @@ pub struct ProposalAccountMeta<'a> {
     pub is_signer: bool,
     /// True if the `pubkey` can be loaded as a read-write account.
     pub is_writable: bool,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// `Tribeca` Govern program account
@@ -1555,6 +1713,10 @@ pub struct ProposalMeta<'a> {
     pub title: Cow<'a, str>,
     /// Link to a description of the proposal.
     pub description_link: Cow<'a, str>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// `Tribeca` Govern program account
@@ -1574,6 +1736,10 @@ pub struct Vote<'a> {
     pub side: i16,
     /// The number of votes this vote holds.
     pub weight: i64,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `smart_wallets` table
@@ -1596,6 +1762,10 @@ pub struct SmartWallet<'a> {
     pub owner_set_seqno: i64,
     /// Total number of [Transaction]s on this [SmartWallet].
     pub num_transactions: i64,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `smart_wallet_owners` table
@@ -1608,6 +1778,10 @@ pub struct SmartWalletOwner<'a> {
     pub owner_address: Cow<'a, str>,
     /// Position of owner in vec<Owners Pubkey>
     pub index: i64,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `transactions` table
@@ -1636,6 +1810,10 @@ pub struct Transaction<'a> {
     pub executor: Cow<'a, str>,
     /// When the transaction was executed. -1 if not executed.
     pub executed_at: i64,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `tx_instructions` table
@@ -1649,6 +1827,10 @@ pub struct TXInstruction<'a> {
     pub program_id: Cow<'a, str>,
     /// Opaque data passed to the instruction processor
     pub data: Vec<u8>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `tx_instruction_keys` table
@@ -1666,6 +1848,10 @@ pub struct TXInstructionKey<'a> {
     pub is_signer: bool,
     /// True if the `pubkey` can be loaded as a read-write account.
     pub is_writable: bool,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `subaccount_infos` table
@@ -1682,6 +1868,10 @@ pub struct SubAccountInfo<'a> {
     pub subaccount_type: i16,
     /// Index of the sub-account.
     pub index: i64,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `instruction_buffers` table
@@ -1701,6 +1891,10 @@ pub struct InstructionBuffer<'a> {
     pub executor: Cow<'a, str>,
     /// Smart wallet the buffer belongs to.
     pub smart_wallet: Cow<'a, str>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `ins_buffer_bundles` table
@@ -1712,6 +1906,10 @@ pub struct InsBufferBundle<'a> {
     pub instruction_buffer_address: Cow<'a, str>,
     /// Execution counter on the `InstructionBundle`.
     pub is_executed: bool,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `ins_buffer_bundle_instructions` table
@@ -1725,6 +1923,10 @@ pub struct InsBuffferBundleInstruction<'a> {
     pub program_id: Cow<'a, str>,
     /// Opaque data passed to the instruction processor
     pub data: Vec<u8>,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in the `ins_buffer_bundle_ins_keys` table
@@ -1741,6 +1943,10 @@ pub struct InsBufferBundleInsKey<'a> {
     pub is_signer: bool,
     /// True if the `pubkey` can be loaded as a read-write account.
     pub is_writable: bool,
+    /// The slot of the account update this row was written from
+    pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 /// A row in the `bonding_change` table
 #[derive(Debug, Clone, Queryable, Insertable, AsChangeset)]
@@ -1793,6 +1999,8 @@ pub struct CurrentMetadataOwner<'a> {
     /// Solana slot number
     /// The period of time for which each leader ingests transactions and produces a block.
     pub slot: i64,
+    /// The write version of the account update this row was written from
+    pub write_version: i64,
 }
 
 /// A row in a `metadatas::count_by_store_creator` query, representing stats for
@@ -1891,3 +2099,187 @@ pub struct WalletTotal {
     /// wallet following
     pub following: i64,
 }
+
+/// A row in the `slots` table
+#[derive(Debug, Clone, Copy, Queryable, Insertable, AsChangeset)]
+#[diesel(treat_none_as_null = true)]
+pub struct Slot {
+    /// The slot number
+    pub slot: i64,
+    /// The parent of this slot, if known
+    pub parent_slot: Option<i64>,
+    /// The highest commitment status observed for this slot
+    pub status: SlotStatusEnum,
+}
+
+/// A row in the `buy_instructions` table
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "buy_instructions"]
+pub struct BuyInstruction<'a> {
+    /// The wallet placing the bid
+    pub wallet: Cow<'a, str>,
+    /// The account paying for the bid
+    pub payment_account: Cow<'a, str>,
+    /// The authority transferring the payment
+    pub transfer_authority: Cow<'a, str>,
+    /// The mint of the currency used by the auction house
+    pub treasury_mint: Cow<'a, str>,
+    /// The token account being bid on
+    pub token_account: Cow<'a, str>,
+    /// The metadata of the token being bid on
+    pub metadata: Cow<'a, str>,
+    /// The escrow account holding the bid funds
+    pub escrow_payment_account: Cow<'a, str>,
+    /// The auction house authority
+    pub authority: Cow<'a, str>,
+    /// The auction house the bid was placed on
+    pub auction_house: Cow<'a, str>,
+    /// The auction house fee account
+    pub auction_house_fee_account: Cow<'a, str>,
+    /// The trade state account for the bid
+    pub buyer_trade_state: Cow<'a, str>,
+    /// The bump seed of the trade state
+    pub trade_state_bump: i16,
+    /// The bump seed of the escrow payment account
+    pub escrow_payment_bump: i16,
+    /// The bid price
+    pub buyer_price: i64,
+    /// The number of tokens bid on
+    pub token_size: i64,
+    /// The slot of the transaction
+    pub slot: i64,
+    /// The signature of the transaction
+    pub tx_signature: Cow<'a, str>,
+    /// The position of this instruction within the transaction's
+    /// instructions, including inner instructions
+    pub instruction_index: i32,
+}
+
+/// A row in the `sell_instructions` table
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "sell_instructions"]
+pub struct SellInstruction<'a> {
+    /// The wallet creating the listing
+    pub wallet: Cow<'a, str>,
+    /// The token account being listed
+    pub token_account: Cow<'a, str>,
+    /// The metadata of the token being listed
+    pub metadata: Cow<'a, str>,
+    /// The auction house authority
+    pub authority: Cow<'a, str>,
+    /// The auction house the listing was created on
+    pub auction_house: Cow<'a, str>,
+    /// The auction house fee account
+    pub auction_house_fee_account: Cow<'a, str>,
+    /// The trade state account for the listing
+    pub seller_trade_state: Cow<'a, str>,
+    /// The zero-price trade state account for the listing
+    pub free_seller_trade_state: Cow<'a, str>,
+    /// The auction house program signer PDA
+    pub program_as_signer: Cow<'a, str>,
+    /// The bump seed of the trade state
+    pub trade_state_bump: i16,
+    /// The bump seed of the zero-price trade state
+    pub free_trade_state_bump: i16,
+    /// The bump seed of the program signer
+    pub program_as_signer_bump: i16,
+    /// The listing price
+    pub buyer_price: i64,
+    /// The number of tokens listed
+    pub token_size: i64,
+    /// The slot of the transaction
+    pub slot: i64,
+    /// The signature of the transaction
+    pub tx_signature: Cow<'a, str>,
+    /// The position of this instruction within the transaction's
+    /// instructions, including inner instructions
+    pub instruction_index: i32,
+}
+
+/// A row in the `cancel_instructions` table
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "cancel_instructions"]
+pub struct CancelInstruction<'a> {
+    /// The wallet canceling the bid or listing
+    pub wallet: Cow<'a, str>,
+    /// The token account of the bid or listing
+    pub token_account: Cow<'a, str>,
+    /// The mint of the token
+    pub token_mint: Cow<'a, str>,
+    /// The auction house authority
+    pub authority: Cow<'a, str>,
+    /// The auction house of the bid or listing
+    pub auction_house: Cow<'a, str>,
+    /// The auction house fee account
+    pub auction_house_fee_account: Cow<'a, str>,
+    /// The trade state account being canceled
+    pub trade_state: Cow<'a, str>,
+    /// The price of the canceled bid or listing
+    pub buyer_price: i64,
+    /// The number of tokens in the canceled bid or listing
+    pub token_size: i64,
+    /// The slot of the transaction
+    pub slot: i64,
+    /// The signature of the transaction
+    pub tx_signature: Cow<'a, str>,
+    /// The position of this instruction within the transaction's
+    /// instructions, including inner instructions
+    pub instruction_index: i32,
+}
+
+/// A row in the `execute_sale_instructions` table
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "execute_sale_instructions"]
+pub struct ExecuteSaleInstruction<'a> {
+    /// The buyer's wallet
+    pub buyer: Cow<'a, str>,
+    /// The seller's wallet
+    pub seller: Cow<'a, str>,
+    /// The token account being sold
+    pub token_account: Cow<'a, str>,
+    /// The mint of the token being sold
+    pub token_mint: Cow<'a, str>,
+    /// The metadata of the token being sold
+    pub metadata: Cow<'a, str>,
+    /// The mint of the currency used by the auction house
+    pub treasury_mint: Cow<'a, str>,
+    /// The escrow account holding the buyer's funds
+    pub escrow_payment_account: Cow<'a, str>,
+    /// The account receiving the seller's payment
+    pub seller_payment_receipt_account: Cow<'a, str>,
+    /// The token account receiving the purchased token
+    pub buyer_receipt_token_account: Cow<'a, str>,
+    /// The auction house authority
+    pub authority: Cow<'a, str>,
+    /// The auction house the sale was executed on
+    pub auction_house: Cow<'a, str>,
+    /// The auction house fee account
+    pub auction_house_fee_account: Cow<'a, str>,
+    /// The auction house treasury account
+    pub auction_house_treasury: Cow<'a, str>,
+    /// The buyer's trade state account
+    pub buyer_trade_state: Cow<'a, str>,
+    /// The seller's trade state account
+    pub seller_trade_state: Cow<'a, str>,
+    /// The zero-price trade state account
+    pub free_trade_state: Cow<'a, str>,
+    /// The auction house program signer PDA
+    pub program_as_signer: Cow<'a, str>,
+    /// The bump seed of the escrow payment account
+    pub escrow_payment_bump: i16,
+    /// The bump seed of the zero-price trade state
+    pub free_trade_state_bump: i16,
+    /// The bump seed of the program signer
+    pub program_as_signer_bump: i16,
+    /// The sale price
+    pub buyer_price: i64,
+    /// The number of tokens sold
+    pub token_size: i64,
+    /// The slot of the transaction
+    pub slot: i64,
+    /// The signature of the transaction
+    pub tx_signature: Cow<'a, str>,
+    /// The position of this instruction within the transaction's
+    /// instructions, including inner instructions
+    pub instruction_index: i32,
+}
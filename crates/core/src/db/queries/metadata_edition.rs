@@ -44,10 +44,12 @@ pub fn load<'a>(
         Option<String>,
         Option<i64>,
         Option<i64>,
+        Option<i64>,
         Option<String>,
         Option<i64>,
         Option<i64>,
         Option<i64>,
+        Option<i64>,
     );
 
     let metas = metadatas::table
@@ -60,10 +62,12 @@ pub fn load<'a>(
             editions::parent_address.nullable(),
             editions::edition.nullable(),
             editions::slot.nullable(),
+            editions::write_version.nullable(),
             master_editions::address.nullable(),
             master_editions::supply.nullable(),
             master_editions::max_supply.nullable(),
             master_editions::slot.nullable(),
+            master_editions::write_version.nullable(),
         ))
         .load::<Cols>(conn)
         .context("Failed to load editions")?;
@@ -73,10 +77,12 @@ pub fn load<'a>(
         edition_parent,
         edition_ord,
         edition_slot,
+        edition_write_version,
         master_addr,
         master_supply,
         master_max,
         master_edition_slot,
+        master_edition_write_version,
     ) = if metas.len() == 1 {
         metas.into_iter().next().unwrap_or_else(|| unreachable!())
     } else {
@@ -105,6 +111,7 @@ pub fn load<'a>(
                     parent_address: Cow::Owned(parent_address),
                     edition: edition_ord.unwrap_or_else(|| unreachable!()),
                     slot: edition_slot,
+                    write_version: edition_write_version,
                 },
                 parent,
             })
@@ -116,6 +123,7 @@ pub fn load<'a>(
                     supply: master_supply.unwrap_or_else(|| unreachable!()),
                     max_supply: master_max,
                     slot: master_edition_slot,
+                    write_version: master_edition_write_version,
                 }))
             })
         })
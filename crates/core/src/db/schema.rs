@@ -1,7 +1,7 @@
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     attributes (id) {
         metadata_address -> Varchar,
@@ -17,7 +17,7 @@ table! {
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     auction_caches (address) {
         address -> Varchar,
@@ -27,13 +27,15 @@ table! {
         auction_ext -> Varchar,
         vault -> Varchar,
         auction_manager -> Varchar,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     auction_datas (address) {
         address -> Varchar,
@@ -46,26 +48,30 @@ table! {
         price_floor -> Nullable<Int8>,
         total_uncancelled_bids -> Nullable<Int4>,
         last_bid_time -> Nullable<Timestamp>,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     auction_datas_ext (address) {
         address -> Varchar,
         gap_tick_size -> Nullable<Int4>,
         instant_sale_price -> Nullable<Int8>,
         name -> Text,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     auction_houses (address) {
         address -> Varchar,
@@ -82,13 +88,15 @@ table! {
         requires_sign_off -> Bool,
         can_change_sale_price -> Bool,
         auction_house_fee_account -> Varchar,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     bid_receipts (address) {
         address -> Varchar,
@@ -105,13 +113,15 @@ table! {
         trade_state_bump -> Int2,
         created_at -> Timestamp,
         canceled_at -> Nullable<Timestamp>,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     bids (listing_address, bidder_address) {
         listing_address -> Varchar,
@@ -119,13 +129,15 @@ table! {
         last_bid_time -> Timestamp,
         last_bid_amount -> Int8,
         cancelled -> Bool,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     bonding_changes (address, slot) {
         address -> Varchar,
@@ -139,44 +151,102 @@ table! {
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
+
+    buy_instructions (id) {
+        id -> Uuid,
+        wallet -> Varchar,
+        payment_account -> Varchar,
+        transfer_authority -> Varchar,
+        treasury_mint -> Varchar,
+        token_account -> Varchar,
+        metadata -> Varchar,
+        escrow_payment_account -> Varchar,
+        authority -> Varchar,
+        auction_house -> Varchar,
+        auction_house_fee_account -> Varchar,
+        buyer_trade_state -> Varchar,
+        trade_state_bump -> Int2,
+        escrow_payment_bump -> Int2,
+        buyer_price -> Int8,
+        token_size -> Int8,
+        slot -> Int8,
+        tx_signature -> Varchar,
+        instruction_index -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
+
+    cancel_instructions (id) {
+        id -> Uuid,
+        wallet -> Varchar,
+        token_account -> Varchar,
+        token_mint -> Varchar,
+        authority -> Varchar,
+        auction_house -> Varchar,
+        auction_house_fee_account -> Varchar,
+        trade_state -> Varchar,
+        buyer_price -> Int8,
+        token_size -> Int8,
+        slot -> Int8,
+        tx_signature -> Varchar,
+        instruction_index -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     candy_machine_collection_pdas (address) {
         address -> Varchar,
         mint -> Varchar,
         candy_machine -> Varchar,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     candy_machine_config_lines (address) {
         address -> Varchar,
         name -> Text,
         uri -> Text,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     candy_machine_creators (candy_machine_address) {
         candy_machine_address -> Varchar,
         creator_address -> Varchar,
         verified -> Bool,
         share -> Int2,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     candy_machine_datas (candy_machine_address) {
         candy_machine_address -> Varchar,
@@ -189,50 +259,58 @@ table! {
         retain_authority -> Bool,
         go_live_date -> Nullable<Int8>,
         items_available -> Int8,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     candy_machine_end_settings (candy_machine_address) {
         candy_machine_address -> Varchar,
         end_setting_type -> Settingtype,
         number -> Int8,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     candy_machine_gate_keeper_configs (candy_machine_address) {
         candy_machine_address -> Varchar,
         gatekeeper_network -> Varchar,
         expire_on_use -> Bool,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     candy_machine_hidden_settings (candy_machine_address) {
         candy_machine_address -> Varchar,
         name -> Text,
         uri -> Text,
         hash -> Bytea,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     candy_machine_whitelist_mint_settings (candy_machine_address) {
         candy_machine_address -> Varchar,
@@ -240,13 +318,15 @@ table! {
         mint -> Varchar,
         presale -> Bool,
         discount_price -> Nullable<Int8>,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     candy_machines (address) {
         address -> Varchar,
@@ -254,13 +334,15 @@ table! {
         wallet -> Varchar,
         token_mint -> Nullable<Varchar>,
         items_redeemed -> Int8,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     cardinal_claim_events (token_manager_address, state_changed_at) {
         token_manager_address -> Varchar,
@@ -310,7 +392,7 @@ table! {
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     cardinal_paid_claim_approvers (paid_claim_approver_address) {
         paid_claim_approver_address -> Varchar,
@@ -320,13 +402,15 @@ table! {
         paid_claim_approver_payment_amount -> Int8,
         paid_claim_approver_payment_mint -> Varchar,
         paid_claim_approver_collector -> Varchar,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     cardinal_time_invalidators (time_invalidator_address) {
         time_invalidator_address -> Varchar,
@@ -341,24 +425,28 @@ table! {
         time_invalidator_extension_payment_mint -> Nullable<Varchar>,
         time_invalidator_max_expiration -> Nullable<Timestamp>,
         time_invalidator_disable_partial_extension -> Nullable<Bool>,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     cardinal_token_manager_invalidators (token_manager_address, invalidator) {
         token_manager_address -> Varchar,
         invalidator -> Varchar,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     cardinal_token_managers (address) {
         address -> Varchar,
@@ -377,13 +465,15 @@ table! {
         receipt_mint -> Nullable<Varchar>,
         claim_approver -> Nullable<Varchar>,
         transfer_authority -> Nullable<Varchar>,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     cardinal_use_invalidators (use_invalidator_address) {
         use_invalidator_address -> Varchar,
@@ -398,13 +488,15 @@ table! {
         use_invalidator_extension_payment_mint -> Nullable<Varchar>,
         use_invalidator_extension_usages -> Nullable<Int8>,
         use_invalidator_max_usages -> Nullable<Int8>,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     current_metadata_owners (mint_address) {
         mint_address -> Varchar,
@@ -412,26 +504,28 @@ table! {
         token_account_address -> Varchar,
         updated_at -> Timestamp,
         slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     editions (address) {
         address -> Varchar,
         parent_address -> Varchar,
         edition -> Int8,
         slot -> Nullable<Int8>,
+        write_version -> Nullable<Int8>,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     escrows (address) {
         address -> Varchar,
@@ -443,13 +537,51 @@ table! {
         escrow_started_at -> Int8,
         escrow_ends_at -> Int8,
         vote_delegate -> Varchar,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
+
+    execute_sale_instructions (id) {
+        id -> Uuid,
+        buyer -> Varchar,
+        seller -> Varchar,
+        token_account -> Varchar,
+        token_mint -> Varchar,
+        metadata -> Varchar,
+        treasury_mint -> Varchar,
+        escrow_payment_account -> Varchar,
+        seller_payment_receipt_account -> Varchar,
+        buyer_receipt_token_account -> Varchar,
+        authority -> Varchar,
+        auction_house -> Varchar,
+        auction_house_fee_account -> Varchar,
+        auction_house_treasury -> Varchar,
+        buyer_trade_state -> Varchar,
+        seller_trade_state -> Varchar,
+        free_trade_state -> Varchar,
+        program_as_signer -> Varchar,
+        escrow_payment_bump -> Int2,
+        free_trade_state_bump -> Int2,
+        program_as_signer_bump -> Int2,
+        buyer_price -> Int8,
+        token_size -> Int8,
+        slot -> Int8,
+        tx_signature -> Varchar,
+        instruction_index -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     feed_event_wallets (wallet_address, feed_event_id) {
         wallet_address -> Varchar,
@@ -460,7 +592,7 @@ table! {
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     feed_events (id) {
         id -> Uuid,
@@ -471,7 +603,7 @@ table! {
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     files (id) {
         metadata_address -> Varchar,
@@ -486,7 +618,7 @@ table! {
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     follow_events (feed_event_id) {
         graph_connection_address -> Varchar,
@@ -497,7 +629,7 @@ table! {
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     governance_parameters (governor_address) {
         governor_address -> Varchar,
@@ -505,13 +637,15 @@ table! {
         voting_period -> Int8,
         quorum_votes -> Int8,
         timelock_delay_seconds -> Int8,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     governors (address) {
         address -> Varchar,
@@ -520,13 +654,15 @@ table! {
         proposal_count -> Int8,
         electorate -> Varchar,
         smart_wallet -> Varchar,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     graph_connections (address) {
         address -> Varchar,
@@ -534,13 +670,15 @@ table! {
         to_account -> Varchar,
         connected_at -> Timestamp,
         disconnected_at -> Nullable<Timestamp>,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     ins_buffer_bundle_ins_keys (instruction_buffer_address, program_id, pubkey) {
         instruction_buffer_address -> Varchar,
@@ -548,36 +686,42 @@ table! {
         pubkey -> Varchar,
         is_signer -> Bool,
         is_writable -> Bool,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     ins_buffer_bundle_instructions (instruction_buffer_address, program_id) {
         instruction_buffer_address -> Varchar,
         program_id -> Varchar,
         data -> Bytea,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     ins_buffer_bundles (instruction_buffer_address) {
         instruction_buffer_address -> Varchar,
         is_executed -> Bool,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     instruction_buffers (address) {
         address -> Varchar,
@@ -586,13 +730,15 @@ table! {
         authority -> Varchar,
         executor -> Varchar,
         smart_wallet -> Varchar,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     listing_denylist (listing_address) {
         listing_address -> Varchar,
@@ -603,7 +749,7 @@ table! {
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     listing_events (feed_event_id) {
         listing_receipt_address -> Varchar,
@@ -615,19 +761,21 @@ table! {
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     listing_metadatas (listing_address, metadata_address) {
         listing_address -> Varchar,
         metadata_address -> Varchar,
         metadata_index -> Int4,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     listing_receipts (address) {
         address -> Varchar,
@@ -643,13 +791,15 @@ table! {
         trade_state_bump -> Int2,
         created_at -> Timestamp,
         canceled_at -> Nullable<Timestamp>,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     locker_params (locker_address) {
         locker_address -> Varchar,
@@ -658,13 +808,15 @@ table! {
         min_stake_duration -> Int8,
         max_stake_duration -> Int8,
         proposal_activation_min_votes -> Int8,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     locker_whitelist_entries (address) {
         address -> Varchar,
@@ -672,13 +824,15 @@ table! {
         locker -> Varchar,
         program_id -> Varchar,
         owner -> Varchar,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     lockers (address) {
         address -> Varchar,
@@ -687,38 +841,43 @@ table! {
         token_mint -> Varchar,
         locked_supply -> Int8,
         governor -> Varchar,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     master_editions (address) {
         address -> Varchar,
         supply -> Int8,
         max_supply -> Nullable<Int8>,
         slot -> Nullable<Int8>,
+        write_version -> Nullable<Int8>,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     metadata_collection_keys (metadata_address, collection_address) {
         metadata_address -> Varchar,
         collection_address -> Varchar,
         verified -> Bool,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     metadata_collections (id) {
         metadata_address -> Varchar,
@@ -733,7 +892,7 @@ table! {
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     metadata_creators (metadata_address, creator_address) {
         metadata_address -> Varchar,
@@ -741,13 +900,15 @@ table! {
         share -> Int4,
         verified -> Bool,
         position -> Nullable<Int4>,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     metadata_jsons (metadata_address) {
         metadata_address -> Varchar,
@@ -769,7 +930,7 @@ table! {
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     metadatas (address) {
         address -> Varchar,
@@ -785,13 +946,14 @@ table! {
         edition_pda -> Varchar,
         token_standard -> Nullable<Token_standard>,
         slot -> Nullable<Int8>,
+        write_version -> Nullable<Int8>,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     mint_events (feed_event_id) {
         metadata_address -> Varchar,
@@ -802,7 +964,7 @@ table! {
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     offer_events (feed_event_id) {
         bid_receipt_address -> Varchar,
@@ -814,7 +976,7 @@ table! {
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     proposal_account_metas (proposal_address, program_id, pubkey) {
         proposal_address -> Varchar,
@@ -822,38 +984,44 @@ table! {
         pubkey -> Varchar,
         is_signer -> Bool,
         is_writable -> Bool,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     proposal_instructions (proposal_address, program_id) {
         proposal_address -> Varchar,
         program_id -> Varchar,
         data -> Bytea,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     proposal_metas (address) {
         address -> Varchar,
         proposal -> Varchar,
         title -> Text,
         description_link -> Text,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     proposals (address) {
         address -> Varchar,
@@ -871,13 +1039,15 @@ table! {
         voting_ends_at -> Int8,
         queued_at -> Int8,
         queued_transaction -> Varchar,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     purchase_events (feed_event_id) {
         purchase_receipt_address -> Varchar,
@@ -888,7 +1058,7 @@ table! {
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     purchase_receipts (address) {
         address -> Varchar,
@@ -901,25 +1071,69 @@ table! {
         price -> Int8,
         bump -> Int2,
         created_at -> Timestamp,
+        slot -> Int8,
+        write_version -> Int8,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
+
+    sell_instructions (id) {
+        id -> Uuid,
+        wallet -> Varchar,
+        token_account -> Varchar,
+        metadata -> Varchar,
+        authority -> Varchar,
+        auction_house -> Varchar,
+        auction_house_fee_account -> Varchar,
+        seller_trade_state -> Varchar,
+        free_seller_trade_state -> Varchar,
+        program_as_signer -> Varchar,
+        trade_state_bump -> Int2,
+        free_trade_state_bump -> Int2,
+        program_as_signer_bump -> Int2,
+        buyer_price -> Int8,
+        token_size -> Int8,
+        slot -> Int8,
+        tx_signature -> Varchar,
+        instruction_index -> Int4,
+        created_at -> Timestamp,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
+
+    slots (slot) {
+        slot -> Int8,
+        parent_slot -> Nullable<Int8>,
+        status -> Slot_status,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     smart_wallet_owners (smart_wallet_address, owner_address) {
         smart_wallet_address -> Varchar,
         owner_address -> Varchar,
         index -> Int8,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     smart_wallets (address) {
         address -> Varchar,
@@ -930,13 +1144,15 @@ table! {
         grace_period -> Int8,
         owner_set_seqno -> Int8,
         num_transactions -> Int8,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     store_config_jsons (config_address) {
         config_address -> Varchar,
@@ -954,18 +1170,20 @@ table! {
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     store_configs (address) {
         address -> Varchar,
         settings_uri -> Text,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     store_creators (store_config_address, creator_address) {
         store_config_address -> Varchar,
@@ -976,7 +1194,7 @@ table! {
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     store_denylist (owner_address) {
         owner_address -> Varchar,
@@ -987,7 +1205,7 @@ table! {
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     storefronts (address) {
         owner_address -> Varchar,
@@ -1006,32 +1224,36 @@ table! {
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     stores (address) {
         address -> Varchar,
         public -> Bool,
         config_address -> Varchar,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     sub_account_infos (address) {
         address -> Varchar,
         smart_wallet -> Varchar,
         subaccount_type -> Int2,
         index -> Int8,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     transactions (address) {
         address -> Varchar,
@@ -1044,13 +1266,15 @@ table! {
         eta -> Int8,
         executor -> Varchar,
         executed_at -> Int8,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     twitter_handle_name_services (address) {
         address -> Varchar,
@@ -1066,7 +1290,7 @@ table! {
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     tx_instruction_keys (transaction_address, program_id, pubkey) {
         transaction_address -> Varchar,
@@ -1074,25 +1298,29 @@ table! {
         pubkey -> Varchar,
         is_signer -> Bool,
         is_writable -> Bool,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     tx_instructions (transaction_address, program_id) {
         transaction_address -> Varchar,
         program_id -> Varchar,
         data -> Bytea,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     votes (address) {
         address -> Varchar,
@@ -1101,13 +1329,15 @@ table! {
         bump -> Int2,
         side -> Int2,
         weight -> Int8,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     wallet_totals (address) {
         address -> Varchar,
@@ -1119,12 +1349,14 @@ table! {
 table! {
     use diesel::sql_types::*;
     use diesel_full_text_search::{TsVector as Tsvector, TsQuery as Tsquery};
-    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, TokenStandard as Token_standard, };
+    use crate::db::custom_types::{ListingEventLifecycle as Listingeventlifecycle, Mode, OfferEventLifecycle as Offereventlifecycle, SettingType as Settingtype, SlotStatus as Slot_status, TokenStandard as Token_standard, };
 
     whitelisted_creators (address) {
         address -> Varchar,
         creator_address -> Varchar,
         activated -> Bool,
+        slot -> Int8,
+        write_version -> Int8,
     }
 }
 
@@ -1149,6 +1381,8 @@ allow_tables_to_appear_in_same_query!(
     bid_receipts,
     bids,
     bonding_changes,
+    buy_instructions,
+    cancel_instructions,
     candy_machine_collection_pdas,
     candy_machine_config_lines,
     candy_machine_creators,
@@ -1167,6 +1401,7 @@ allow_tables_to_appear_in_same_query!(
     current_metadata_owners,
     editions,
     escrows,
+    execute_sale_instructions,
     feed_event_wallets,
     feed_events,
     files,
@@ -1199,6 +1434,8 @@ allow_tables_to_appear_in_same_query!(
     proposals,
     purchase_events,
     purchase_receipts,
+    sell_instructions,
+    slots,
     smart_wallet_owners,
     smart_wallets,
     store_config_jsons,
@@ -1,9 +1,13 @@
-use hashbrown::HashSet;
+use std::{net::SocketAddr, path::PathBuf};
+
+use hashbrown::{HashMap, HashSet};
 use serde::Deserialize;
 
 use crate::{
     prelude::*,
+    queue,
     selectors::{AccountSelector, InstructionSelector},
+    token_registry,
 };
 
 #[derive(Debug, Deserialize)]
@@ -15,9 +19,21 @@ pub struct Config {
     #[serde(default)]
     metrics: Metrics,
 
+    #[serde(default)]
+    queue: Queue,
+
     accounts: Accounts,
 
+    #[serde(default)]
+    token_registry: TokenRegistry,
+
     instruction_programs: HashSet<String>,
+
+    /// Send a single message containing the full transaction for each
+    /// transaction including a selected program, rather than a message per
+    /// selected instruction.
+    #[serde(default)]
+    full_transactions: bool,
 }
 
 #[serde_with::serde_as]
@@ -28,6 +44,49 @@ pub struct Amqp {
 
     #[serde_as(as = "serde_with::DisplayFromStr")]
     pub network: indexer_rabbitmq::geyser::Network,
+
+    /// Wait for the broker to confirm each published message, treating
+    /// unconfirmed messages as failed sends
+    #[serde(default)]
+    pub publisher_confirms: bool,
+
+    /// Publish to a topic exchange, using routing keys derived from the
+    /// program that produced each message
+    #[serde(default)]
+    pub topics: bool,
+
+    /// Declare the queue with priority lanes, delivering live account
+    /// updates ahead of startup updates.  This is only useful when publishing
+    /// both kinds of update, and consumers must be configured to match.
+    #[serde(default)]
+    pub priority_lanes: bool,
+
+    /// The wire format to publish messages in, e.g. `bare` or `cbor`
+    #[serde(default)]
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    pub wire_format: indexer_rabbitmq::serialize::Format,
+
+    /// Compress message payloads over a size threshold
+    #[serde(default)]
+    pub compression: Option<indexer_rabbitmq::compression::Policy>,
+
+    /// Publish to Kafka instead of RabbitMQ.  The settings above other than
+    /// `address` and `publisherConfirms` still apply.
+    #[cfg(feature = "kafka")]
+    #[serde(default)]
+    pub kafka: Option<Kafka>,
+}
+
+#[cfg(feature = "kafka")]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Kafka {
+    /// Comma-separated list of bootstrap servers
+    pub brokers: String,
+
+    /// Additional librdkafka producer properties
+    #[serde(default)]
+    pub properties: HashMap<String, String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -43,6 +102,54 @@ pub struct Jobs {
 #[serde(rename_all = "camelCase")]
 pub struct Metrics {
     pub config: Option<String>,
+
+    /// Address to serve Prometheus metrics on, at the `/metrics` path
+    #[serde(default)]
+    pub prometheus: Option<SocketAddr>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Queue {
+    /// Maximum number of messages held in memory awaiting publication
+    pub capacity: usize,
+
+    /// What to do with new messages when the queue is full
+    pub overflow: queue::Overflow,
+
+    /// Maximum number of messages to publish at once
+    pub batch_size: usize,
+
+    /// On-disk log for messages that overflow the queue or fail to publish.
+    /// Required by the `spill` policy.
+    pub wal: Option<Wal>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Wal {
+    /// Directory to store log segments in
+    pub dir: PathBuf,
+
+    /// Maximum size in bytes of a single log segment
+    #[serde(default)]
+    pub segment_bytes: Option<u64>,
+
+    /// Maximum total size in bytes of the log.  The oldest segments are
+    /// discarded when this is exceeded.
+    #[serde(default)]
+    pub max_bytes: Option<u64>,
+}
+
+impl Default for Queue {
+    fn default() -> Self {
+        Self {
+            capacity: 65_536,
+            overflow: queue::Overflow::default(),
+            batch_size: 64,
+            wal: None,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -57,6 +164,103 @@ pub struct Accounts {
     ///  - `Some(true)`: Only send updates when `is_startup` is `true`.
     ///  - `Some(false)`: Only send updates when `is_startup` is `false`.
     pub startup: Option<bool>,
+
+    /// Additional filters to apply to updates for specific owners, keyed by
+    /// owner program.  Each owner listed here must also appear in `owners`.
+    #[serde(default)]
+    pub filters: HashMap<String, AccountFilter>,
+}
+
+/// A set of conditions an account update must satisfy to be sent.  All
+/// specified conditions must hold for an update to pass.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AccountFilter {
+    /// Only send accounts whose data is exactly this many bytes long
+    #[serde(default)]
+    pub data_size: Option<usize>,
+
+    /// Only send accounts whose data contains the given bytes at the given
+    /// offsets
+    #[serde(default)]
+    pub memcmp: Vec<Memcmp>,
+
+    /// If non-empty, only send updates for these account keys
+    #[serde(default)]
+    pub include: HashSet<String>,
+
+    /// Never send updates for these account keys
+    #[serde(default)]
+    pub exclude: HashSet<String>,
+
+    /// Filters applied to data the size of an SPL token account.  Data of
+    /// that size which is not an initialized token account is rejected, and
+    /// data of any other size is not checked against these.
+    #[serde(default)]
+    pub token: Option<TokenFilter>,
+}
+
+/// Equivalent to the `memcmp` filter of the `getProgramAccounts` RPC method
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Memcmp {
+    /// Offset into the account data to compare at
+    pub offset: usize,
+    /// Base58-encoded bytes to compare against
+    pub bytes: String,
+}
+
+/// Filters specific to SPL token accounts
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TokenFilter {
+    /// If set, only send token accounts for these mints
+    #[serde(default)]
+    pub mints: Option<HashSet<String>>,
+
+    /// Never send token accounts for these mints
+    #[serde(default)]
+    pub exclude_mints: HashSet<String>,
+
+    /// Only send token accounts holding at most this many tokens
+    #[serde(default)]
+    pub max_amount: Option<u64>,
+
+    /// Never send token accounts whose mint appears in the token registry
+    #[serde(default)]
+    pub exclude_registry: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TokenRegistry {
+    /// Where to load the list of known fungible tokens from
+    pub source: token_registry::Source,
+
+    /// Interval in seconds between reloads of the token list, or zero to
+    /// only load it once
+    pub refresh_secs: u64,
+
+    /// Additionally treat any observed mint with nonzero decimals or a supply
+    /// greater than one as fungible
+    pub heuristic: bool,
+
+    /// The maximum number of mints to remember as fungible from the
+    /// heuristic.  The oldest observations are forgotten first, after which
+    /// the mint is treated as non-fungible until it is observed again, so
+    /// this should exceed the number of fungible mints in use.
+    pub observed_capacity: usize,
+}
+
+impl Default for TokenRegistry {
+    fn default() -> Self {
+        Self {
+            source: token_registry::Source::default(),
+            refresh_secs: 6 * 60 * 60,
+            heuristic: false,
+            observed_capacity: 100_000,
+        }
+    }
 }
 
 impl Config {
@@ -67,20 +271,33 @@ impl Config {
         Ok(cfg)
     }
 
-    pub fn into_parts(self) -> Result<(Amqp, Jobs, Metrics, AccountSelector, InstructionSelector)> {
+    pub fn into_parts(
+        self,
+    ) -> Result<(
+        Amqp,
+        Jobs,
+        Metrics,
+        Queue,
+        AccountSelector,
+        InstructionSelector,
+        TokenRegistry,
+    )> {
         let Self {
             amqp,
             jobs,
             metrics,
+            queue,
             accounts,
+            token_registry,
             instruction_programs,
+            full_transactions,
         } = self;
 
         let acct =
             AccountSelector::from_config(accounts).context("Failed to create account selector")?;
-        let ins = InstructionSelector::from_config(instruction_programs)
+        let ins = InstructionSelector::from_config(instruction_programs, full_transactions)
             .context("Failed to create instruction selector")?;
 
-        Ok((amqp, jobs, metrics, acct, ins))
+        Ok((amqp, jobs, metrics, queue, acct, ins, token_registry))
     }
 }
@@ -25,8 +25,11 @@ pub(crate) mod prelude {
 pub(crate) mod config;
 pub(crate) mod metrics;
 mod plugin;
+pub(crate) mod queue;
 pub(crate) mod selectors;
 pub(crate) mod sender;
+pub(crate) mod token_registry;
+pub(crate) mod wal;
 
 pub use plugin::GeyserPluginRabbitMq;
 
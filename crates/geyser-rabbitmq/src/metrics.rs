@@ -1,13 +1,22 @@
-use std::sync::Arc;
+use std::{convert::Infallible, future::Future, net::SocketAddr, sync::Arc, time::Duration};
 
+use hashbrown::HashMap;
+use hyper::{
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
 use log::Level;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
+use prometheus::{Encoder, Histogram, HistogramOpts, IntCounter, IntGauge, Registry, TextEncoder};
 use solana_metrics::counter::Counter as CounterInner;
 
+use crate::prelude::*;
+
 // Despite being entirely atomic, Solana's counter still requires a mutable
 // borrow for the inc() method.  So we have to do this awful Mutex<Atomic>
 // pattern unless they change that.
-pub struct Counter(Mutex<CounterInner>, Level);
+pub struct Counter(Mutex<CounterInner>, Level, IntCounter);
 
 // Solana's counter also doesn't implement Debug.
 impl std::fmt::Debug for Counter {
@@ -21,7 +30,7 @@ impl std::fmt::Debug for Counter {
 
 impl Counter {
     #[inline]
-    fn new(name: &'static str, lvl: Level) -> Self {
+    fn new(registry: &Registry, name: &'static str, help: &str, lvl: Level) -> Self {
         let mut inner = CounterInner {
             name,
             counts: 0.into(),
@@ -32,11 +41,19 @@ impl Counter {
         };
 
         inner.init();
-        Self(Mutex::new(inner), lvl)
+
+        let prom =
+            IntCounter::new(format!("{}_total", name), help).expect("Invalid Prometheus counter");
+        registry
+            .register(Box::new(prom.clone()))
+            .expect("Failed to register Prometheus counter");
+
+        Self(Mutex::new(inner), lvl, prom)
     }
 
     pub fn log(&self, n: usize) {
         self.0.lock().inc(self.1, n);
+        self.2.inc_by(n as u64);
     }
 }
 
@@ -46,15 +63,184 @@ pub struct Metrics {
     pub recvs: Counter,
     pub errs: Counter,
     pub reconnects: Counter,
+    pub drops: Counter,
+    pub spills: Counter,
+    pub observed_evictions: Counter,
+    publish_latency: Histogram,
+    queue_depth: IntGauge,
+    owner_sends: RwLock<HashMap<Pubkey, IntCounter>>,
+    registry: Registry,
 }
 
 impl Metrics {
     pub fn new_rc() -> Arc<Self> {
+        let registry = Registry::new();
+
+        let publish_latency = Histogram::with_opts(
+            HistogramOpts::new(
+                "geyser_publish_latency_seconds",
+                "Time taken to publish a batch of messages",
+            )
+            .buckets(vec![
+                0.000_5, 0.001, 0.002_5, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0,
+            ]),
+        )
+        .expect("Invalid Prometheus histogram");
+        let queue_depth = IntGauge::new(
+            "geyser_queue_depth",
+            "Number of messages waiting to be published",
+        )
+        .expect("Invalid Prometheus gauge");
+
+        registry
+            .register(Box::new(publish_latency.clone()))
+            .and_then(|()| registry.register(Box::new(queue_depth.clone())))
+            .expect("Failed to register Prometheus metrics");
+
         Arc::new(Self {
-            sends: Counter::new("geyser_sends", Level::Info),
-            recvs: Counter::new("geyser_recvs", Level::Info),
-            errs: Counter::new("geyser_errs", Level::Error),
-            reconnects: Counter::new("geyser_reconnects", Level::Error),
+            sends: Counter::new(&registry, "geyser_sends", "Messages published", Level::Info),
+            recvs: Counter::new(
+                &registry,
+                "geyser_recvs",
+                "Notifications received from the validator",
+                Level::Info,
+            ),
+            errs: Counter::new(&registry, "geyser_errs", "Errors encountered", Level::Error),
+            reconnects: Counter::new(
+                &registry,
+                "geyser_reconnects",
+                "AMQP reconnection attempts",
+                Level::Error,
+            ),
+            drops: Counter::new(
+                &registry,
+                "geyser_drops",
+                "Messages discarded without being published",
+                Level::Warn,
+            ),
+            spills: Counter::new(
+                &registry,
+                "geyser_spills",
+                "Messages written to the write-ahead log",
+                Level::Info,
+            ),
+            observed_evictions: Counter::new(
+                &registry,
+                "geyser_observed_mint_evictions",
+                "Fungible mints forgotten by the token registry heuristic",
+                Level::Warn,
+            ),
+            publish_latency,
+            queue_depth,
+            owner_sends: RwLock::default(),
+            registry,
+        })
+    }
+
+    /// Report the number of messages waiting to be published
+    pub fn queue_depth(&self, depth: usize) {
+        let depth = i64::try_from(depth).unwrap_or(i64::MAX);
+
+        self.queue_depth.set(depth);
+        solana_metrics::datapoint_info!("geyser_queue", ("depth", depth, i64));
+    }
+
+    /// Record the time taken to publish a batch of messages
+    pub fn publish_latency(&self, elapsed: Duration) {
+        self.publish_latency.observe(elapsed.as_secs_f64());
+    }
+
+    /// Record a published account update for the given owner program
+    pub fn owner_send(&self, owner: &Pubkey) {
+        if let Some(counter) = self.owner_sends.read().get(owner) {
+            counter.inc();
+            return;
+        }
+
+        let mut owners = self.owner_sends.write();
+        let counter = owners.entry(*owner).or_insert_with(|| {
+            let counter = IntCounter::with_opts(
+                prometheus::Opts::new(
+                    "geyser_owner_sends_total",
+                    "Account updates published per owner program",
+                )
+                .const_label("owner", owner.to_string()),
+            )
+            .expect("Invalid Prometheus counter");
+
+            if let Err(e) = self.registry.register(Box::new(counter.clone())) {
+                warn!("Failed to register counter for owner {}: {:?}", owner, e);
+            }
+
+            counter
+        });
+
+        counter.inc();
+    }
+
+    fn render(&self) -> Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buf)
+            .context("Failed to encode metrics")?;
+
+        Ok(buf)
+    }
+
+    /// Bind a Prometheus scrape endpoint to the given address, returning the
+    /// server future.  This must be called from within a Tokio runtime.
+    pub fn serve(
+        self: &Arc<Self>,
+        addr: SocketAddr,
+    ) -> Result<impl Future<Output = ()> + Send + 'static> {
+        let this = Arc::clone(self);
+
+        let server = Server::try_bind(&addr)
+            .with_context(|| format!("Failed to bind metrics listener to {}", addr))?
+            .serve(make_service_fn(move |_| {
+                let this = Arc::clone(&this);
+
+                async move {
+                    Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                        let this = Arc::clone(&this);
+
+                        async move { Ok::<_, Infallible>(this.handle(&req)) }
+                    }))
+                }
+            }));
+
+        info!("Serving Prometheus metrics on {}", addr);
+
+        Ok(async move {
+            if let Err(e) = server.await {
+                error!("Metrics listener failed: {:?}", e);
+            }
         })
     }
+
+    fn handle(&self, req: &Request<Body>) -> Response<Body> {
+        let mut res = Response::new(Body::empty());
+
+        match (req.method(), req.uri().path()) {
+            (&Method::GET, "/metrics") => match self.render() {
+                Ok(buf) => {
+                    res.headers_mut().insert(
+                        CONTENT_TYPE,
+                        TextEncoder::new()
+                            .format_type()
+                            .parse()
+                            .unwrap_or_else(|_| unreachable!()),
+                    );
+                    *res.body_mut() = buf.into();
+                },
+                Err(e) => {
+                    error!("{:?}", e);
+                    *res.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                },
+            },
+            _ => *res.status_mut() = StatusCode::NOT_FOUND,
+        }
+
+        res
+    }
 }
@@ -1,27 +1,26 @@
 use std::{env, sync::Arc};
 
 use anyhow::Context;
-use hashbrown::HashSet;
-use indexer_rabbitmq::geyser::{AccountUpdate, Message};
-use solana_program::{
-    instruction::CompiledInstruction, message::SanitizedMessage, program_pack::Pack,
+use indexer_rabbitmq::geyser::{
+    AccountUpdate, Message, SlotStatus, StartupType, TokenBalance, Transaction,
+    TransactionInstruction,
 };
-use spl_token::state::Account as TokenAccount;
-
-static TOKEN_KEY: Pubkey = solana_program::pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
-
-use serde::Deserialize;
+use solana_program::{instruction::CompiledInstruction, message::SanitizedMessage};
+use solana_transaction_status::TransactionTokenBalance;
 
 use crate::{
     config::Config,
     interface::{
         GeyserPlugin, GeyserPluginError, ReplicaAccountInfo, ReplicaAccountInfoVersions,
-        ReplicaTransactionInfoVersions, Result,
+        ReplicaTransactionInfo, ReplicaTransactionInfoVersions, Result,
+        SlotStatus as PluginSlotStatus,
     },
     metrics::{Counter, Metrics},
     prelude::*,
+    queue::SendQueue,
     selectors::{AccountSelector, InstructionSelector},
     sender::Sender,
+    token_registry::TokenRegistry,
 };
 
 const UNINIT: &str = "RabbitMQ plugin not initialized yet!";
@@ -39,20 +38,11 @@ fn custom_err<'a, E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>>(
 #[derive(Debug)]
 pub(crate) struct Inner {
     rt: tokio::runtime::Runtime,
-    producer: Sender,
+    queue: Arc<SendQueue>,
     acct_sel: AccountSelector,
     ins_sel: InstructionSelector,
     metrics: Arc<Metrics>,
-    token_addresses: HashSet<Pubkey>,
-}
-
-impl Inner {
-    pub fn spawn<F: std::future::Future<Output = anyhow::Result<()>> + Send + 'static>(
-        self: &Arc<Self>,
-        f: impl FnOnce(Arc<Self>) -> F,
-    ) {
-        self.rt.spawn(f(Arc::clone(self)));
-    }
+    token_registry: Option<Arc<TokenRegistry>>,
 }
 
 /// An instance of the plugin
@@ -60,34 +50,7 @@ impl Inner {
 #[repr(transparent)]
 pub struct GeyserPluginRabbitMq(Option<Arc<Inner>>);
 
-#[derive(Deserialize)]
-struct TokenItem {
-    address: String,
-}
-
-#[derive(Deserialize)]
-struct TokenList {
-    tokens: Vec<TokenItem>,
-}
-
 impl GeyserPluginRabbitMq {
-    const TOKEN_REG_URL: &'static str = "https://raw.githubusercontent.com/solana-labs/token-list/main/src/tokens/solana.tokenlist.json";
-
-    async fn load_token_reg() -> anyhow::Result<HashSet<Pubkey>> {
-        let res: TokenList = reqwest::get(Self::TOKEN_REG_URL)
-            .await
-            .context("HTTP request failed")?
-            .json()
-            .await
-            .context("Failed to parse response JSON")?;
-
-        res.tokens
-            .into_iter()
-            .map(|TokenItem { address }| address.parse())
-            .collect::<StdResult<_, _>>()
-            .context("Failed to convert token list")
-    }
-
     fn expect_inner(&self) -> &Arc<Inner> {
         self.0.as_ref().expect(UNINIT)
     }
@@ -150,9 +113,10 @@ impl GeyserPlugin for GeyserPluginRabbitMq {
                 .map_err(custom_err(&metrics.errs))?;
         }
 
-        let (amqp, jobs, metrics_conf, acct_sel, ins_sel) = Config::read(cfg)
-            .and_then(Config::into_parts)
-            .map_err(custom_err(&metrics.errs))?;
+        let (amqp, jobs, metrics_conf, queue_conf, acct_sel, ins_sel, token_reg_conf) =
+            Config::read(cfg)
+                .and_then(Config::into_parts)
+                .map_err(custom_err(&metrics.errs))?;
 
         let startup_type = acct_sel.startup();
 
@@ -174,30 +138,67 @@ impl GeyserPlugin for GeyserPluginRabbitMq {
             .build()
             .map_err(custom_err(&metrics.errs))?;
 
-        let (producer, token_addresses) = rt.block_on(async {
-            let producer = Sender::new(
+        if let Some(addr) = metrics_conf.prometheus {
+            let _rt = rt.enter();
+            let server = metrics.serve(addr).map_err(custom_err(&metrics.errs))?;
+
+            rt.spawn(server);
+        }
+
+        let queue = SendQueue::from_config(queue_conf, Arc::clone(&metrics))
+            .map(Arc::new)
+            .map_err(custom_err(&metrics.errs))?;
+
+        let token_registry = acct_sel
+            .uses_token_registry()
+            .then(|| Arc::new(TokenRegistry::from_config(token_reg_conf)));
+
+        let producer = rt.block_on(async {
+            if let Some(ref reg) = token_registry {
+                // A missing token list only weakens filtering, so don't fail
+                // to load the plugin over it
+                if let Err(e) = reg.load().await {
+                    warn!("Failed to load token registry: {:?}", e);
+                    metrics.errs.log(1);
+                }
+            }
+
+            Sender::new(
                 amqp,
                 format!("geyser-rabbitmq-{}@{}", version, host),
                 startup_type,
                 Arc::clone(&metrics),
             )
             .await
-            .map_err(custom_err(&metrics.errs))?;
+            .map_err(custom_err(&metrics.errs))
+        })?;
 
-            let tokens = Self::load_token_reg()
-                .await
-                .map_err(custom_err(&metrics.errs))?;
+        if let Some(ref reg) = token_registry {
+            let reg = Arc::clone(reg);
+            let metrics = Arc::clone(&metrics);
 
-            Result::<_>::Ok((producer, tokens))
-        })?;
+            rt.spawn(async move {
+                reg.refresh(|e| {
+                    warn!("{:?}", e);
+                    metrics.errs.log(1);
+                })
+                .await;
+            });
+        }
+
+        {
+            let queue = Arc::clone(&queue);
+
+            rt.spawn(async move { queue.run(&producer).await });
+        }
 
         self.0 = Some(Arc::new(Inner {
             rt,
-            producer,
+            queue,
             acct_sel,
             ins_sel,
             metrics,
-            token_addresses,
+            token_registry,
         }));
 
         Ok(())
@@ -216,7 +217,19 @@ impl GeyserPlugin for GeyserPluginRabbitMq {
 
                 match account {
                     ReplicaAccountInfoVersions::V0_0_1(acct) => {
-                        if !this.acct_sel.is_selected(acct, is_startup) {
+                        if let Some(ref reg) = this.token_registry {
+                            let evicted = reg.observe(acct.pubkey, acct.owner, acct.data);
+
+                            if evicted > 0 {
+                                this.metrics.observed_evictions.log(evicted);
+                            }
+                        }
+
+                        if !this.acct_sel.is_selected(acct, is_startup, |m| {
+                            this.token_registry
+                                .as_ref()
+                                .map_or(false, |r| r.contains(m))
+                        }) {
                             return Ok(());
                         }
 
@@ -230,42 +243,21 @@ impl GeyserPlugin for GeyserPluginRabbitMq {
                             write_version,
                         } = *acct;
 
-                        if owner == TOKEN_KEY.as_ref()
-                            && data.len() == TokenAccount::get_packed_len()
-                        {
-                            let token_account = TokenAccount::unpack_from_slice(data);
-
-                            if let Ok(token_account) = token_account {
-                                if token_account.amount > 1
-                                    || this.token_addresses.contains(&token_account.mint)
-                                {
-                                    return Ok(());
-                                }
-                            }
-                        }
-
                         let key = Pubkey::new_from_array(pubkey.try_into()?);
                         let owner = Pubkey::new_from_array(owner.try_into()?);
                         let data = data.to_owned();
 
-                        this.spawn(|this| async move {
-                            this.producer
-                                .send(Message::AccountUpdate(AccountUpdate {
-                                    key,
-                                    lamports,
-                                    owner,
-                                    executable,
-                                    rent_epoch,
-                                    data,
-                                    write_version,
-                                    slot,
-                                    is_startup,
-                                }))
-                                .await;
-                            this.metrics.sends.log(1);
-
-                            Ok(())
-                        });
+                        this.queue.push(Message::AccountUpdate(AccountUpdate {
+                            key,
+                            lamports,
+                            owner,
+                            executable,
+                            rent_epoch,
+                            data,
+                            write_version,
+                            slot,
+                            is_startup,
+                        }));
                     },
                 };
 
@@ -274,10 +266,43 @@ impl GeyserPlugin for GeyserPluginRabbitMq {
         )
     }
 
+    fn update_slot_status(
+        &mut self,
+        slot: u64,
+        parent: Option<u64>,
+        status: PluginSlotStatus,
+    ) -> Result<()> {
+        self.with_inner(
+            || GeyserPluginError::SlotStatusUpdateError { msg: UNINIT.into() },
+            |this| {
+                // Startup-only queues have no use for live slot notifications
+                if matches!(this.acct_sel.startup(), StartupType::Startup) {
+                    return Ok(());
+                }
+
+                this.metrics.recvs.log(1);
+
+                let status = match status {
+                    PluginSlotStatus::Processed => SlotStatus::Processed,
+                    PluginSlotStatus::Confirmed => SlotStatus::Confirmed,
+                    PluginSlotStatus::Rooted => SlotStatus::Rooted,
+                };
+
+                this.queue.push(Message::SlotStatus {
+                    slot,
+                    parent,
+                    status,
+                });
+
+                Ok(())
+            },
+        )
+    }
+
     fn notify_transaction(
         &mut self,
         transaction: ReplicaTransactionInfoVersions,
-        _slot: u64,
+        slot: u64,
     ) -> Result<()> {
         #[inline]
         fn process_instruction(
@@ -313,6 +338,120 @@ impl GeyserPlugin for GeyserPluginRabbitMq {
             }))
         }
 
+        fn process_token_balance(
+            msg: &SanitizedMessage,
+            bal: &TransactionTokenBalance,
+        ) -> anyhow::Result<TokenBalance> {
+            Ok(TokenBalance {
+                account: *msg
+                    .get_account_key(bal.account_index.into())
+                    .ok_or_else(|| anyhow!("Couldn't get account for token balance"))?,
+                mint: bal
+                    .mint
+                    .parse()
+                    .context("Couldn't parse token balance mint")?,
+                owner: if bal.owner.is_empty() {
+                    None
+                } else {
+                    Some(
+                        bal.owner
+                            .parse()
+                            .context("Couldn't parse token balance owner")?,
+                    )
+                },
+                amount: bal
+                    .ui_token_amount
+                    .amount
+                    .parse()
+                    .context("Couldn't parse token balance amount")?,
+                decimals: bal.ui_token_amount.decimals,
+            })
+        }
+
+        fn process_transaction(
+            sel: &InstructionSelector,
+            tx: &ReplicaTransactionInfo,
+            slot: u64,
+        ) -> anyhow::Result<Option<Message>> {
+            let msg = tx.transaction.message();
+            let meta = tx.transaction_status_meta;
+
+            let get_key = |i: u8| {
+                msg.get_account_key(i.into())
+                    .copied()
+                    .ok_or_else(|| anyhow!("Couldn't get account key for instruction"))
+            };
+
+            let mut selected = false;
+            let mut instructions = vec![];
+
+            for (index, ins) in msg.instructions().iter().enumerate() {
+                let index: u8 = index
+                    .try_into()
+                    .context("Transaction had too many instructions")?;
+
+                let inner = meta
+                    .inner_instructions
+                    .iter()
+                    .flatten()
+                    .filter(|i| i.index == index)
+                    .flat_map(|i| i.instructions.iter().enumerate())
+                    .map(|(j, ins)| (Some(j), ins));
+
+                for (inner_index, ins) in std::iter::once((None, ins)).chain(inner) {
+                    let program = get_key(ins.program_id_index)?;
+
+                    selected |= sel.is_selected(&program);
+
+                    instructions.push(TransactionInstruction {
+                        program,
+                        data: ins.data.clone(),
+                        accounts: ins
+                            .accounts
+                            .iter()
+                            .map(|i| get_key(*i))
+                            .collect::<anyhow::Result<_>>()?,
+                        index,
+                        inner_index: inner_index
+                            .map(TryInto::try_into)
+                            .transpose()
+                            .context("Transaction had too many inner instructions")?,
+                    });
+                }
+            }
+
+            if !selected {
+                return Ok(None);
+            }
+
+            let token_balances = |bals: &Option<Vec<TransactionTokenBalance>>| {
+                bals.iter()
+                    .flatten()
+                    .map(|b| process_token_balance(msg, b))
+                    .collect::<anyhow::Result<Vec<_>>>()
+            };
+
+            Ok(Some(Message::Transaction(Box::new(Transaction {
+                signature: tx.signature.to_string(),
+                slot,
+                fee_payer: *msg
+                    .get_account_key(0)
+                    .ok_or_else(|| anyhow!("Couldn't get transaction fee payer"))?,
+                account_keys: (0..)
+                    .map_while(|i| msg.get_account_key(i))
+                    .copied()
+                    .collect(),
+                instructions,
+                fee: meta.fee,
+                pre_balances: meta.pre_balances.clone(),
+                post_balances: meta.post_balances.clone(),
+                pre_token_balances: token_balances(&meta.pre_token_balances)?,
+                post_token_balances: token_balances(&meta.post_token_balances)?,
+                log_messages: meta.log_messages.clone().unwrap_or_default(),
+                error: meta.status.as_ref().err().map(ToString::to_string),
+            }))))
+        }
+
         self.with_inner(
             || GeyserPluginError::Custom(anyhow!(UNINIT).into()),
             |this| {
@@ -323,6 +462,24 @@ impl GeyserPlugin for GeyserPluginRabbitMq {
                 this.metrics.recvs.log(1);
 
                 match transaction {
+                    ReplicaTransactionInfoVersions::V0_0_1(tx)
+                        if this.ins_sel.full_transactions() =>
+                    {
+                        if tx.is_vote {
+                            return Ok(());
+                        }
+
+                        match process_transaction(&this.ins_sel, tx, slot) {
+                            Ok(Some(m)) => {
+                                this.queue.push(m);
+                            },
+                            Ok(None) => (),
+                            Err(e) => {
+                                warn!("Error processing transaction: {:?}", e);
+                                this.metrics.errs.log(1);
+                            },
+                        }
+                    },
                     ReplicaTransactionInfoVersions::V0_0_1(tx) => {
                         if matches!(tx.transaction_status_meta.status, Err(..)) {
                             return Ok(());
@@ -339,12 +496,7 @@ impl GeyserPlugin for GeyserPluginRabbitMq {
                         ) {
                             match process_instruction(&this.ins_sel, ins, msg) {
                                 Ok(Some(m)) => {
-                                    this.spawn(|this| async move {
-                                        this.producer.send(m).await;
-                                        this.metrics.sends.log(1);
-
-                                        Ok(())
-                                    });
+                                    this.queue.push(m);
                                 },
                                 Ok(None) => (),
                                 Err(e) => {
@@ -0,0 +1,298 @@
+use std::{
+    collections::VecDeque,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use indexer_rabbitmq::geyser::Message;
+use parking_lot::{Condvar, Mutex};
+use serde::Deserialize;
+use tokio::sync::Notify;
+
+use crate::{config, metrics::Metrics, prelude::*, sender::Sender, wal::Wal};
+
+const DEPTH_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+const MIN_RETRY_DELAY: Duration = Duration::from_millis(500);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Behavior of the send queue when it is at capacity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Overflow {
+    /// Block the calling validator thread until space is available
+    Block,
+    /// Discard the oldest queued message to make room
+    DropOldest,
+    /// Write messages to the WAL until the queue has drained
+    Spill,
+}
+
+impl Default for Overflow {
+    fn default() -> Self {
+        Self::Block
+    }
+}
+
+/// Where a popped batch of messages came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    Memory,
+    Wal,
+}
+
+#[derive(Debug)]
+struct State {
+    queue: VecDeque<Message>,
+    wal: Option<Wal>,
+}
+
+/// A bounded queue of messages waiting to be published.  Messages are pushed
+/// synchronously by the plugin callbacks and published in batches by a single
+/// background task.
+///
+/// If a WAL is configured, messages that fail to publish are written to it
+/// and retried in order.  While the WAL holds any messages, all new messages
+/// are appended to it as well so that ordering is preserved.
+#[derive(Debug)]
+pub struct SendQueue {
+    state: Mutex<State>,
+    not_full: Condvar,
+    not_empty: Notify,
+    capacity: usize,
+    batch_size: usize,
+    overflow: Overflow,
+    metrics: Arc<Metrics>,
+}
+
+impl SendQueue {
+    pub fn from_config(config: config::Queue, metrics: Arc<Metrics>) -> Result<Self> {
+        let config::Queue {
+            capacity,
+            overflow,
+            batch_size,
+            wal,
+        } = config;
+
+        if capacity == 0 {
+            bail!("Queue capacity must be nonzero");
+        }
+
+        if batch_size == 0 {
+            bail!("Queue batch size must be nonzero");
+        }
+
+        if overflow == Overflow::Spill && wal.is_none() {
+            bail!("The spill overflow policy requires a WAL to be configured");
+        }
+
+        let wal = wal
+            .map(Wal::open)
+            .transpose()
+            .context("Failed to open WAL")?;
+
+        Ok(Self {
+            state: Mutex::new(State {
+                queue: VecDeque::with_capacity(capacity),
+                wal,
+            }),
+            not_full: Condvar::new(),
+            not_empty: Notify::new(),
+            capacity,
+            batch_size,
+            overflow,
+            metrics,
+        })
+    }
+
+    /// Append messages to the WAL, logging any errors or lost messages
+    fn append_wal<'a>(&self, wal: &mut Wal, msgs: impl IntoIterator<Item = &'a Message>) {
+        for msg in msgs {
+            match wal.append(msg) {
+                Ok(dropped) => {
+                    self.metrics.spills.log(1);
+
+                    if dropped > 0 {
+                        warn!("WAL size cap reached, discarded {} message(s)", dropped);
+                        self.metrics.drops.log(dropped);
+                    }
+                },
+                Err(e) => {
+                    error!("Failed to write message to WAL: {:?}", e);
+                    self.metrics.errs.log(1);
+                    self.metrics.drops.log(1);
+                },
+            }
+        }
+    }
+
+    /// Enqueue a message, applying the overflow policy if the queue is full
+    pub fn push(&self, msg: Message) {
+        let mut state = self.state.lock();
+
+        let spill = loop {
+            if state.wal.as_ref().map_or(false, |w| w.pending() > 0) {
+                break true;
+            }
+
+            let full = state.queue.len() >= self.capacity;
+
+            match self.overflow {
+                Overflow::Block if full => self.not_full.wait(&mut state),
+                Overflow::DropOldest if full => {
+                    state.queue.pop_front();
+                    self.metrics.drops.log(1);
+
+                    break false;
+                },
+                Overflow::Spill => break full,
+                Overflow::Block | Overflow::DropOldest => break false,
+            }
+        };
+
+        if spill {
+            let wal = state.wal.as_mut().unwrap_or_else(|| unreachable!());
+            self.append_wal(wal, [&msg]);
+        } else {
+            state.queue.push_back(msg);
+        }
+
+        std::mem::drop(state);
+        self.not_empty.notify_one();
+    }
+
+    fn pop_batch(&self, batch: &mut Vec<Message>) -> (Source, usize) {
+        let mut state = self.state.lock();
+        let n = self.batch_size.min(state.queue.len());
+        let mut source = Source::Memory;
+
+        batch.extend(state.queue.drain(..n));
+
+        if batch.is_empty() {
+            if let Some(ref mut wal) = state.wal {
+                source = Source::Wal;
+
+                if let Err(e) = wal.peek(self.batch_size, batch) {
+                    error!("Failed to read messages from WAL: {:?}", e);
+                    self.metrics.errs.log(1);
+                    batch.clear();
+
+                    // Skip the unreadable segment rather than retrying it
+                    // forever
+                    match wal.discard_front() {
+                        Ok(n) => self.metrics.drops.log(n),
+                        Err(e) => error!("Failed to discard WAL segment: {:?}", e),
+                    }
+                }
+            }
+        }
+
+        let depth = state.queue.len() + state.wal.as_ref().map_or(0, Wal::pending);
+        std::mem::drop(state);
+
+        if n > 0 {
+            self.not_full.notify_all();
+        }
+
+        (source, depth)
+    }
+
+    /// Handle a batch that failed to publish, returning true if it was saved
+    /// to the WAL to be retried
+    fn stash(&self, batch: &[Message]) -> bool {
+        let mut state = self.state.lock();
+        let State {
+            ref mut queue,
+            ref mut wal,
+        } = *state;
+
+        let wal = match wal {
+            Some(w) => w,
+            None => {
+                self.metrics.drops.log(batch.len());
+                return false;
+            },
+        };
+
+        // Any messages still in memory are newer than the failed batch, so
+        // move them into the WAL behind it
+        self.append_wal(wal, batch.iter().chain(queue.iter()));
+        queue.clear();
+
+        // Single spilled messages are synced when the WAL is next read, but a
+        // failed batch is flushed to disk right away
+        if let Err(e) = wal.sync() {
+            error!("Failed to sync WAL: {:?}", e);
+            self.metrics.errs.log(1);
+        }
+
+        std::mem::drop(state);
+        self.not_full.notify_all();
+
+        true
+    }
+
+    fn commit_wal(&self) {
+        let mut state = self.state.lock();
+
+        if let Some(Err(e)) = state.wal.as_mut().map(Wal::commit) {
+            error!("Failed to commit WAL read: {:?}", e);
+            self.metrics.errs.log(1);
+        }
+    }
+
+    /// Publish queued messages with the given sender.  This function never
+    /// returns.
+    pub async fn run(&self, sender: &Sender) {
+        let mut batch = Vec::with_capacity(self.batch_size);
+        let mut last_report = Instant::now();
+        let mut retry_delay = MIN_RETRY_DELAY;
+
+        loop {
+            let (source, depth) = self.pop_batch(&mut batch);
+
+            if last_report.elapsed() >= DEPTH_REPORT_INTERVAL {
+                self.metrics.queue_depth(depth);
+                last_report = Instant::now();
+            }
+
+            if batch.is_empty() {
+                self.not_empty.notified().await;
+                continue;
+            }
+
+            let start = Instant::now();
+            let sent = sender.send(&batch).await;
+            self.metrics.publish_latency(start.elapsed());
+
+            if sent {
+                if source == Source::Wal {
+                    self.commit_wal();
+                }
+
+                self.metrics.sends.log(batch.len());
+
+                for msg in &batch {
+                    if let Message::AccountUpdate(ref update) = msg {
+                        self.metrics.owner_send(&update.owner);
+                    }
+                }
+
+                retry_delay = MIN_RETRY_DELAY;
+            } else {
+                let retry = match source {
+                    Source::Memory => self.stash(&batch),
+                    // Uncommitted messages are read from the WAL again on
+                    // the next attempt
+                    Source::Wal => true,
+                };
+
+                if retry {
+                    tokio::time::sleep(retry_delay).await;
+                    retry_delay = (retry_delay * 2).min(MAX_RETRY_DELAY);
+                }
+            }
+
+            batch.clear();
+        }
+    }
+}
@@ -1,26 +1,213 @@
-use hashbrown::HashSet;
+use hashbrown::{HashMap, HashSet};
 use indexer_rabbitmq::geyser::StartupType;
+use solana_program::program_pack::Pack;
+use spl_token::state::Account as TokenAccount;
 
-use super::config::Accounts;
+use super::config::{AccountFilter, Accounts, Memcmp, TokenFilter};
 use crate::{interface::ReplicaAccountInfo, prelude::*};
 
+fn parse_keys<T: FromIterator<Pubkey>>(keys: impl IntoIterator<Item = String>) -> Result<T> {
+    keys.into_iter()
+        .map(|s| s.parse().with_context(|| format!("Invalid pubkey {:?}", s)))
+        .collect()
+}
+
+#[derive(Debug)]
+struct DataFilter {
+    data_size: Option<usize>,
+    memcmp: Vec<(usize, Vec<u8>)>,
+    include: HashSet<Pubkey>,
+    exclude: HashSet<Pubkey>,
+    token: Option<TokenDataFilter>,
+}
+
+#[derive(Debug)]
+struct TokenDataFilter {
+    mints: Option<HashSet<Pubkey>>,
+    exclude_mints: HashSet<Pubkey>,
+    max_amount: Option<u64>,
+    exclude_registry: bool,
+}
+
+impl DataFilter {
+    fn from_config(config: AccountFilter) -> Result<Self> {
+        let AccountFilter {
+            data_size,
+            memcmp,
+            include,
+            exclude,
+            token,
+        } = config;
+
+        let memcmp = memcmp
+            .into_iter()
+            .map(|Memcmp { offset, bytes }| {
+                bs58::decode(&bytes)
+                    .into_vec()
+                    .map(|b| (offset, b))
+                    .with_context(|| format!("Invalid base58 memcmp bytes {:?}", bytes))
+            })
+            .collect::<Result<_>>()?;
+
+        let token = token
+            .map(
+                |TokenFilter {
+                     mints,
+                     exclude_mints,
+                     max_amount,
+                     exclude_registry,
+                 }| {
+                    Result::<_>::Ok(TokenDataFilter {
+                        mints: mints.map(parse_keys).transpose()?,
+                        exclude_mints: parse_keys(exclude_mints)?,
+                        max_amount,
+                        exclude_registry,
+                    })
+                },
+            )
+            .transpose()
+            .context("Failed to parse token filter")?;
+
+        Ok(Self {
+            data_size,
+            memcmp,
+            include: parse_keys(include).context("Failed to parse included keys")?,
+            exclude: parse_keys(exclude).context("Failed to parse excluded keys")?,
+            token,
+        })
+    }
+
+    /// The filter applied to SPL token accounts if none is configured,
+    /// selecting only accounts holding at most one token of a mint missing
+    /// from the token registry
+    fn default_token() -> Self {
+        Self {
+            data_size: None,
+            memcmp: vec![],
+            include: HashSet::new(),
+            exclude: HashSet::new(),
+            token: Some(TokenDataFilter {
+                mints: None,
+                exclude_mints: HashSet::new(),
+                max_amount: Some(1),
+                exclude_registry: true,
+            }),
+        }
+    }
+
+    fn is_selected(
+        &self,
+        key: &[u8],
+        data: &[u8],
+        is_registered: impl FnOnce(&Pubkey) -> bool,
+    ) -> bool {
+        if self.data_size.map_or(false, |s| data.len() != s) {
+            return false;
+        }
+
+        if !self
+            .memcmp
+            .iter()
+            .all(|(off, bytes)| data.get(*off..).map_or(false, |d| d.starts_with(bytes)))
+        {
+            return false;
+        }
+
+        if !self.include.is_empty() || !self.exclude.is_empty() {
+            let key = match <[u8; 32]>::try_from(key) {
+                Ok(k) => Pubkey::new_from_array(k),
+                Err(_) => return false,
+            };
+
+            if (!self.include.is_empty() && !self.include.contains(&key))
+                || self.exclude.contains(&key)
+            {
+                return false;
+            }
+        }
+
+        // Data sized like a token account must be an initialized token account
+        // to pass the token filter, so garbage cannot bypass it
+        match self.token {
+            Some(ref token) if data.len() == TokenAccount::get_packed_len() => {
+                TokenAccount::unpack(data)
+                    .map_or(false, |acct| token.is_selected(&acct, is_registered))
+            },
+            Some(_) | None => true,
+        }
+    }
+}
+
+impl TokenDataFilter {
+    fn is_selected(
+        &self,
+        acct: &TokenAccount,
+        is_registered: impl FnOnce(&Pubkey) -> bool,
+    ) -> bool {
+        self.mints.as_ref().map_or(true, |m| m.contains(&acct.mint))
+            && !self.exclude_mints.contains(&acct.mint)
+            && self.max_amount.map_or(true, |m| acct.amount <= m)
+            && !(self.exclude_registry && is_registered(&acct.mint))
+    }
+}
+
 #[derive(Debug)]
 pub struct AccountSelector {
     owners: HashSet<[u8; 32]>,
+    filters: HashMap<[u8; 32], DataFilter>,
     startup: Option<bool>,
 }
 
 impl AccountSelector {
     pub fn from_config(config: Accounts) -> Result<Self> {
-        let Accounts { owners, startup } = config;
+        let Accounts {
+            owners,
+            startup,
+            filters,
+        } = config;
 
-        let owners = owners
+        let owners: HashSet<_> = owners
             .into_iter()
             .map(|s| s.parse().map(Pubkey::to_bytes))
             .collect::<Result<_, _>>()
             .context("Failed to parse account owner keys")?;
 
-        Ok(Self { owners, startup })
+        let mut filters: HashMap<_, _> = filters
+            .into_iter()
+            .map(|(owner, filter)| -> Result<_> {
+                let key: Pubkey = owner
+                    .parse()
+                    .with_context(|| format!("Invalid filter owner {:?}", owner))?;
+
+                if !owners.contains(key.as_ref()) {
+                    bail!("Filter owner {} is not a selected owner", key);
+                }
+
+                let filter = DataFilter::from_config(filter)
+                    .with_context(|| format!("Failed to parse filter for owner {}", key))?;
+
+                Ok((key.to_bytes(), filter))
+            })
+            .collect::<Result<_>>()?;
+
+        let token_key = spl_token::id().to_bytes();
+
+        if owners.contains(&token_key) && !filters.contains_key(&token_key) {
+            filters.insert(token_key, DataFilter::default_token());
+        }
+
+        Ok(Self {
+            owners,
+            filters,
+            startup,
+        })
+    }
+
+    /// Returns true if any filter needs to query the token registry
+    pub fn uses_token_registry(&self) -> bool {
+        self.filters
+            .values()
+            .any(|f| f.token.as_ref().map_or(false, |t| t.exclude_registry))
     }
 
     #[inline]
@@ -28,26 +215,46 @@ impl AccountSelector {
         StartupType::new(self.startup)
     }
 
-    #[inline]
-    pub fn is_selected(&self, acct: &ReplicaAccountInfo, is_startup: bool) -> bool {
-        self.startup.map_or(true, |s| is_startup == s) && self.owners.contains(acct.owner)
+    /// Check an account update against the owner and startup selection, as
+    /// well as any data filters configured for its owner.  `is_registered`
+    /// is used to look up token mints in the token registry.
+    pub fn is_selected(
+        &self,
+        acct: &ReplicaAccountInfo,
+        is_startup: bool,
+        is_registered: impl FnOnce(&Pubkey) -> bool,
+    ) -> bool {
+        if !(self.startup.map_or(true, |s| is_startup == s) && self.owners.contains(acct.owner)) {
+            return false;
+        }
+
+        <[u8; 32]>::try_from(acct.owner)
+            .ok()
+            .and_then(|o| self.filters.get(&o))
+            .map_or(true, |f| {
+                f.is_selected(acct.pubkey, acct.data, is_registered)
+            })
     }
 }
 
 #[derive(Debug)]
 pub struct InstructionSelector {
     programs: HashSet<Pubkey>,
+    full_transactions: bool,
 }
 
 impl InstructionSelector {
-    pub fn from_config(programs: HashSet<String>) -> Result<Self> {
+    pub fn from_config(programs: HashSet<String>, full_transactions: bool) -> Result<Self> {
         let programs = programs
             .into_iter()
             .map(|s| s.parse())
             .collect::<Result<_, _>>()
             .context("Failed to parse instruction program keys")?;
 
-        Ok(Self { programs })
+        Ok(Self {
+            programs,
+            full_transactions,
+        })
     }
 
     #[inline]
@@ -59,4 +266,145 @@ impl InstructionSelector {
     pub fn is_selected(&self, pgm: &Pubkey) -> bool {
         self.programs.contains(pgm)
     }
+
+    #[inline]
+    pub fn full_transactions(&self) -> bool {
+        self.full_transactions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spl_token::state::AccountState;
+
+    use super::*;
+
+    fn key(b: u8) -> Pubkey {
+        Pubkey::new_from_array([b; 32])
+    }
+
+    fn token_data(mint: Pubkey, amount: u64, state: AccountState) -> Vec<u8> {
+        let mut data = vec![0; TokenAccount::get_packed_len()];
+        TokenAccount::pack_into_slice(
+            &TokenAccount {
+                mint,
+                owner: key(9),
+                amount,
+                state,
+                ..TokenAccount::default()
+            },
+            &mut data,
+        );
+
+        data
+    }
+
+    fn filter(config: AccountFilter) -> DataFilter {
+        DataFilter::from_config(config).unwrap()
+    }
+
+    #[test]
+    fn data_size_and_memcmp() {
+        let f = filter(AccountFilter {
+            data_size: Some(4),
+            memcmp: vec![Memcmp {
+                offset: 1,
+                bytes: bs58::encode([2, 3]).into_string(),
+            }],
+            ..AccountFilter::default()
+        });
+
+        assert!(f.is_selected(key(1).as_ref(), &[1, 2, 3, 4], |_| false));
+        assert!(!f.is_selected(key(1).as_ref(), &[1, 2, 4, 4], |_| false));
+        assert!(!f.is_selected(key(1).as_ref(), &[1, 2, 3], |_| false));
+        assert!(!f.is_selected(key(1).as_ref(), &[1, 2, 3, 4, 5], |_| false));
+    }
+
+    #[test]
+    fn memcmp_past_end() {
+        let f = filter(AccountFilter {
+            memcmp: vec![Memcmp {
+                offset: 3,
+                bytes: bs58::encode([1, 2]).into_string(),
+            }],
+            ..AccountFilter::default()
+        });
+
+        assert!(f.is_selected(key(1).as_ref(), &[0, 0, 0, 1, 2], |_| false));
+        assert!(!f.is_selected(key(1).as_ref(), &[0, 0, 0, 1], |_| false));
+    }
+
+    #[test]
+    fn include_and_exclude() {
+        let f = filter(AccountFilter {
+            include: [key(1), key(2)].iter().map(ToString::to_string).collect(),
+            exclude: [key(2)].iter().map(ToString::to_string).collect(),
+            ..AccountFilter::default()
+        });
+
+        assert!(f.is_selected(key(1).as_ref(), &[], |_| false));
+        assert!(!f.is_selected(key(2).as_ref(), &[], |_| false));
+        assert!(!f.is_selected(key(3).as_ref(), &[], |_| false));
+    }
+
+    #[test]
+    fn token_filter() {
+        let f = filter(AccountFilter {
+            token: Some(TokenFilter {
+                mints: Some([key(1), key(2)].iter().map(ToString::to_string).collect()),
+                exclude_mints: [key(2)].iter().map(ToString::to_string).collect(),
+                max_amount: Some(1),
+                exclude_registry: false,
+            }),
+            ..AccountFilter::default()
+        });
+        let check = |data: &[u8]| f.is_selected(key(5).as_ref(), data, |_| false);
+
+        assert!(check(&token_data(key(1), 1, AccountState::Initialized)));
+        assert!(!check(&token_data(key(1), 2, AccountState::Initialized)));
+        assert!(!check(&token_data(key(2), 1, AccountState::Initialized)));
+        assert!(!check(&token_data(key(3), 1, AccountState::Initialized)));
+    }
+
+    #[test]
+    fn token_filter_rejects_invalid_accounts() {
+        let f = DataFilter::default_token();
+        let check = |data: &[u8]| f.is_selected(key(5).as_ref(), data, |_| false);
+
+        assert!(check(&token_data(key(1), 1, AccountState::Initialized)));
+        assert!(!check(&token_data(key(1), 1, AccountState::Uninitialized)));
+
+        let mut garbage = token_data(key(1), 1, AccountState::Initialized);
+        garbage[108] = 0xff;
+        assert!(!check(&garbage));
+
+        // Data not sized like a token account, such as a mint, is not checked
+        assert!(check(&[0; 82]));
+    }
+
+    #[test]
+    fn default_token_filter() {
+        let token = spl_token::id().to_string();
+        let selector = AccountSelector::from_config(Accounts {
+            owners: [token].into_iter().collect(),
+            startup: None,
+            filters: HashMap::new(),
+        })
+        .unwrap();
+        let f = &selector.filters[&spl_token::id().to_bytes()];
+        let registered = key(1);
+        let check = |mint, amount| {
+            f.is_selected(
+                key(5).as_ref(),
+                &token_data(mint, amount, AccountState::Initialized),
+                |m| *m == registered,
+            )
+        };
+
+        assert!(selector.uses_token_registry());
+        assert!(check(key(2), 1));
+        assert!(check(key(2), 0));
+        assert!(!check(key(2), 2));
+        assert!(!check(registered, 1));
+    }
 }
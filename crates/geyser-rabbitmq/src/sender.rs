@@ -3,6 +3,7 @@ use std::sync::Arc;
 use indexer_rabbitmq::{
     geyser::{Message, Producer, QueueType, StartupType},
     lapin::{Connection, ConnectionProperties},
+    producer::Confirms,
     suffix::Suffix,
 };
 use tokio::sync::{RwLock, RwLockReadGuard};
@@ -17,10 +18,27 @@ pub struct Sender {
     amqp: config::Amqp,
     name: String,
     startup_type: StartupType,
-    producer: RwLock<Producer>,
+    backend: Backend,
     metrics: Arc<Metrics>,
 }
 
+#[derive(Debug)]
+enum Backend {
+    Amqp(RwLock<Producer>),
+    #[cfg(feature = "kafka")]
+    Kafka(KafkaProducer),
+}
+
+#[cfg(feature = "kafka")]
+struct KafkaProducer(indexer_rabbitmq::kafka::Producer<QueueType>);
+
+#[cfg(feature = "kafka")]
+impl std::fmt::Debug for KafkaProducer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("KafkaProducer").finish_non_exhaustive()
+    }
+}
+
 impl Sender {
     pub async fn new(
         amqp: config::Amqp,
@@ -28,17 +46,59 @@ impl Sender {
         startup_type: StartupType,
         metrics: Arc<Metrics>,
     ) -> Result<Self, indexer_rabbitmq::Error> {
+        #[cfg(feature = "kafka")]
+        if let Some(ref kafka) = amqp.kafka {
+            let producer = Self::create_kafka_producer(&amqp, kafka, &name, startup_type)?;
+
+            return Ok(Self {
+                amqp,
+                name,
+                startup_type,
+                backend: Backend::Kafka(KafkaProducer(producer)),
+                metrics,
+            });
+        }
+
         let producer = Self::create_producer(&amqp, name.as_ref(), startup_type).await?;
 
         Ok(Self {
             amqp,
             name,
             startup_type,
-            producer: RwLock::new(producer),
+            backend: Backend::Amqp(RwLock::new(producer)),
             metrics,
         })
     }
 
+    #[cfg(feature = "kafka")]
+    fn create_kafka_producer(
+        amqp: &config::Amqp,
+        kafka: &config::Kafka,
+        name: &str,
+        startup_type: StartupType,
+    ) -> Result<indexer_rabbitmq::kafka::Producer<QueueType>, indexer_rabbitmq::Error> {
+        let mut client = indexer_rabbitmq::kafka::ClientConfig::new();
+
+        for (key, val) in &kafka.properties {
+            client.set(key, val);
+        }
+
+        client
+            .set("bootstrap.servers", &kafka.brokers)
+            .set("client.id", name);
+
+        Ok(indexer_rabbitmq::kafka::Producer::new(
+            &client,
+            if amqp.topics {
+                QueueType::new_topic(amqp.network, startup_type, &Suffix::Production, &[])?
+            } else {
+                QueueType::new(amqp.network, startup_type, &Suffix::Production)?
+            },
+        )?
+        .with_format(amqp.wire_format)
+        .with_compression(amqp.compression))
+    }
+
     async fn create_producer(
         amqp: &config::Amqp,
         name: impl Into<indexer_rabbitmq::lapin::types::LongString>,
@@ -53,28 +113,46 @@ impl Sender {
         )
         .await?;
 
-        Producer::new(
+        let ty = if amqp.topics {
+            QueueType::new_topic(amqp.network, startup_type, &Suffix::Production, &[])?
+        } else {
+            QueueType::new(amqp.network, startup_type, &Suffix::Production)?
+        };
+
+        Producer::with_confirms(
             &conn,
-            QueueType::new(amqp.network, startup_type, &Suffix::Production)?,
+            ty.with_priority_lanes(amqp.priority_lanes),
+            if amqp.publisher_confirms {
+                Confirms::enabled()
+            } else {
+                Confirms::Disabled
+            },
         )
         .await
+        .map(|p| {
+            p.with_format(amqp.wire_format)
+                .with_compression(amqp.compression)
+        })
     }
 
     async fn connect<'a>(
         &'a self,
+        lock: &'a RwLock<Producer>,
         prod: RwLockReadGuard<'a, Producer>,
     ) -> Result<RwLockReadGuard<'a, Producer>, indexer_rabbitmq::Error> {
         // Anti-deadlock safeguard - force the current reader to hand us their
         // lock so we can make sure it's destroyed.
         std::mem::drop(prod);
-        let mut prod = self.producer.write().await;
+        let mut prod = lock.write().await;
 
         *prod = Self::create_producer(&self.amqp, self.name.as_ref(), self.startup_type).await?;
 
         Ok(prod.downgrade())
     }
 
-    pub async fn send(&self, msg: Message) {
+    /// Publish a batch of messages, reconnecting once if publishing fails.
+    /// Returns true if the batch was published successfully.
+    pub async fn send(&self, msgs: &[Message]) -> bool {
         #[inline]
         fn log_err<E: std::fmt::Debug>(counter: &'_ Counter) -> impl FnOnce(E) + '_ {
             |err| {
@@ -84,21 +162,38 @@ impl Sender {
         }
 
         let metrics = &self.metrics;
-        let prod = self.producer.read().await;
+        let lock = match self.backend {
+            Backend::Amqp(ref l) => l,
+            #[cfg(feature = "kafka")]
+            Backend::Kafka(KafkaProducer(ref p)) => {
+                // The Kafka client reconnects on its own
+                return p
+                    .write_batch(msgs)
+                    .await
+                    .map_err(log_err(&metrics.errs))
+                    .is_ok();
+            },
+        };
+        let prod = lock.read().await;
 
-        match prod.write(&msg).await.map_err(log_err(&metrics.errs)) {
-            Ok(()) => return,
+        match prod.write_batch(msgs).await.map_err(log_err(&metrics.errs)) {
+            Ok(()) => return true,
             Err(()) => (),
         }
 
         metrics.reconnects.log(1);
-        let prod = match self.connect(prod).await.map_err(log_err(&metrics.errs)) {
+        let prod = match self
+            .connect(lock, prod)
+            .await
+            .map_err(log_err(&metrics.errs))
+        {
             Ok(p) => p,
-            Err(()) => return,
+            Err(()) => return false,
         };
 
-        match prod.write(&msg).await.map_err(log_err(&metrics.errs)) {
-            Ok(()) | Err(()) => (), // Type-level assertion that we consumed the error
-        }
+        prod.write_batch(msgs)
+            .await
+            .map_err(log_err(&metrics.errs))
+            .is_ok()
     }
 }
@@ -0,0 +1,331 @@
+use std::{collections::VecDeque, path::PathBuf, time::Duration};
+
+use hashbrown::HashSet;
+use parking_lot::RwLock;
+use serde::Deserialize;
+use solana_program::program_pack::Pack;
+use spl_token::state::Mint;
+
+use crate::{config, prelude::*};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Source {
+    /// Fetch the token list from the given URL
+    Url(String),
+    /// Read the token list from a local file
+    File(PathBuf),
+    /// Don't load a token list
+    None,
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Self::Url(
+            "https://raw.githubusercontent.com/solana-labs/token-list/main/src/tokens/solana.tokenlist.json"
+                .into(),
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenItem {
+    address: String,
+}
+
+#[derive(Deserialize)]
+struct TokenList {
+    tokens: Vec<TokenItem>,
+}
+
+/// The number of independently-locked shards the observed mint set is split
+/// into
+const OBSERVED_SHARDS: usize = 16;
+
+#[derive(Debug, Default)]
+struct Shard {
+    set: HashSet<Pubkey>,
+    /// Insertion order of `set`, oldest first
+    order: VecDeque<Pubkey>,
+}
+
+/// A bounded set of observed mints, split into shards so that recording a new
+/// mint only locks a fraction of the set.  Each shard evicts its oldest
+/// entries once full.
+#[derive(Debug)]
+struct Observed {
+    shard_capacity: usize,
+    shards: Vec<RwLock<Shard>>,
+}
+
+impl Observed {
+    fn new(capacity: usize) -> Self {
+        Self {
+            shard_capacity: ((capacity + OBSERVED_SHARDS - 1) / OBSERVED_SHARDS).max(1),
+            shards: (0..OBSERVED_SHARDS).map(|_| RwLock::default()).collect(),
+        }
+    }
+
+    fn shard(&self, key: &Pubkey) -> &RwLock<Shard> {
+        // Keys are uniformly distributed, so any byte of one is a fine hash
+        &self.shards[usize::from(key.as_ref()[0]) % self.shards.len()]
+    }
+
+    fn contains(&self, key: &Pubkey) -> bool {
+        self.shard(key).read().set.contains(key)
+    }
+
+    /// Record a key, returning the number of older keys evicted to make room
+    /// for it
+    fn insert(&self, key: Pubkey) -> usize {
+        let shard = self.shard(&key);
+
+        // Most observations are of mints already recorded, which only need a
+        // read lock
+        if shard.read().set.contains(&key) {
+            return 0;
+        }
+
+        let mut shard = shard.write();
+
+        if !shard.set.insert(key) {
+            return 0;
+        }
+
+        shard.order.push_back(key);
+
+        let mut evicted = 0;
+
+        while shard.order.len() > self.shard_capacity {
+            if let Some(old) = shard.order.pop_front() {
+                shard.set.remove(&old);
+                evicted += 1;
+            }
+        }
+
+        evicted
+    }
+}
+
+/// The set of mints known to be fungible, sourced from a token list and
+/// optionally from the mint accounts observed by the plugin
+#[derive(Debug)]
+pub struct TokenRegistry {
+    source: Source,
+    refresh: Option<Duration>,
+    heuristic: bool,
+    listed: RwLock<HashSet<Pubkey>>,
+    observed: Observed,
+}
+
+impl TokenRegistry {
+    pub fn from_config(config: config::TokenRegistry) -> Self {
+        let config::TokenRegistry {
+            source,
+            refresh_secs,
+            heuristic,
+            observed_capacity,
+        } = config;
+
+        Self {
+            source,
+            refresh: Some(Duration::from_secs(refresh_secs)).filter(|d| !d.is_zero()),
+            heuristic,
+            listed: RwLock::default(),
+            observed: Observed::new(observed_capacity),
+        }
+    }
+
+    async fn fetch(&self) -> Result<Option<HashSet<Pubkey>>> {
+        let list: TokenList = match self.source {
+            Source::Url(ref url) => reqwest::get(url)
+                .await
+                .context("HTTP request failed")?
+                .json()
+                .await
+                .context("Failed to parse response JSON")?,
+            Source::File(ref path) => {
+                let bytes = tokio::fs::read(path)
+                    .await
+                    .with_context(|| format!("Failed to read {:?}", path))?;
+
+                serde_json::from_slice(&bytes).context("Failed to parse token list file")?
+            },
+            Source::None => return Ok(None),
+        };
+
+        list.tokens
+            .into_iter()
+            .map(|TokenItem { address }| address.parse())
+            .collect::<StdResult<_, _>>()
+            .map(Some)
+            .context("Failed to convert token list")
+    }
+
+    /// Replace the listed token set with the current contents of the
+    /// configured source
+    pub async fn load(&self) -> Result<()> {
+        if let Some(tokens) = self.fetch().await? {
+            info!("Loaded {} token(s) from token registry", tokens.len());
+
+            *self.listed.write() = tokens;
+        }
+
+        Ok(())
+    }
+
+    /// Periodically reload the token list, logging any errors encountered.
+    /// Returns immediately if refreshing is disabled.
+    pub async fn refresh(&self, on_err: impl Fn(Error)) {
+        let period = match (self.refresh, &self.source) {
+            (Some(p), Source::Url(_) | Source::File(_)) => p,
+            (None, _) | (_, Source::None) => return,
+        };
+
+        let mut interval = tokio::time::interval(period);
+        // The first tick completes immediately, and the initial load has
+        // already been performed by this point
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.load().await {
+                on_err(e.context("Failed to refresh token registry"));
+            }
+        }
+    }
+
+    /// Record the mint described by an account update as fungible if it
+    /// looks like one, i.e. it has nonzero decimals or a supply greater than
+    /// one.  Does nothing unless the heuristic is enabled.
+    ///
+    /// Only mints seen in such a state are remembered, and once the bounded
+    /// set of them is full the oldest are forgotten.  A mint that has not been
+    /// observed, or has been forgotten, is not known to be fungible until its
+    /// mint account is updated again, so filtering with the heuristic is best
+    /// effort.  Returns the number of mints forgotten to make room.
+    pub fn observe(&self, key: &[u8], owner: &[u8], data: &[u8]) -> usize {
+        if !self.heuristic || data.len() != Mint::LEN || owner != spl_token::id().as_ref() {
+            return 0;
+        }
+
+        let mint = match Mint::unpack(data) {
+            Ok(m) => m,
+            Err(_) => return 0,
+        };
+
+        if mint.decimals == 0 && mint.supply <= 1 {
+            return 0;
+        }
+
+        let key = match <[u8; 32]>::try_from(key) {
+            Ok(k) => Pubkey::new_from_array(k),
+            Err(_) => return 0,
+        };
+
+        self.observed.insert(key)
+    }
+
+    /// Returns true if the given mint is known to be fungible
+    pub fn contains(&self, mint: &Pubkey) -> bool {
+        self.listed.read().contains(mint) || self.observed.contains(mint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::program_option::COption;
+
+    use super::*;
+
+    fn key(shard: u8, n: u8) -> Pubkey {
+        let mut key = [n; 32];
+        key[0] = shard;
+
+        Pubkey::new_from_array(key)
+    }
+
+    fn mint(decimals: u8, supply: u64) -> Vec<u8> {
+        let mut data = vec![0; Mint::LEN];
+        Mint::pack(
+            Mint {
+                mint_authority: COption::None,
+                supply,
+                decimals,
+                is_initialized: true,
+                freeze_authority: COption::None,
+            },
+            &mut data,
+        )
+        .unwrap();
+
+        data
+    }
+
+    fn registry(heuristic: bool, observed_capacity: usize) -> TokenRegistry {
+        TokenRegistry::from_config(config::TokenRegistry {
+            source: Source::None,
+            refresh_secs: 0,
+            heuristic,
+            observed_capacity,
+        })
+    }
+
+    #[test]
+    fn heuristic() {
+        let reg = registry(true, 1024);
+        let token = spl_token::id();
+        let observe = |k: Pubkey, data: &[u8]| reg.observe(k.as_ref(), token.as_ref(), data);
+
+        observe(key(0, 1), &mint(0, 1));
+        observe(key(0, 2), &mint(0, 0));
+        observe(key(0, 3), &mint(6, 1));
+        observe(key(0, 4), &mint(0, 2));
+        reg.observe(key(0, 5).as_ref(), key(9, 9).as_ref(), &mint(6, 100));
+
+        assert!(!reg.contains(&key(0, 1)));
+        assert!(!reg.contains(&key(0, 2)));
+        assert!(reg.contains(&key(0, 3)));
+        assert!(reg.contains(&key(0, 4)));
+        // Only mints owned by the token program are considered
+        assert!(!reg.contains(&key(0, 5)));
+    }
+
+    #[test]
+    fn heuristic_disabled() {
+        let reg = registry(false, 1024);
+
+        reg.observe(key(0, 1).as_ref(), spl_token::id().as_ref(), &mint(6, 100));
+
+        assert!(!reg.contains(&key(0, 1)));
+    }
+
+    #[test]
+    fn sharded_eviction() {
+        let observed = Observed::new(OBSERVED_SHARDS * 2);
+
+        assert_eq!(observed.insert(key(0, 1)), 0);
+        assert_eq!(observed.insert(key(0, 2)), 0);
+        assert_eq!(observed.insert(key(1, 1)), 0);
+        // Reinserting a key does not refresh or evict anything
+        assert_eq!(observed.insert(key(0, 1)), 0);
+
+        // Filling shard 0 evicts its oldest key, leaving shard 1 alone
+        assert_eq!(observed.insert(key(0, 3)), 1);
+        assert!(!observed.contains(&key(0, 1)));
+        assert!(observed.contains(&key(0, 2)));
+        assert!(observed.contains(&key(0, 3)));
+        assert!(observed.contains(&key(1, 1)));
+    }
+
+    #[test]
+    fn small_capacity() {
+        let observed = Observed::new(1);
+
+        assert_eq!(observed.insert(key(0, 1)), 0);
+        assert_eq!(observed.insert(key(1, 1)), 0);
+        assert_eq!(observed.insert(key(0, 2)), 1);
+        assert!(observed.contains(&key(1, 1)));
+    }
+}
@@ -0,0 +1,475 @@
+use std::{
+    collections::VecDeque,
+    fs::{File, OpenOptions},
+    io::{BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use indexer_rabbitmq::geyser::Message;
+
+use crate::{config, prelude::*};
+
+const EXTENSION: &str = "wal";
+const DEFAULT_SEGMENT_BYTES: u64 = 64 * 1024 * 1024;
+const DEFAULT_MAX_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Size of the length prefix preceding each record
+const HEADER_LEN: u64 = 4;
+
+#[derive(Debug)]
+struct Segment {
+    id: u64,
+    bytes: u64,
+    records: usize,
+}
+
+/// A position within the front segment of the log
+#[derive(Debug, Default, Clone, Copy)]
+struct Cursor {
+    offset: u64,
+    records: usize,
+}
+
+/// An on-disk FIFO of messages, split across numbered segment files.  Each
+/// record is a little-endian `u32` length followed by a MsgPack-encoded
+/// message.  Segments left over from a previous run are replayed before any
+/// new messages.
+#[derive(Debug)]
+pub struct Wal {
+    dir: PathBuf,
+    segment_bytes: u64,
+    max_bytes: u64,
+    segments: VecDeque<Segment>,
+    writer: Option<BufWriter<File>>,
+    read: Cursor,
+    peeked: Option<Cursor>,
+    next_id: u64,
+    pending: usize,
+    bytes: u64,
+}
+
+fn segment_path(dir: &Path, id: u64) -> PathBuf {
+    dir.join(format!("{:020}.{}", id, EXTENSION))
+}
+
+/// Read a single record no longer than `max_len` bytes, returning `None` at
+/// the end of the file or at a truncated or corrupt trailing record
+fn read_record(r: &mut impl Read, max_len: u64) -> Result<Option<Vec<u8>>> {
+    let mut len = [0_u8; 4];
+
+    match r.read_exact(&mut len) {
+        Ok(()) => (),
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+
+    let len = u32::from_le_bytes(len);
+
+    // A length this large can only come from a torn or corrupt write, so
+    // don't trust it with an allocation
+    if u64::from(len) > max_len {
+        return Ok(None);
+    }
+
+    let mut buf = vec![0_u8; len as usize];
+
+    match r.read_exact(&mut buf) {
+        Ok(()) => Ok(Some(buf)),
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Count the complete records in a segment, truncating any partial record
+/// left by an unclean shutdown
+fn recover_segment(path: &Path, max_len: u64) -> Result<(u64, usize)> {
+    let mut r = BufReader::new(File::open(path)?);
+    let mut bytes = 0;
+    let mut records = 0;
+
+    while let Some(rec) = read_record(&mut r, max_len)? {
+        bytes += HEADER_LEN + rec.len() as u64;
+        records += 1;
+    }
+
+    let file = OpenOptions::new().write(true).open(path)?;
+    if file.metadata()?.len() != bytes {
+        warn!(
+            "Truncating incomplete or corrupt record at end of {:?}",
+            path
+        );
+        file.set_len(bytes)?;
+        file.sync_all()?;
+    }
+
+    Ok((bytes, records))
+}
+
+/// Flush the creation or removal of a segment file to disk
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> Result<()> {
+    File::open(dir)
+        .and_then(|d| d.sync_all())
+        .with_context(|| format!("Failed to sync WAL directory {:?}", dir))
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_: &Path) -> Result<()> {
+    Ok(())
+}
+
+impl Wal {
+    pub fn open(config: config::Wal) -> Result<Self> {
+        let config::Wal {
+            dir,
+            segment_bytes,
+            max_bytes,
+        } = config;
+        let segment_bytes = segment_bytes.unwrap_or(DEFAULT_SEGMENT_BYTES);
+        let max_bytes = max_bytes.unwrap_or(DEFAULT_MAX_BYTES);
+
+        if segment_bytes <= HEADER_LEN || segment_bytes > max_bytes {
+            bail!("WAL segment size must be nonzero and no larger than the WAL size cap");
+        }
+
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create WAL directory {:?}", dir))?;
+
+        let mut ids = std::fs::read_dir(&dir)
+            .with_context(|| format!("Failed to read WAL directory {:?}", dir))?
+            .filter_map(|e| {
+                let path = e.ok()?.path();
+
+                if path.extension()? != EXTENSION {
+                    return None;
+                }
+
+                path.file_stem()?.to_str()?.parse::<u64>().ok()
+            })
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+
+        let mut segments = VecDeque::with_capacity(ids.len());
+        for id in ids {
+            let path = segment_path(&dir, id);
+            let (bytes, records) = recover_segment(&path, segment_bytes - HEADER_LEN)
+                .with_context(|| format!("Failed to recover WAL segment {:?}", path))?;
+
+            if records == 0 {
+                std::fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove WAL segment {:?}", path))?;
+                continue;
+            }
+
+            segments.push_back(Segment { id, bytes, records });
+        }
+
+        let pending = segments.iter().map(|s| s.records).sum();
+        let bytes = segments.iter().map(|s| s.bytes).sum();
+
+        if pending > 0 {
+            info!(
+                "Recovered {} message(s) from {} WAL segment(s)",
+                pending,
+                segments.len()
+            );
+        }
+
+        Ok(Self {
+            next_id: segments.back().map_or(0, |s| s.id + 1),
+            dir,
+            segment_bytes,
+            max_bytes,
+            segments,
+            writer: None,
+            read: Cursor::default(),
+            peeked: None,
+            pending,
+            bytes,
+        })
+    }
+
+    /// The number of messages currently stored in the log
+    #[inline]
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
+
+    /// The largest record that fits in a single segment
+    #[inline]
+    fn max_record_len(&self) -> u64 {
+        self.segment_bytes - HEADER_LEN
+    }
+
+    /// Flush any buffered writes and wait for them to reach the disk
+    pub fn sync(&mut self) -> Result<()> {
+        if let Some(ref mut w) = self.writer {
+            w.flush().context("Failed to flush WAL segment")?;
+            w.get_ref()
+                .sync_data()
+                .context("Failed to sync WAL segment")?;
+        }
+
+        Ok(())
+    }
+
+    fn roll(&mut self) -> Result<()> {
+        self.sync()?;
+        self.writer = None;
+
+        let id = self.next_id;
+        let path = segment_path(&self.dir, id);
+        let file = OpenOptions::new()
+            .create_new(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to create WAL segment {:?}", path))?;
+
+        self.next_id += 1;
+        self.segments.push_back(Segment {
+            id,
+            bytes: 0,
+            records: 0,
+        });
+        self.writer = Some(BufWriter::new(file));
+
+        sync_dir(&self.dir)
+    }
+
+    /// Append a message to the end of the log.  If this pushes the log over
+    /// its size cap, the oldest segments are discarded and the number of
+    /// messages lost is returned.
+    ///
+    /// Appended messages are not guaranteed to be on disk until the next call
+    /// to [`sync`](Self::sync) or [`peek`](Self::peek).
+    pub fn append(&mut self, msg: &Message) -> Result<usize> {
+        let mut buf = Vec::new();
+        rmp_serde::encode::write(&mut buf, msg).context("Failed to encode message")?;
+
+        let len: u32 = buf.len().try_into().context("Message too large for WAL")?;
+
+        if u64::from(len) > self.max_record_len() {
+            bail!("Message too large for a WAL segment ({} bytes)", len);
+        }
+
+        let rec_bytes = HEADER_LEN + u64::from(len);
+
+        let roll = match (self.segments.back(), &self.writer) {
+            (Some(s), Some(_)) => s.bytes > 0 && s.bytes + rec_bytes > self.segment_bytes,
+            (Some(_), None) | (None, _) => true,
+        };
+
+        if roll {
+            self.roll()?;
+        }
+
+        let writer = self.writer.as_mut().unwrap_or_else(|| unreachable!());
+        writer.write_all(&len.to_le_bytes())?;
+        writer.write_all(&buf)?;
+
+        let seg = self.segments.back_mut().unwrap_or_else(|| unreachable!());
+        seg.bytes += rec_bytes;
+        seg.records += 1;
+        self.pending += 1;
+        self.bytes += rec_bytes;
+
+        let mut dropped = 0;
+        while self.bytes > self.max_bytes && self.segments.len() > 1 {
+            dropped += self.discard_front()?;
+        }
+
+        Ok(dropped)
+    }
+
+    /// Delete the front segment, returning the number of unread messages it
+    /// contained
+    pub fn discard_front(&mut self) -> Result<usize> {
+        let seg = match self.segments.pop_front() {
+            Some(s) => s,
+            None => return Ok(0),
+        };
+
+        if self.segments.is_empty() {
+            self.writer = None;
+        }
+
+        let unread = seg.records - self.read.records;
+        self.pending -= unread;
+        self.bytes -= seg.bytes;
+        self.read = Cursor::default();
+        self.peeked = None;
+
+        let path = segment_path(&self.dir, seg.id);
+        std::fs::remove_file(&path)
+            .with_context(|| format!("Failed to remove WAL segment {:?}", path))?;
+        sync_dir(&self.dir)?;
+
+        Ok(unread)
+    }
+
+    /// Read up to `max` messages from the front of the log without removing
+    /// them.  Call [`commit`](Self::commit) once they have been handled.
+    pub fn peek(&mut self, max: usize, out: &mut Vec<Message>) -> Result<()> {
+        let seg = match self.segments.front() {
+            Some(s) if self.pending > 0 => s,
+            _ => return Ok(()),
+        };
+
+        let (id, records) = (seg.id, seg.records);
+        self.sync()?;
+
+        let path = segment_path(&self.dir, id);
+        let mut file =
+            File::open(&path).with_context(|| format!("Failed to open WAL segment {:?}", path))?;
+        file.seek(SeekFrom::Start(self.read.offset))?;
+        let mut r = BufReader::new(file);
+
+        let mut cursor = self.read;
+        while cursor.records < records && out.len() < max {
+            let rec = read_record(&mut r, self.max_record_len())?
+                .ok_or_else(|| anyhow!("WAL segment {:?} ended unexpectedly", path))?;
+
+            cursor.offset += HEADER_LEN + rec.len() as u64;
+            cursor.records += 1;
+            out.push(rmp_serde::from_slice(&rec).context("Failed to decode WAL message")?);
+        }
+
+        self.peeked = Some(cursor);
+
+        Ok(())
+    }
+
+    /// Remove the messages returned by the last call to
+    /// [`peek`](Self::peek)
+    pub fn commit(&mut self) -> Result<()> {
+        let cursor = match self.peeked.take() {
+            Some(c) => c,
+            None => return Ok(()),
+        };
+
+        self.pending -= cursor.records - self.read.records;
+        self.read = cursor;
+
+        if self
+            .segments
+            .front()
+            .map_or(false, |s| s.records == self.read.records)
+        {
+            self.discard_front()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexer_rabbitmq::geyser::SlotStatus;
+
+    use super::*;
+
+    fn open(name: &str, fresh: bool) -> (PathBuf, Wal) {
+        let dir = std::env::temp_dir().join(format!("geyser-wal-{}-{}", name, std::process::id()));
+
+        if fresh {
+            std::fs::remove_dir_all(&dir).ok();
+        }
+
+        let wal = Wal::open(config::Wal {
+            dir: dir.clone(),
+            segment_bytes: Some(4096),
+            max_bytes: None,
+        })
+        .unwrap();
+
+        (dir, wal)
+    }
+
+    fn slot(slot: u64) -> Message {
+        Message::SlotStatus {
+            slot,
+            parent: None,
+            status: SlotStatus::Processed,
+        }
+    }
+
+    fn read_slots(wal: &mut Wal) -> Vec<u64> {
+        let mut out = vec![];
+        wal.peek(usize::MAX, &mut out).unwrap();
+        wal.commit().unwrap();
+
+        out.into_iter()
+            .map(|m| match m {
+                Message::SlotStatus { slot, .. } => slot,
+                m => panic!("Unexpected message {:?}", m),
+            })
+            .collect()
+    }
+
+    fn tear(dir: &Path, bytes: &[u8]) -> u64 {
+        let path = segment_path(dir, 0);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(bytes).unwrap();
+
+        file.metadata().unwrap().len()
+    }
+
+    #[test]
+    fn recover_torn_write() {
+        let (dir, mut wal) = open("torn", true);
+
+        for i in 0..3 {
+            wal.append(&slot(i)).unwrap();
+        }
+        wal.sync().unwrap();
+        std::mem::drop(wal);
+
+        // A length header followed by only part of the record it describes
+        let torn = tear(&dir, &[16, 0, 0, 0, 0x93, 0x01]);
+
+        let (dir, mut wal) = open("torn", false);
+        assert_eq!(wal.pending(), 3);
+        assert!(std::fs::metadata(segment_path(&dir, 0)).unwrap().len() < torn);
+
+        wal.append(&slot(3)).unwrap();
+        assert_eq!(read_slots(&mut wal), [0, 1, 2]);
+        assert_eq!(read_slots(&mut wal), [3]);
+        assert_eq!(wal.pending(), 0);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn recover_oversized_length() {
+        let (dir, mut wal) = open("oversized", true);
+
+        wal.append(&slot(0)).unwrap();
+        wal.sync().unwrap();
+        std::mem::drop(wal);
+
+        // A corrupt header claiming a record far larger than a segment
+        tear(&dir, &[0xff, 0xff, 0xff, 0xff, 0x00]);
+
+        let (dir, mut wal) = open("oversized", false);
+        assert_eq!(wal.pending(), 1);
+        assert_eq!(read_slots(&mut wal), [0]);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn reject_oversized_message() {
+        let (dir, mut wal) = open("reject", true);
+
+        let msg = Message::InstructionNotify {
+            program: Pubkey::default(),
+            data: vec![0; 8192],
+            accounts: vec![],
+        };
+
+        assert!(wal.append(&msg).is_err());
+        assert_eq!(wal.pending(), 0);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -167,7 +167,7 @@ fn main() {
 
         // TODO: db_ty indicates if any actions that mutate the database can be run
         let (db, _db_ty) =
-            db::connect(db, db::ConnectMode::Read).context("Failed to connect to Postgres")?;
+            db::connect(&db, db::ConnectMode::Read).context("Failed to connect to Postgres")?;
         let db = Arc::new(db);
         let search = search.into_client();
         let rpc = RpcClient::new(solana_endpoint);
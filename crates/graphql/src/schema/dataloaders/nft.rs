@@ -83,6 +83,7 @@ impl TryBatchFn<PublicKey<Nft>, Option<NftOwner>> for Batcher {
                         current_metadata_owners::owner_address,
                         current_metadata_owners::token_account_address,
                         current_metadata_owners::slot,
+                        current_metadata_owners::write_version,
                     ),
                 ))
                 .load(&conn)
@@ -108,6 +108,7 @@ impl<'a> TryFrom<models::BidReceipt<'a>> for BidReceipt {
             trade_state_bump,
             created_at,
             canceled_at,
+            ..
         }: models::BidReceipt,
     ) -> Result<Self, Self::Error> {
         Ok(Self {
@@ -12,6 +12,7 @@ pub mod marketplace;
 pub mod nft;
 pub mod profile;
 pub mod purchase_receipt;
+pub mod slot;
 pub mod stats;
 pub mod store_creator;
 pub mod storefront;
@@ -1,11 +1,11 @@
 use indexer_core::{
     assets::{proxy_url, AssetIdentifier, ImageSize},
-    db::queries,
+    db::{custom_types::SlotStatusEnum, queries, tables::slots},
     util::unix_timestamp,
 };
 use objects::{
     auction_house::AuctionHouse, bid_receipt::BidReceipt, listing_receipt::ListingReceipt,
-    profile::TwitterProfile, purchase_receipt::PurchaseReceipt, wallet::Wallet,
+    profile::TwitterProfile, purchase_receipt::PurchaseReceipt, slot::Commitment, wallet::Wallet,
 };
 use reqwest::Url;
 use scalars::{PublicKey, U64};
@@ -158,6 +158,7 @@ impl<'a> From<(Option<String>, models::MetadataCreator<'a>)> for NftCreator {
                 share,
                 verified,
                 position,
+                ..
             },
         ): (Option<String>, models::MetadataCreator),
     ) -> Self {
@@ -479,6 +480,24 @@ If no value is provided, it will return XSmall")))]
             Ok(None)
         }
     }
+
+    #[graphql(description = "The commitment of the slot the NFT's metadata was last written in")]
+    pub fn commitment(&self, ctx: &AppContext) -> FieldResult<Option<Commitment>> {
+        let slot = match self.slot {
+            Some(s) => i64::from(s),
+            None => return Ok(None),
+        };
+
+        let conn = ctx.shared.db.get()?;
+        let status: Option<SlotStatusEnum> = slots::table
+            .select(slots::status)
+            .filter(slots::slot.eq(slot))
+            .first(&conn)
+            .optional()
+            .context("Failed to load slot status")?;
+
+        Ok(status.map(Into::into))
+    }
 }
 
 #[derive(Debug, Clone)]
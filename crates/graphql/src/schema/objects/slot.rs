@@ -0,0 +1,62 @@
+use indexer_core::db::custom_types::SlotStatusEnum;
+use juniper::GraphQLEnum;
+use scalars::U64;
+
+use super::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, GraphQLEnum)]
+/// The commitment level of a slot
+pub enum Commitment {
+    /// The slot was processed but may still be abandoned
+    Processed,
+    /// The slot was confirmed by a supermajority of the cluster
+    Confirmed,
+    /// The slot was rooted and is final
+    Rooted,
+}
+
+impl From<SlotStatusEnum> for Commitment {
+    fn from(status: SlotStatusEnum) -> Self {
+        match status {
+            SlotStatusEnum::Processed => Self::Processed,
+            SlotStatusEnum::Confirmed => Self::Confirmed,
+            SlotStatusEnum::Rooted => Self::Rooted,
+        }
+    }
+}
+
+impl From<Commitment> for SlotStatusEnum {
+    fn from(commitment: Commitment) -> Self {
+        match commitment {
+            Commitment::Processed => Self::Processed,
+            Commitment::Confirmed => Self::Confirmed,
+            Commitment::Rooted => Self::Rooted,
+        }
+    }
+}
+
+#[derive(Debug, Clone, GraphQLObject)]
+/// A slot and the highest commitment status the indexer has observed for it
+pub struct Slot {
+    pub slot: U64,
+    pub parent_slot: Option<U64>,
+    pub commitment: Commitment,
+}
+
+impl TryFrom<models::Slot> for Slot {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(
+        models::Slot {
+            slot,
+            parent_slot,
+            status,
+        }: models::Slot,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            slot: slot.try_into()?,
+            parent_slot: parent_slot.map(TryInto::try_into).transpose()?,
+            commitment: status.into(),
+        })
+    }
+}
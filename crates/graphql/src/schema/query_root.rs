@@ -1,4 +1,5 @@
 use indexer_core::db::{
+    custom_types::SlotStatusEnum,
     queries::{self, feed_event::EventType},
     tables::twitter_handle_name_services,
 };
@@ -16,14 +17,15 @@ use objects::{
     marketplace::Marketplace,
     nft::{MetadataJson, Nft, NftActivity, NftCount, NftCreator},
     profile::TwitterProfile,
+    slot::{Commitment, Slot},
     storefront::{Storefront, StorefrontColumns},
     wallet::Wallet,
 };
-use scalars::PublicKey;
+use scalars::{PublicKey, U64};
 use serde_json::Value;
 use tables::{
     auction_caches, auction_datas, auction_datas_ext, bid_receipts, graph_connections,
-    metadata_jsons, metadatas, store_config_jsons, storefronts, wallet_totals,
+    metadata_jsons, metadatas, slots, store_config_jsons, storefronts, wallet_totals,
 };
 
 use super::prelude::*;
@@ -449,6 +451,43 @@ impl QueryRoot {
             .map_err(Into::into)
     }
 
+    #[graphql(description = "A slot and its commitment status, if the indexer has seen it")]
+    fn slot(
+        &self,
+        context: &AppContext,
+        #[graphql(description = "The slot number")] slot: U64,
+    ) -> FieldResult<Option<Slot>> {
+        let conn = context.shared.db.get().context("failed to connect to db")?;
+
+        let row: Option<models::Slot> = slots::table
+            .select(slots::all_columns)
+            .filter(slots::slot.eq(i64::try_from(u64::from(slot))?))
+            .first(&conn)
+            .optional()
+            .context("Failed to load slot")?;
+
+        row.map(TryInto::try_into).transpose().map_err(Into::into)
+    }
+
+    #[graphql(description = "The highest slot the indexer has seen at or above a commitment")]
+    fn latest_slot(
+        &self,
+        context: &AppContext,
+        #[graphql(description = "The minimum commitment of the slot")] commitment: Commitment,
+    ) -> FieldResult<Option<Slot>> {
+        let conn = context.shared.db.get().context("failed to connect to db")?;
+
+        let row: Option<models::Slot> = slots::table
+            .select(slots::all_columns)
+            .filter(slots::status.ge(SlotStatusEnum::from(commitment)))
+            .order(slots::slot.desc())
+            .first(&conn)
+            .optional()
+            .context("Failed to load latest slot")?;
+
+        row.map(TryInto::try_into).transpose().map_err(Into::into)
+    }
+
     fn storefronts(&self, context: &AppContext) -> FieldResult<Vec<Storefront>> {
         let conn = context.shared.db.get()?;
         let rows: Vec<models::Storefront> = storefronts::table
@@ -56,6 +56,12 @@ impl From<u64> for U64 {
     }
 }
 
+impl From<U64> for u64 {
+    fn from(U64(value): U64) -> Self {
+        value
+    }
+}
+
 impl TryFrom<i64> for U64 {
     type Error = std::num::TryFromIntError;
 
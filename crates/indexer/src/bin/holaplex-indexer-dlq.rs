@@ -0,0 +1,350 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+};
+
+use indexer_core::{
+    clap,
+    clap::{Args, Parser, Subcommand},
+    prelude::*,
+};
+use indexer_rabbitmq::{
+    dlq::{DeadLetter, DeadLetterQueue, Reason},
+    geyser, http_indexer, search_indexer,
+    suffix::Suffix,
+    QueueType,
+};
+use serde::Serialize;
+
+#[derive(Debug, Parser)]
+struct Opts {
+    /// The address of an AMQP server to connect to
+    #[clap(long, env)]
+    amqp_url: String,
+
+    #[clap(subcommand)]
+    cmd: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Print a summary of each dead letter
+    List(Target),
+    /// Write each dead letter as a line of JSON
+    Export {
+        /// The file to write to.  Defaults to standard output.
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+
+        #[clap(flatten)]
+        target: Target,
+    },
+    /// Redeliver dead letters to their live queue
+    Republish {
+        /// Only print the dead letters that would be republished
+        #[clap(long)]
+        dry_run: bool,
+
+        #[clap(flatten)]
+        target: Target,
+    },
+}
+
+#[derive(Debug, Clone, Copy, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "kebab-case")]
+enum QueueKind {
+    Geyser,
+    Http,
+    Search,
+}
+
+#[derive(Debug, Args)]
+struct Target {
+    /// The kind of queue to inspect dead letters for
+    #[clap(long)]
+    queue: QueueKind,
+
+    /// The network of the Geyser queue
+    #[clap(long, env, required_if_eq("queue", "geyser"))]
+    network: Option<geyser::Network>,
+
+    /// The startup type of the Geyser queue
+    #[clap(long, env, default_value_t = geyser::StartupType::Normal)]
+    startup: geyser::StartupType,
+
+    /// Use the topic exchange queue for the given Geyser programs
+    #[clap(long, env, use_value_delimiter(true))]
+    topics: Option<Vec<String>>,
+
+    /// The ID of the indexer sending events to the HTTP or search queue
+    #[clap(long, env, required_if_eq_any(&[("queue", "http"), ("queue", "search")]))]
+    sender: Option<String>,
+
+    /// The entity type of the HTTP queue
+    #[clap(long, env, required_if_eq("queue", "http"))]
+    entity: Option<http_indexer::EntityId>,
+
+    #[clap(flatten)]
+    filter: Filter,
+
+    #[clap(flatten)]
+    queue_suffix: Suffix,
+}
+
+#[derive(Debug, Args)]
+struct Filter {
+    /// Only include dead letters that failed for this reason
+    #[clap(long)]
+    reason: Option<Reason>,
+
+    /// Only include dead letters whose root cause error contains this string
+    #[clap(long)]
+    root_cause: Option<String>,
+
+    /// Only include dead letters whose decoded JSON contains this string
+    #[clap(long)]
+    contains: Option<String>,
+
+    /// Only include dead letters that failed to decode
+    #[clap(long, conflicts_with("contains"))]
+    undecodable: bool,
+
+    /// Stop after this many matching dead letters
+    #[clap(short = 'n', long)]
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct Record<M> {
+    reason: Option<String>,
+    deaths: u64,
+    processor: Option<String>,
+    root_cause: Option<String>,
+    message: Option<M>,
+    error: Option<String>,
+}
+
+enum Action {
+    List,
+    Export(Box<dyn Write>),
+    Republish { dry_run: bool },
+}
+
+fn main() {
+    indexer_core::run(|| {
+        let Opts { amqp_url, cmd } = Opts::parse();
+
+        let (action, target) = match cmd {
+            Command::List(target) => (Action::List, target),
+            Command::Export { output, target } => (
+                Action::Export(match output {
+                    Some(p) => {
+                        Box::new(BufWriter::new(File::create(&p).with_context(|| {
+                            format!("Failed to create output file {:?}", p)
+                        })?))
+                    },
+                    None => Box::new(io::stdout()),
+                }),
+                target,
+            ),
+            Command::Republish { dry_run, target } => (Action::Republish { dry_run }, target),
+        };
+
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("Failed to initialize async runtime")?
+            .block_on(dispatch(amqp_url, action, target))
+    });
+}
+
+async fn dispatch(amqp_url: String, action: Action, target: Target) -> Result<()> {
+    let Target {
+        queue,
+        network,
+        startup,
+        topics,
+        sender,
+        entity,
+        filter,
+        queue_suffix,
+    } = target;
+
+    let conn = holaplex_indexer::amqp_connect(amqp_url, env!("CARGO_BIN_NAME")).await?;
+
+    match queue {
+        QueueKind::Geyser => {
+            let network = network.ok_or_else(|| anyhow!("Missing Geyser network"))?;
+            let ty = match topics {
+                Some(t) => geyser::QueueType::new_topic(network, startup, &queue_suffix, &t)?,
+                None => geyser::QueueType::new(network, startup, &queue_suffix)?,
+            };
+
+            run(&conn, ty, action, filter).await
+        },
+        QueueKind::Http => {
+            use http_indexer::{EntityId, MetadataJson, StoreConfig};
+
+            let sender = sender.ok_or_else(|| anyhow!("Missing sender ID"))?;
+
+            match entity.ok_or_else(|| anyhow!("Missing HTTP entity type"))? {
+                EntityId::MetadataJson => {
+                    let ty = http_indexer::QueueType::<MetadataJson>::new(&sender, &queue_suffix)?;
+                    run(&conn, ty, action, filter).await
+                },
+                EntityId::StoreConfig => {
+                    let ty = http_indexer::QueueType::<StoreConfig>::new(&sender, &queue_suffix)?;
+                    run(&conn, ty, action, filter).await
+                },
+            }
+        },
+        QueueKind::Search => {
+            let sender = sender.ok_or_else(|| anyhow!("Missing sender ID"))?;
+            let ty = search_indexer::QueueType::new(&sender, &queue_suffix)?;
+
+            run(&conn, ty, action, filter).await
+        },
+    }
+}
+
+fn to_record<Q: QueueType>(msg: &DeadLetter<Q>) -> Record<Q::Message>
+where
+    Q::Message: for<'a> serde::Deserialize<'a>,
+{
+    let (message, error) = match msg.decode() {
+        Ok(m) => (Some(m), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+
+    Record {
+        reason: msg.reason().map(|r| r.to_string()),
+        deaths: msg.deaths(),
+        processor: msg.processor(),
+        root_cause: msg.root_cause(),
+        message,
+        error,
+    }
+}
+
+fn is_match<M: Serialize>(
+    filter: &Filter,
+    reason: Option<Reason>,
+    record: &Record<M>,
+) -> Result<bool> {
+    let Filter {
+        reason: want_reason,
+        root_cause,
+        contains,
+        undecodable,
+        limit: _,
+    } = filter;
+
+    if want_reason.map_or(false, |r| reason != Some(r)) {
+        return Ok(false);
+    }
+
+    if let Some(ref root_cause) = root_cause {
+        if !record
+            .root_cause
+            .as_ref()
+            .map_or(false, |c| c.contains(root_cause.as_str()))
+        {
+            return Ok(false);
+        }
+    }
+
+    if *undecodable {
+        return Ok(record.message.is_none());
+    }
+
+    if let Some(ref contains) = contains {
+        let json = match record.message {
+            Some(ref m) => serde_json::to_string(m).context("Failed to serialize dead letter")?,
+            None => return Ok(false),
+        };
+
+        return Ok(json.contains(contains.as_str()));
+    }
+
+    Ok(true)
+}
+
+async fn run<Q: QueueType>(
+    conn: &indexer_rabbitmq::lapin::Connection,
+    ty: Q,
+    mut action: Action,
+    filter: Filter,
+) -> Result<()>
+where
+    Q::Message: std::fmt::Debug + Serialize + for<'a> serde::Deserialize<'a>,
+{
+    let dlq = DeadLetterQueue::new(conn, &ty)
+        .await
+        .context("Failed to open dead-letter queue")?;
+
+    info!("Reading dead letters from {:?}", dlq.queue());
+
+    let mut seen = 0_usize;
+    let mut matched = 0_usize;
+
+    while filter.limit.map_or(true, |l| matched < l) {
+        let msg = match dlq.get().await.context("Failed to read dead letter")? {
+            Some(m) => m,
+            None => break,
+        };
+
+        seen += 1;
+
+        let record = to_record(&msg);
+
+        if !is_match(&filter, msg.reason(), &record)? {
+            continue;
+        }
+
+        matched += 1;
+
+        match action {
+            Action::List => {
+                let summary = match (&record.message, &record.error) {
+                    (Some(m), _) => format!("{:?}", m),
+                    (None, Some(e)) => format!("<failed to decode: {}>", e),
+                    (None, None) => unreachable!(),
+                };
+
+                println!(
+                    "{}\t{}\t{}\t{}\t{}\t{:.200}",
+                    seen,
+                    record.reason.as_deref().unwrap_or("unknown"),
+                    record.deaths,
+                    record.processor.as_deref().unwrap_or("-"),
+                    record.root_cause.as_deref().unwrap_or("-"),
+                    summary
+                );
+            },
+            Action::Export(ref mut w) => {
+                serde_json::to_writer(&mut *w, &record).context("Failed to write dead letter")?;
+                writeln!(w).context("Failed to write dead letter")?;
+            },
+            Action::Republish { dry_run: true } => {
+                println!("Would republish dead letter {}", seen);
+            },
+            Action::Republish { dry_run: false } => {
+                dlq.republish(msg)
+                    .await
+                    .context("Failed to republish dead letter")?;
+            },
+        }
+    }
+
+    if let Action::Export(ref mut w) = action {
+        w.flush().context("Failed to flush output")?;
+    }
+
+    eprintln!("Matched {} of {} dead letter(s)", matched, seen);
+
+    // Any dead letters not republished are returned to the queue
+    dlq.close()
+        .await
+        .context("Failed to close dead-letter queue")
+}
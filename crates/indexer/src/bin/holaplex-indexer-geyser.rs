@@ -1,8 +1,11 @@
 use std::{collections::HashSet, sync::Arc};
 
-use holaplex_indexer::geyser::{Client, ClientArgs, IgnoreType};
+use holaplex_indexer::{
+    geyser::{Client, ClientArgs, Dedup, IgnoreType, Publishers, SlotBuffer},
+    publish::ProducerArgs,
+};
 use indexer_core::{clap, prelude::*};
-use indexer_rabbitmq::{geyser, http_indexer, search_indexer, suffix::Suffix};
+use indexer_rabbitmq::{geyser, http_indexer, lapin, search_indexer, suffix::Suffix};
 
 #[derive(Debug, clap::Args)]
 struct Args {
@@ -25,9 +28,80 @@ struct Args {
     #[clap(long, env, use_value_delimiter(true))]
     ignore_on_startup: Option<Vec<IgnoreType>>,
 
+    /// Only apply live account updates once their slot has been confirmed
+    ///
+    /// Updates are buffered per slot until a confirmed or rooted slot status
+    /// is received, and updates from abandoned forks are discarded.  Released
+    /// updates for different accounts are applied concurrently, across as
+    /// many lanes as there are worker threads.  This requires the Geyser
+    /// plugin to publish slot status notifications.
+    #[clap(long, env)]
+    confirmed_only: bool,
+
+    /// The maximum number of account updates to hold while waiting for their
+    /// slot to be confirmed
+    ///
+    /// Buffered updates are not acknowledged until they are applied, so this
+    /// must be well below the prefetch limit of the Geyser queue.  Updates
+    /// received while the buffer is full wait for space, unacknowledged.  The
+    /// buffer is emptied whenever the AMQP connection is re-established.
+    #[clap(long, env, default_value_t = 2048)]
+    slot_buffer_capacity: usize,
+
+    /// Skip account updates already applied from another Geyser plugin
+    /// instance, tracking up to this many accounts
+    ///
+    /// This allows running the plugin on several validators for redundancy,
+    /// all publishing to the same exchange.
+    #[clap(long, env)]
+    dedup_capacity: Option<usize>,
+
+    /// Consume from the topic exchange instead of the fanout exchange
+    ///
+    /// The Geyser plugin must be configured to publish to topic exchanges.
+    #[clap(long, env)]
+    topic_exchange: bool,
+
+    /// List of programs to receive messages for from the topic exchange
+    ///
+    /// For example, `metadata,auction-house` will bind only account updates,
+    /// instructions and transactions for the Metaplex metadata and auction
+    /// house programs, plus slot status updates.  Programs without a
+    /// well-known name are specified by address.  If omitted, all messages
+    /// are received.
+    #[clap(long, env, use_value_delimiter(true), requires("topic-exchange"))]
+    topics: Vec<String>,
+
+    /// Consume Geyser messages from these Kafka bootstrap servers instead of
+    /// from AMQP
+    ///
+    /// The AMQP server is still used to send messages to the HTTP and search
+    /// indexers.
+    #[cfg(feature = "kafka")]
+    #[clap(long, env)]
+    kafka_brokers: Option<String>,
+
+    /// The Kafka consumer group to join.  Defaults to the name of the AMQP
+    /// queue that would have been used.  Starting a new group replays all
+    /// retained history.
+    #[cfg(feature = "kafka")]
+    #[clap(long, env, requires("kafka-brokers"))]
+    kafka_group: Option<String>,
+
+    /// Declare queues with priority lanes, delivering live work ahead of
+    /// backfill work
+    ///
+    /// Every service sharing a queue, including the Geyser plugin, must agree
+    /// on this setting.
+    #[clap(long, env)]
+    amqp_priority_lanes: bool,
+
     #[clap(flatten)]
     queue_suffix: Suffix,
 
+    #[clap(flatten)]
+    producer: ProducerArgs,
+
     #[clap(flatten)]
     client: ClientArgs,
 }
@@ -39,7 +113,18 @@ fn main() {
              network,
              startup,
              ignore_on_startup,
+             confirmed_only,
+             slot_buffer_capacity,
+             dedup_capacity,
+             topic_exchange,
+             topics,
+             #[cfg(feature = "kafka")]
+             kafka_brokers,
+             #[cfg(feature = "kafka")]
+             kafka_group,
+             amqp_priority_lanes,
              queue_suffix,
+             producer,
              client,
          },
          params,
@@ -49,23 +134,19 @@ fn main() {
                 _ => network.to_string(),
             };
 
-            let conn = holaplex_indexer::amqp_connect(amqp_url, env!("CARGO_BIN_NAME")).await?;
-            let client = Client::new_rc(
-                db,
-                &conn,
-                http_indexer::QueueType::new(&receiver, &queue_suffix)?,
-                http_indexer::QueueType::new(&receiver, &queue_suffix)?,
-                search_indexer::QueueType::new(&receiver, &queue_suffix)?,
-                client,
-            )
-            .await
-            .context("Failed to construct Client")?;
-
-            let queue_type = geyser::QueueType::new(network, startup, &queue_suffix)?;
-            let consumer = geyser::Consumer::new(&conn, queue_type.clone(), "geyser-consumer")
-                .await
-                .context("Failed to create queue consumer")?;
+            let meta_queue = http_indexer::QueueType::new(&receiver, &queue_suffix)?
+                .with_priority_lanes(amqp_priority_lanes);
+            let store_cfg_queue = http_indexer::QueueType::new(&receiver, &queue_suffix)?
+                .with_priority_lanes(amqp_priority_lanes);
+            let search_queue = search_indexer::QueueType::new(&receiver, &queue_suffix)?
+                .with_priority_lanes(amqp_priority_lanes);
 
+            let queue_type = if topic_exchange {
+                geyser::QueueType::new_topic(network, startup, &queue_suffix, &topics)?
+            } else {
+                geyser::QueueType::new(network, startup, &queue_suffix)?
+            }
+            .with_priority_lanes(amqp_priority_lanes);
             let ignore_on_startup = Arc::new(
                 ignore_on_startup
                     .into_iter()
@@ -73,21 +154,91 @@ fn main() {
                     .collect::<HashSet<_>>(),
             );
 
+            let dedup = dedup_capacity
+                .map(|c| Dedup::new(c).map(Arc::new))
+                .transpose()?;
+            let lanes = params.concurrency();
+
+            // Called for every new AMQP connection, to create publishers on it
+            let init = move |conn: Arc<lapin::Connection>| {
+                let db = db.clone();
+                let client = client.clone();
+                let meta_queue = meta_queue.clone();
+                let store_cfg_queue = store_cfg_queue.clone();
+                let search_queue = search_queue.clone();
+                let ignore_on_startup = ignore_on_startup.clone();
+                let dedup = dedup.clone();
+
+                async move {
+                    // Updates buffered by a previous session were received on
+                    // a dead channel, and will be redelivered on this one
+                    let slot_buffer = confirmed_only
+                        .then(|| SlotBuffer::new(slot_buffer_capacity, lanes).map(Arc::new))
+                        .transpose()?;
+                    let publishers = Publishers::amqp(
+                        &conn,
+                        meta_queue,
+                        store_cfg_queue,
+                        search_queue,
+                        producer,
+                    )
+                    .await?;
+                    let client = Client::new_rc(db, publishers, client)
+                        .context("Failed to construct Client")?;
+
+                    Ok(move |m| {
+                        let client = client.clone();
+                        let ignore_on_startup = ignore_on_startup.clone();
+                        let dedup = dedup.clone();
+                        let slot_buffer = slot_buffer.clone();
+
+                        async move {
+                            holaplex_indexer::geyser::process_message(
+                                m,
+                                &*client,
+                                ignore_on_startup,
+                                dedup.as_ref(),
+                                slot_buffer.as_deref(),
+                            )
+                            .await
+                        }
+                    })
+                }
+            };
+
+            #[cfg(feature = "kafka")]
+            if let Some(brokers) = kafka_brokers {
+                let mut config = holaplex_indexer::kafka_config(brokers, env!("CARGO_BIN_NAME"))?;
+
+                if let Some(group) = kafka_group {
+                    config.set("group.id", group);
+                }
+
+                let consumer =
+                    indexer_rabbitmq::kafka::Consumer::new(&config, queue_type, "geyser-consumer")
+                        .context("Failed to create Kafka consumer")?;
+                let conn = Arc::new(
+                    holaplex_indexer::amqp_connect(amqp_url, env!("CARGO_BIN_NAME")).await?,
+                );
+                let process = init(Arc::clone(&conn)).await?;
+
+                return holaplex_indexer::consume(
+                    &params,
+                    consumer,
+                    StdDuration::from_millis(100),
+                    process,
+                )
+                .await;
+            }
+
             holaplex_indexer::amqp_consume(
                 &params,
-                conn,
-                consumer,
+                amqp_url,
+                env!("CARGO_BIN_NAME"),
                 queue_type,
+                "geyser-consumer",
                 StdDuration::from_millis(100),
-                move |m| {
-                    let client = client.clone();
-                    let ignore_on_startup = ignore_on_startup.clone();
-
-                    async move {
-                        holaplex_indexer::geyser::process_message(m, &*client, ignore_on_startup)
-                            .await
-                    }
-                },
+                init,
             )
             .await
         },
@@ -1,4 +1,7 @@
-use holaplex_indexer::http::{Client, ClientArgs};
+use holaplex_indexer::{
+    http::{Client, ClientArgs},
+    publish::ProducerArgs,
+};
 use indexer_core::{clap, prelude::*};
 use indexer_rabbitmq::{http_indexer, search_indexer, suffix::Suffix};
 
@@ -16,9 +19,20 @@ struct Args {
     #[clap(long, env)]
     entity: http_indexer::EntityId,
 
+    /// Declare queues with priority lanes, delivering live work ahead of
+    /// backfill work
+    ///
+    /// Every service sharing a queue, including the Geyser plugin, must agree
+    /// on this setting.
+    #[clap(long, env)]
+    amqp_priority_lanes: bool,
+
     #[clap(flatten)]
     queue_suffix: Suffix,
 
+    #[clap(flatten)]
+    producer: ProducerArgs,
+
     #[clap(flatten)]
     client: ClientArgs,
 }
@@ -46,7 +60,9 @@ async fn run<E: Send + holaplex_indexer::http::Process + 'static>(
         amqp_url,
         sender,
         entity: _,
+        amqp_priority_lanes,
         queue_suffix,
+        producer,
         client,
     } = args;
 
@@ -55,30 +71,36 @@ async fn run<E: Send + holaplex_indexer::http::Process + 'static>(
         _ => sender.clone(),
     };
 
-    let conn = holaplex_indexer::amqp_connect(amqp_url, env!("CARGO_BIN_NAME")).await?;
-    let client = Client::new_rc(
-        db,
-        &conn,
-        client,
-        search_indexer::QueueType::new(&receiver, &queue_suffix)?,
-    )
-    .await
-    .context("Failed to construct Client")?;
-
-    let queue_type = http_indexer::QueueType::<E>::new(&sender, &queue_suffix)?;
-    let consumer = http_indexer::Consumer::new(&conn, queue_type.clone(), "http-consumer")
-        .await
-        .context("Failed to create queue consumer")?;
+    let search_queue = search_indexer::QueueType::new(&receiver, &queue_suffix)?
+        .with_priority_lanes(amqp_priority_lanes);
+    let queue_type = http_indexer::QueueType::<E>::new(&sender, &queue_suffix)?
+        .with_priority_lanes(amqp_priority_lanes);
 
     holaplex_indexer::amqp_consume(
         &params,
-        conn,
-        consumer,
+        amqp_url,
+        env!("CARGO_BIN_NAME"),
         queue_type,
+        "http-consumer",
         StdDuration::from_millis(500),
-        move |m| {
+        move |conn| {
+            let db = db.clone();
+            let search_queue = search_queue.clone();
             let client = client.clone();
-            async move { m.process(&client).await }
+
+            async move {
+                let search_prod = producer
+                    .publisher(&conn, search_queue)
+                    .await
+                    .context("Couldn't create AMQP search producer")?;
+                let client = Client::new_rc(db, search_prod, client)
+                    .context("Failed to construct Client")?;
+
+                Ok(move |m: E| {
+                    let client = client.clone();
+                    async move { m.process(&client).await }
+                })
+            }
         },
     )
     .await
@@ -0,0 +1,174 @@
+use std::{collections::HashSet, path::PathBuf, sync::Arc};
+
+use holaplex_indexer::{
+    geyser::{self, Publishers},
+    http, search,
+};
+use indexer_core::{clap, prelude::*};
+use indexer_rabbitmq::{
+    geyser::Message,
+    http_indexer::{MetadataJson, StoreConfig},
+    memory,
+};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
+
+#[derive(Debug, clap::Args)]
+struct Args {
+    /// A file of Geyser messages to index, one JSON object per line.
+    /// Defaults to standard input.
+    #[clap(long)]
+    input: Option<PathBuf>,
+
+    /// The number of messages each in-memory queue may hold
+    #[clap(long, env, default_value_t = 4096)]
+    queue_capacity: usize,
+
+    #[clap(flatten)]
+    geyser: geyser::ClientArgs,
+
+    #[clap(flatten)]
+    http: http::ClientArgs,
+
+    #[clap(flatten)]
+    search: search::ClientArgs,
+}
+
+fn main() {
+    holaplex_indexer::run(
+        |Args {
+             input,
+             queue_capacity,
+             geyser,
+             http,
+             search,
+         },
+         params,
+         db| async move {
+            let (geyser_prod, geyser_cons) = memory::channel(queue_capacity, "geyser-consumer");
+            let (meta_prod, meta_cons) = memory::channel(queue_capacity, "metadata-json-consumer");
+            let (store_prod, store_cons) = memory::channel(queue_capacity, "store-config-consumer");
+            let (search_prod, search_cons) = memory::channel(queue_capacity, "search-consumer");
+
+            let geyser_client = geyser::Client::new_rc(
+                db.clone(),
+                Publishers {
+                    metadata_json: Box::new(meta_prod),
+                    store_config: Box::new(store_prod),
+                    search: Box::new(search_prod.clone()),
+                },
+                geyser,
+            )
+            .context("Failed to construct Geyser client")?;
+            let http_client = http::Client::new_rc(db.clone(), Box::new(search_prod), http)
+                .context("Failed to construct HTTP client")?;
+            let (search_client, upsert_task, stop_upsert) = search::Client::new_rc(db, search)
+                .await
+                .context("Failed to construct search client")?;
+
+            let ignore_on_startup = Arc::new(HashSet::new());
+            let grace_period = StdDuration::from_millis(500);
+
+            // Each indexer hangs up once every producer for its queue has been
+            // dropped, so the pipeline drains from front to back once the
+            // input is exhausted.
+            let ret = tokio::try_join!(
+                async move {
+                    let ret = match input {
+                        Some(path) => {
+                            feed(
+                                BufReader::new(
+                                    tokio::fs::File::open(&path)
+                                        .await
+                                        .with_context(|| format!("Failed to open {:?}", path))?,
+                                ),
+                                geyser_prod,
+                            )
+                            .await
+                        },
+                        None => feed(BufReader::new(tokio::io::stdin()), geyser_prod).await,
+                    };
+
+                    info!("Finished reading input");
+
+                    ret
+                },
+                holaplex_indexer::consume(&params, geyser_cons, grace_period, move |m| {
+                    let client = geyser_client.clone();
+                    let ignore_on_startup = Arc::clone(&ignore_on_startup);
+
+                    async move {
+                        geyser::process_message(m, &*client, ignore_on_startup, None, None).await
+                    }
+                }),
+                holaplex_indexer::consume(&params, meta_cons, grace_period, {
+                    let client = http_client.clone();
+
+                    move |m: MetadataJson| {
+                        let client = client.clone();
+                        async move { http::Process::process(m, &client).await }
+                    }
+                }),
+                holaplex_indexer::consume(
+                    &params,
+                    store_cons,
+                    grace_period,
+                    move |m: StoreConfig| {
+                        let client = http_client.clone();
+                        async move { http::Process::process(m, &client).await }
+                    }
+                ),
+                holaplex_indexer::consume(&params, search_cons, grace_period, move |m| {
+                    let client = search_client.clone();
+                    async move { search::process_message(m, &*client).await }
+                }),
+            )
+            .map(|_| ());
+
+            if let Err(()) = stop_upsert.send(()) {
+                error!("Failed to stop upsert task");
+                upsert_task.abort();
+            }
+
+            upsert_task.await.context("Join for upsert task failed")?;
+
+            ret
+        },
+    );
+}
+
+async fn feed(
+    mut input: impl AsyncBufRead + Unpin,
+    producer: memory::Producer<Message>,
+) -> Result<()> {
+    let mut line = String::new();
+    let mut n = 0_usize;
+
+    loop {
+        line.clear();
+
+        if input
+            .read_line(&mut line)
+            .await
+            .context("Failed to read input")?
+            == 0
+        {
+            break;
+        }
+
+        n += 1;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let msg: Message = serde_json::from_str(&line)
+            .with_context(|| format!("Failed to parse message on line {}", n))?;
+
+        producer
+            .write(msg)
+            .await
+            .context("Failed to queue message")?;
+    }
+
+    Ok(())
+}
@@ -12,6 +12,14 @@ struct Args {
     #[clap(long, env)]
     sender: String,
 
+    /// Declare queues with priority lanes, delivering live work ahead of
+    /// backfill work
+    ///
+    /// Every service sharing a queue, including the Geyser plugin, must agree
+    /// on this setting.
+    #[clap(long, env)]
+    amqp_priority_lanes: bool,
+
     #[clap(flatten)]
     queue_suffix: indexer_rabbitmq::suffix::Suffix,
 
@@ -24,35 +32,41 @@ fn main() {
         |Args {
              amqp_url,
              sender,
+             amqp_priority_lanes,
              queue_suffix,
              client,
          },
          params,
          db| async move {
-            let conn = holaplex_indexer::amqp_connect(amqp_url, env!("CARGO_BIN_NAME")).await?;
-
-            let queue_type = search_indexer::QueueType::new(&sender, &queue_suffix)?;
-            let consumer =
-                search_indexer::Consumer::new(&conn, queue_type.clone(), "search-consumer")
-                    .await
-                    .context("Failed to create queue consumer")?;
+            let queue_type = search_indexer::QueueType::new(&sender, &queue_suffix)?
+                .with_priority_lanes(amqp_priority_lanes);
 
             let (client, upsert_task, stop_upsert) = Client::new_rc(db, client)
                 .await
                 .context("Failed to construct Client")?;
 
-            let ret = holaplex_indexer::amqp_consume(
-                &params,
-                conn,
-                consumer,
-                queue_type,
-                StdDuration::from_millis(500),
-                move |m| {
-                    let client = client.clone();
-                    async move { holaplex_indexer::search::process_message(m, &*client).await }
-                },
-            )
-            .await;
+            let ret =
+                holaplex_indexer::amqp_consume(
+                    &params,
+                    amqp_url,
+                    env!("CARGO_BIN_NAME"),
+                    queue_type,
+                    "search-consumer",
+                    StdDuration::from_millis(500),
+                    move |_| {
+                        let client = client.clone();
+
+                        async move {
+                            Ok(move |m| {
+                                let client = client.clone();
+                                async move {
+                                    holaplex_indexer::search::process_message(m, &*client).await
+                                }
+                            })
+                        }
+                    },
+                )
+                .await;
 
             if let Err(()) = stop_upsert.send(()) {
                 error!("Failed to stop upsert task");
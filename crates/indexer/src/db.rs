@@ -1,27 +1,97 @@
 //! Support module for running Diesel operations in an async context.
 
-use std::fmt;
+use std::{collections::hash_map::Entry, fmt, hash::Hash, sync::Arc};
 
-use indexer_core::{db, db::PooledConnection};
+use futures_util::future::try_join_all;
+use indexer_core::{
+    clap, db,
+    db::{
+        delete, excluded, insert_into,
+        models::{CurrentMetadataOwner, MetadataAttributeWrite, MetadataCreator},
+        tables::{attributes, current_metadata_owners, metadata_creators, metadatas},
+        PooledConnection,
+    },
+    hash::HashMap,
+};
+use tokio::{
+    runtime::Handle,
+    sync::{mpsc, oneshot, Semaphore},
+};
 
 use crate::prelude::*;
 
+/// Arguments for tuning batched database writes
+#[derive(Debug, Clone, Copy, clap::Args)]
+pub struct BatchArgs {
+    /// The maximum number of rows to upsert into a table at once
+    #[clap(long, env, default_value_t = 500)]
+    db_batch_size: usize,
+
+    /// The time in milliseconds to wait for more rows before upserting a
+    /// batch
+    #[clap(long, env, default_value_t = 10)]
+    db_batch_window: u64,
+
+    /// The maximum number of batches to write into a table at once
+    #[clap(long, env, default_value_t = 4)]
+    db_batch_concurrency: usize,
+}
+
 /// Handle to a database pool used by an indexer consumer
-pub struct Pool(db::Pool, db::ConnectionType);
+#[derive(Clone)]
+pub struct Pool {
+    pool: db::Pool,
+    permits: Arc<Semaphore>,
+    ty: db::ConnectionType,
+    writers: Arc<Writers>,
+}
 
 impl Pool {
-    pub(crate) fn new((pool, ty): (db::Pool, db::ConnectionType)) -> Self {
-        Self(pool, ty)
+    /// Connect to the database and run any pending migrations, spawning the
+    /// batch writers on the given runtime
+    ///
+    /// # Errors
+    /// This function fails if the connection pool cannot be created or the
+    /// migrations fail to run.
+    pub(crate) fn connect(args: &db::ConnectArgs, batch: BatchArgs, rt: &Handle) -> Result<Self> {
+        let (pool, ty) = db::connect(args, db::ConnectMode::Write)?;
+        let permits = Arc::new(Semaphore::new(
+            pool.max_size().try_into().unwrap_or(usize::MAX),
+        ));
+        let writers = Writers::new(&pool, &permits, batch, rt);
+
+        Ok(Self {
+            pool,
+            permits,
+            ty,
+            writers: Arc::new(writers),
+        })
     }
 
     /// Get the connection-type hint for this database connection
     #[must_use]
     pub fn ty(&self) -> db::ConnectionType {
-        self.1
+        self.ty
+    }
+
+    /// Get the number of open connections, the number of idle connections,
+    /// and the maximum number of connections in this pool
+    pub(crate) fn utilization(&self) -> (u32, u32, u32) {
+        let state = self.pool.state();
+
+        (
+            state.connections,
+            state.idle_connections,
+            self.pool.max_size(),
+        )
     }
 
     /// Spawn a blocking thread to perform operations on the database.
     ///
+    /// Callers wait asynchronously for a free connection before a thread is
+    /// spawned, so no more blocking threads are used than the pool has
+    /// connections.
+    ///
     /// # Errors
     /// This function fails if `r2d2` cannot acquire a database connection or
     /// the provided callback returns an error.
@@ -29,19 +99,626 @@ impl Pool {
         &self,
         f: impl FnOnce(&PooledConnection) -> Result<T, E> + Send + 'static,
     ) -> Result<T> {
-        let db = self
-            .0
-            .get()
-            .context("Failed to acquire database connection");
+        run_blocking(&self.pool, &self.permits, f).await
+    }
+
+    /// Upsert a row, coalescing it with other upserts into the same table.
+    /// Resolves once the batch containing the row has been written.
+    ///
+    /// # Errors
+    /// This function fails if the row could not be written.
+    pub(crate) async fn upsert<R: Upsert>(&self, row: R) -> Result<()> {
+        let (tx, rx) = oneshot::channel();
 
-        tokio::task::spawn_blocking(|| f(&db?).map_err(Into::into))
+        R::writer(&self.writers)
+            .send(Pending { row, tx })
             .await
-            .context("Blocking task failed")?
+            .map_err(|_| anyhow!("Batch writer for {} has stopped", R::TABLE))?;
+
+        rx.await
+            .with_context(|| format!("Batch writer for {} dropped a row", R::TABLE))?
+            .with_context(|| format!("Failed to upsert into {}", R::TABLE))
+    }
+
+    /// Upsert several rows into the same table.  See [`upsert`](Self::upsert).
+    ///
+    /// # Errors
+    /// This function fails if any row could not be written.
+    pub(crate) async fn upsert_all<R: Upsert>(
+        &self,
+        rows: impl IntoIterator<Item = R>,
+    ) -> Result<()> {
+        try_join_all(rows.into_iter().map(|r| self.upsert(r)))
+            .await
+            .map(|_| ())
+    }
+}
+
+/// Spawn a blocking thread to run `f` with a connection from `pool`, waiting
+/// for one of `permits` first
+async fn run_blocking<T: 'static + Send, E: 'static + Into<indexer_core::error::Error>>(
+    pool: &db::Pool,
+    permits: &Arc<Semaphore>,
+    f: impl FnOnce(&PooledConnection) -> Result<T, E> + Send + 'static,
+) -> Result<T> {
+    let permit = Arc::clone(permits)
+        .acquire_owned()
+        .await
+        .context("Database pool was closed")?;
+    let pool = pool.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let db = pool
+            .get()
+            .context("Failed to acquire database connection")?;
+        let ret = f(&db).map_err(Into::into);
+
+        std::mem::drop(permit);
+        ret
+    })
+    .await
+    .context("Blocking task failed")?
+}
+
+/// A row which can be written to its table in a multi-row upsert
+pub(crate) trait Upsert: Send + Sync + Sized + 'static {
+    /// The name of the table rows are written to
+    const TABLE: &'static str;
+
+    /// The conflict target of the upsert.  A batch may only contain one row
+    /// for each key, so only the newest row for a key is written.
+    type Key: Eq + Hash + Send;
+
+    /// Get the conflict key of this row
+    fn key(&self) -> Self::Key;
+
+    /// Get the version of the account update this row was derived from
+    fn version(&self) -> AccountVersion;
+
+    /// Get the batch writer for this table
+    fn writer(writers: &Writers) -> &mpsc::Sender<Pending<Self>>;
+
+    /// Upsert the given rows in a single statement
+    ///
+    /// # Errors
+    /// This function fails if the statement fails.
+    fn upsert(db: &db::Connection, rows: &[Self]) -> Result<()>;
+}
+
+/// An error writing a batch, shared by every row it affected
+#[derive(Debug, Clone)]
+struct WriteError(Arc<Error>);
+
+impl From<Error> for WriteError {
+    fn from(e: Error) -> Self {
+        Self(Arc::new(e))
+    }
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&*self.0, f)
+    }
+}
+
+impl std::error::Error for WriteError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.source()
+    }
+}
+
+/// A row waiting to be written, and a channel to report the result on
+pub(crate) struct Pending<R> {
+    row: R,
+    tx: oneshot::Sender<Result<(), WriteError>>,
+}
+
+/// Batch writers for each table supporting batched upserts
+pub(crate) struct Writers {
+    current_metadata_owners: mpsc::Sender<Pending<CurrentMetadataOwner<'static>>>,
+    metadata_attributes: mpsc::Sender<Pending<MetadataAttributeWrite<'static>>>,
+    metadata_creators: mpsc::Sender<Pending<MetadataCreators>>,
+}
+
+impl Writers {
+    fn new(pool: &db::Pool, permits: &Arc<Semaphore>, batch: BatchArgs, rt: &Handle) -> Self {
+        Self {
+            current_metadata_owners: spawn_writer(pool, permits, batch, rt),
+            metadata_attributes: spawn_writer(pool, permits, batch, rt),
+            metadata_creators: spawn_writer(pool, permits, batch, rt),
+        }
+    }
+}
+
+fn spawn_writer<R: Upsert>(
+    pool: &db::Pool,
+    permits: &Arc<Semaphore>,
+    batch: BatchArgs,
+    rt: &Handle,
+) -> mpsc::Sender<Pending<R>> {
+    let (tx, rx) = mpsc::channel(batch.db_batch_size.max(1));
+
+    rt.spawn(run_writer(pool.clone(), Arc::clone(permits), batch, rx));
+
+    tx
+}
+
+/// Collect rows into batches until every sender has been dropped, writing
+/// each batch once it is full or its window has elapsed
+async fn run_writer<R: Upsert>(
+    pool: db::Pool,
+    conn_permits: Arc<Semaphore>,
+    BatchArgs {
+        db_batch_size,
+        db_batch_window,
+        db_batch_concurrency,
+    }: BatchArgs,
+    mut rx: mpsc::Receiver<Pending<R>>,
+) {
+    let window = StdDuration::from_millis(db_batch_window);
+    let permits = Arc::new(Semaphore::new(db_batch_concurrency.max(1)));
+
+    while let Some(first) = rx.recv().await {
+        let mut batch = vec![first];
+        let deadline = tokio::time::sleep(window);
+        tokio::pin!(deadline);
+
+        while batch.len() < db_batch_size {
+            tokio::select! {
+                Some(pending) = rx.recv() => batch.push(pending),
+                () = &mut deadline => break,
+                else => break,
+            }
+        }
+
+        // Let the next batch fill while this one is written, but stop
+        // reading rows once too many batches are in flight so that callers
+        // are held back by the channel filling up
+        let permit = match Arc::clone(&permits).acquire_owned().await {
+            Ok(p) => p,
+            Err(_) => break,
+        };
+        let pool = pool.clone();
+        let conn_permits = Arc::clone(&conn_permits);
+
+        tokio::spawn(async move {
+            write_batch(&pool, &conn_permits, batch).await;
+            std::mem::drop(permit);
+        });
+    }
+}
+
+async fn write_batch<R: Upsert>(pool: &db::Pool, permits: &Arc<Semaphore>, batch: Vec<Pending<R>>) {
+    let (rows, txs): (Vec<_>, Vec<_>) = coalesce(batch).into_iter().unzip();
+
+    let results = run_blocking(pool, permits, move |db| {
+        Result::<_>::Ok(upsert_rows(&rows, |rows| R::upsert(db, rows)))
+    })
+    .await;
+
+    match results {
+        Ok(results) => {
+            for (res, txs) in results.into_iter().zip(txs) {
+                for tx in txs {
+                    tx.send(res.clone()).ok();
+                }
+            }
+        },
+        Err(e) => {
+            let e = WriteError::from(e);
+
+            for tx in txs.into_iter().flatten() {
+                tx.send(Err(e.clone())).ok();
+            }
+        },
+    }
+}
+
+/// Merge the rows of a batch sharing a conflict key, keeping only the newest
+/// row for each key along with every channel waiting on the key.  Rows from
+/// the same slot replace each other in the order they were received.
+fn coalesce<R: Upsert>(
+    batch: Vec<Pending<R>>,
+) -> Vec<(R, Vec<oneshot::Sender<Result<(), WriteError>>>)> {
+    let mut indices = HashMap::default();
+    let mut rows: Vec<(R, Vec<_>)> = Vec::with_capacity(batch.len());
+
+    for Pending { row, tx } in batch {
+        match indices.entry(row.key()) {
+            Entry::Occupied(e) => {
+                let (newest, txs) = &mut rows[*e.get()];
+
+                if !newest.version().supersedes(&row.version()) {
+                    *newest = row;
+                }
+
+                txs.push(tx);
+            },
+            Entry::Vacant(e) => {
+                e.insert(rows.len());
+                rows.push((row, vec![tx]));
+            },
+        }
+    }
+
+    rows
+}
+
+/// Upsert a batch of rows in a single statement, returning the result for
+/// each row.  If the statement fails, each row is retried in its own
+/// statement, so a single bad row does not fail the rest of the batch.
+fn upsert_rows<R: Upsert>(
+    rows: &[R],
+    upsert: impl Fn(&[R]) -> Result<()>,
+) -> Vec<Result<(), WriteError>> {
+    match upsert(rows) {
+        Ok(()) => vec![Ok(()); rows.len()],
+        Err(e) if rows.len() <= 1 => vec![Err(e.into()); rows.len()],
+        Err(e) => {
+            warn!(
+                "Batch upsert of {} row(s) into {} failed, retrying rows individually: {:?}",
+                rows.len(),
+                R::TABLE,
+                e
+            );
+
+            rows.iter()
+                .map(|r| upsert(std::slice::from_ref(r)).map_err(Into::into))
+                .collect()
+        },
+    }
+}
+
+impl Upsert for CurrentMetadataOwner<'static> {
+    type Key = String;
+
+    const TABLE: &'static str = "current_metadata_owners";
+
+    fn key(&self) -> String {
+        self.mint_address.to_string()
+    }
+
+    fn version(&self) -> AccountVersion {
+        AccountVersion {
+            slot: self.slot,
+            write_version: self.write_version,
+        }
+    }
+
+    fn writer(writers: &Writers) -> &mpsc::Sender<Pending<Self>> {
+        &writers.current_metadata_owners
+    }
+
+    fn upsert(db: &db::Connection, rows: &[Self]) -> Result<()> {
+        insert_into(current_metadata_owners::table)
+            .values(rows)
+            .on_conflict(current_metadata_owners::mint_address)
+            .do_update()
+            .set((
+                current_metadata_owners::owner_address
+                    .eq(excluded(current_metadata_owners::owner_address)),
+                current_metadata_owners::token_account_address
+                    .eq(excluded(current_metadata_owners::token_account_address)),
+                current_metadata_owners::slot.eq(excluded(current_metadata_owners::slot)),
+                current_metadata_owners::write_version
+                    .eq(excluded(current_metadata_owners::write_version)),
+            ))
+            .execute(db)
+            .context("Failed to insert token metadata owners")?;
+
+        Ok(())
     }
 }
 
+impl Upsert for MetadataAttributeWrite<'static> {
+    type Key = (String, Option<String>, Option<String>);
+
+    const TABLE: &'static str = "attributes";
+
+    fn key(&self) -> Self::Key {
+        (
+            self.metadata_address.to_string(),
+            self.value.as_ref().map(ToString::to_string),
+            self.trait_type.as_ref().map(ToString::to_string),
+        )
+    }
+
+    fn version(&self) -> AccountVersion {
+        AccountVersion {
+            slot: self.slot,
+            write_version: self.write_version,
+        }
+    }
+
+    fn writer(writers: &Writers) -> &mpsc::Sender<Pending<Self>> {
+        &writers.metadata_attributes
+    }
+
+    fn upsert(db: &db::Connection, rows: &[Self]) -> Result<()> {
+        insert_into(attributes::table)
+            .values(rows)
+            .on_conflict((
+                attributes::metadata_address,
+                attributes::value,
+                attributes::trait_type,
+            ))
+            .do_update()
+            .set((
+                attributes::first_verified_creator.eq(excluded(attributes::first_verified_creator)),
+                attributes::slot.eq(excluded(attributes::slot)),
+                attributes::write_version.eq(excluded(attributes::write_version)),
+            ))
+            .execute(db)
+            .context("Failed to insert attributes")?;
+
+        Ok(())
+    }
+}
+
+/// Every creator of a metadata account as of a single update, which replace
+/// the creators previously stored for it
+#[derive(Debug, Clone)]
+pub(crate) struct MetadataCreators {
+    /// The address of the metadata account
+    pub address: String,
+    /// The version of the update the creators were read from
+    pub version: AccountVersion,
+    /// The creators, in the order they appear in the account
+    pub creators: Vec<MetadataCreator<'static>>,
+}
+
+impl Upsert for MetadataCreators {
+    type Key = String;
+
+    const TABLE: &'static str = "metadata_creators";
+
+    fn key(&self) -> String {
+        self.address.clone()
+    }
+
+    fn version(&self) -> AccountVersion {
+        self.version
+    }
+
+    fn writer(writers: &Writers) -> &mpsc::Sender<Pending<Self>> {
+        &writers.metadata_creators
+    }
+
+    /// Replace the creators of each metadata account in a single transaction.
+    /// As with [`upsert_children`], the creators of an account are left
+    /// untouched if its `metadatas` row holds an update from a newer slot.
+    fn upsert(db: &db::Connection, rows: &[Self]) -> Result<()> {
+        db.build_transaction().read_write().run(|| {
+            let addresses: Vec<_> = rows.iter().map(|r| r.address.as_str()).collect();
+
+            let stored: HashMap<String, AccountVersion> = metadatas::table
+                .select((
+                    metadatas::address,
+                    metadatas::slot,
+                    metadatas::write_version,
+                ))
+                .filter(metadatas::address.eq(any(addresses)))
+                .for_update()
+                .load::<(String, Option<i64>, Option<i64>)>(db)
+                .context("Failed to lock metadatas")?
+                .into_iter()
+                .filter_map(|(address, slot, write_version)| {
+                    Some((address, AccountVersion::from((slot?, write_version?))))
+                })
+                .collect();
+
+            let rows: Vec<_> = rows
+                .iter()
+                .filter(|r| {
+                    !stored
+                        .get(&r.address)
+                        .map_or(false, |v| v.supersedes(&r.version))
+                })
+                .collect();
+
+            let addresses: Vec<_> = rows.iter().map(|r| r.address.as_str()).collect();
+            let creators: Vec<_> = rows.iter().flat_map(|r| r.creators.clone()).collect();
+
+            delete(
+                metadata_creators::table
+                    .filter(metadata_creators::metadata_address.eq(any(addresses))),
+            )
+            .execute(db)
+            .context("Failed to delete stale metadata creators")?;
+
+            if !creators.is_empty() {
+                insert_into(metadata_creators::table)
+                    .values(&creators)
+                    .on_conflict_do_nothing()
+                    .execute(db)
+                    .context("Failed to insert metadata creators")?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// The position of the account update a row was derived from.
+///
+/// Every table holding account state carries `slot` and `write_version`
+/// columns, guarded by a `check_slot` trigger which leaves a row untouched if
+/// an upsert would replace it with data from an older slot.  Write versions are
+/// assigned independently by each validator, so the trigger orders updates by
+/// slot alone, as the Geyser consumer's deduplication cache does, and updates
+/// from the same slot are applied in the order received.  Processors
+/// can therefore upsert unconditionally, provided each row is stamped with the
+/// version of the update it came from, and messages redelivered out of order
+/// cannot overwrite newer state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AccountVersion {
+    /// The slot in which the account was written
+    pub slot: i64,
+    /// The validator's write version for the account write, which is stored
+    /// for reference but never compared
+    pub write_version: i64,
+}
+
+impl AccountVersion {
+    /// Construct a version from the raw slot and write version of an account
+    /// update
+    ///
+    /// # Errors
+    /// This function fails if either value is too large to store.
+    pub fn new(slot: u64, write_version: u64) -> Result<Self> {
+        Ok(Self {
+            slot: slot.try_into().context("Slot was too big to store")?,
+            write_version: write_version
+                .try_into()
+                .context("Write version was too big to store")?,
+        })
+    }
+}
+
+impl AccountVersion {
+    /// Returns true if data written by this version must not be replaced by
+    /// data from `other`
+    #[must_use]
+    pub fn supersedes(&self, other: &Self) -> bool {
+        self.slot > other.slot
+    }
+}
+
+impl From<(i64, i64)> for AccountVersion {
+    fn from((slot, write_version): (i64, i64)) -> Self {
+        Self {
+            slot,
+            write_version,
+        }
+    }
+}
+
+/// Replace the rows derived from an account, such as its invalidators, with
+/// those from a new version of the account, in a single transaction.
+///
+/// The `check_slot` trigger only guards rows that already exist, so child
+/// rows cannot simply be deleted and reinserted.  This should be called after
+/// the account's own row has been upserted: `current` reads the version stored
+/// in that row, locking it, and `replace` deletes the old child rows and
+/// inserts the new ones.  The child rows are left untouched if the stored
+/// version is from a newer slot than `version`, so a stale update can neither
+/// delete newer rows nor restore ones that have since been removed.
+///
+/// Returns true if the child rows were replaced.
+///
+/// # Errors
+/// This function fails if any query fails, in which case the transaction is
+/// rolled back.
+pub(crate) fn upsert_children<E: From<db::Error>>(
+    db: &db::Connection,
+    version: AccountVersion,
+    current: impl FnOnce() -> Result<Option<AccountVersion>, E>,
+    replace: impl FnOnce() -> Result<(), E>,
+) -> Result<bool, E> {
+    db.build_transaction().read_write().run(|| {
+        if current()?.map_or(false, |stored| stored.supersedes(&version)) {
+            return Ok(false);
+        }
+
+        replace()?;
+
+        Ok(true)
+    })
+}
+
 impl fmt::Debug for Pool {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Pool").finish_non_exhaustive()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    fn owner(mint: &str, owner: &str, slot: i64) -> CurrentMetadataOwner<'static> {
+        CurrentMetadataOwner {
+            mint_address: Owned(mint.into()),
+            owner_address: Owned(owner.into()),
+            token_account_address: Owned(format!("{}-account", owner)),
+            slot,
+            write_version: 0,
+        }
+    }
+
+    fn pending<R>(row: R) -> Pending<R> {
+        let (tx, _rx) = oneshot::channel();
+
+        Pending { row, tx }
+    }
+
+    #[test]
+    fn coalesce_keeps_newest_row_per_key() {
+        let rows = coalesce(vec![
+            pending(owner("a", "w", 5)),
+            pending(owner("b", "x", 3)),
+            pending(owner("a", "y", 4)),
+            pending(owner("b", "x", 6)),
+            pending(owner("a", "z", 5)),
+        ]);
+
+        let rows: Vec<_> = rows
+            .iter()
+            .map(|(r, txs)| (&*r.mint_address, &*r.owner_address, r.slot, txs.len()))
+            .collect();
+        assert_eq!(rows, vec![("a", "z", 5, 3), ("b", "x", 6, 2)]);
+    }
+
+    #[test]
+    fn upserts_batch_in_one_statement() {
+        let calls = RefCell::new(vec![]);
+        let rows = [owner("a", "w", 1), owner("b", "x", 1)];
+
+        let results = upsert_rows(&rows, |rows| {
+            calls.borrow_mut().push(rows.len());
+            Ok(())
+        });
+
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(calls.into_inner(), vec![2]);
+    }
+
+    #[test]
+    fn retries_rows_individually_after_batch_fails() {
+        let calls = RefCell::new(vec![]);
+        let rows = [owner("a", "w", 1), owner("b", "bad", 1), owner("c", "y", 1)];
+
+        let results = upsert_rows(&rows, |rows| {
+            calls.borrow_mut().push(rows.len());
+
+            if rows.iter().any(|r| r.owner_address == "bad") {
+                bail!("Bad owner");
+            }
+
+            Ok(())
+        });
+
+        let results: Vec<_> = results
+            .iter()
+            .map(|r| r.clone().map_err(|e| e.to_string()))
+            .collect();
+        assert_eq!(results, vec![Ok(()), Err("Bad owner".into()), Ok(())]);
+        assert_eq!(calls.into_inner(), vec![3, 1, 1, 1]);
+    }
+
+    #[test]
+    fn does_not_retry_single_row() {
+        let calls = RefCell::new(vec![]);
+
+        let results = upsert_rows(&[owner("a", "bad", 1)], |rows| {
+            calls.borrow_mut().push(rows.len());
+            bail!("Bad owner");
+        });
+
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+        assert_eq!(calls.into_inner(), vec![1]);
+    }
+}
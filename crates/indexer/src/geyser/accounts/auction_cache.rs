@@ -9,13 +9,14 @@ use indexer_core::{
 };
 use metaplex::state::AuctionCache as AuctionCacheAccount;
 
-use super::Client;
+use super::{AccountVersion, Client};
 use crate::prelude::*;
 
 pub(crate) async fn process(
     client: &Client,
     cache_key: Pubkey,
     cache: AuctionCacheAccount,
+    version: AccountVersion,
 ) -> Result<()> {
     let AuctionCacheAccount {
         metadata,
@@ -37,6 +38,8 @@ pub(crate) async fn process(
         auction_ext: Owned(bs58::encode(auction_ext).into_string()),
         vault: Owned(bs58::encode(vault).into_string()),
         auction_manager: Owned(bs58::encode(auction_manager).into_string()),
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     let listing_address: Cow<str> = Owned(bs58::encode(auction).into_string());
@@ -50,6 +53,8 @@ pub(crate) async fn process(
                 metadata_index: i
                     .try_into()
                     .context("Metadata index was too big to store")?,
+                slot: version.slot,
+                write_version: version.write_version,
             })
         })
         .collect::<Result<Vec<_>>>()?;
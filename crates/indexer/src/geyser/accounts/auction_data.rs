@@ -12,13 +12,14 @@ use metaplex_auction::processor::{
     AuctionData as AuctionDataAccount, AuctionDataExtended, BidState, PriceFloor,
 };
 
-use super::Client;
+use super::{AccountVersion, Client};
 use crate::prelude::*;
 
 pub(crate) async fn process(
     client: &Client,
     key: Pubkey,
     auction: AuctionDataAccount,
+    version: AccountVersion,
 ) -> Result<()> {
     let total_uncancelled_bids;
     let highest_bid;
@@ -69,6 +70,8 @@ pub(crate) async fn process(
             PriceFloor::BlindedPrice(_) => Some(-1),
         },
         total_uncancelled_bids,
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -91,6 +94,7 @@ pub(crate) async fn process_extended(
     client: &Client,
     key: Pubkey,
     ext: AuctionDataExtended,
+    version: AccountVersion,
 ) -> Result<()> {
     let values = AuctionDataExt {
         address: Owned(bs58::encode(key).into_string()),
@@ -110,6 +114,8 @@ pub(crate) async fn process_extended(
                 .trim_end_matches('\0')
                 .to_owned(),
         ),
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -10,13 +10,14 @@ use mpl_auction_house::{
     AuctionHouse,
 };
 
-use super::Client;
+use super::{AccountVersion, Client};
 use crate::prelude::*;
 
 pub(crate) async fn process(
     client: &Client,
     key: Pubkey,
     account_data: AuctionHouse,
+    version: AccountVersion,
 ) -> Result<()> {
     let (ah_address, _) =
         find_auction_house_address(&account_data.authority, &account_data.treasury_mint);
@@ -63,6 +64,8 @@ pub(crate) async fn process(
         auction_house_fee_account: Owned(
             bs58::encode(account_data.auction_house_fee_account).into_string(),
         ),
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
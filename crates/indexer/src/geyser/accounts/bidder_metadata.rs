@@ -4,13 +4,14 @@ use indexer_core::{
 };
 use metaplex_auction::processor::BidderMetadata as BidderMetadataAccount;
 
-use super::Client;
+use super::{AccountVersion, Client};
 use crate::prelude::*;
 
 pub(crate) async fn process(
     client: &Client,
     _key: Pubkey,
     meta: BidderMetadataAccount,
+    version: AccountVersion,
 ) -> Result<()> {
     let BidderMetadataAccount {
         bidder_pubkey,
@@ -28,6 +29,8 @@ pub(crate) async fn process(
             .try_into()
             .context("Last bid amount was too high to store")?,
         cancelled,
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -23,13 +23,14 @@ use mpl_candy_machine::{
     EndSettings, GatekeeperConfig, HiddenSettings, WhitelistMintMode, WhitelistMintSettings,
 };
 
-use super::Client;
+use super::{AccountVersion, Client};
 use crate::prelude::*;
 
 pub(crate) async fn process(
     client: &Client,
     key: Pubkey,
     candy_machine: CandyMachine,
+    version: AccountVersion,
 ) -> Result<()> {
     let cm = DbCandyMachine {
         address: Owned(bs58::encode(key).into_string()),
@@ -39,6 +40,8 @@ pub(crate) async fn process(
             .token_mint
             .map(|t| Owned(bs58::encode(t).into_string())),
         items_redeemed: candy_machine.items_redeemed.try_into()?,
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -55,24 +58,38 @@ pub(crate) async fn process(
         .context("failed to insert candy machine")?;
 
     let mut futures: Vec<std::pin::Pin<Box<dyn Future<Output = Result<()>> + Send>>> = vec![
-        Box::pin(process_data(client, key, candy_machine.data.clone())),
-        Box::pin(process_creators(client, key, candy_machine.data.creators)),
+        Box::pin(process_data(
+            client,
+            key,
+            candy_machine.data.clone(),
+            version,
+        )),
+        Box::pin(process_creators(
+            client,
+            key,
+            candy_machine.data.creators,
+            version,
+        )),
     ];
 
     if let Some(es) = candy_machine.data.end_settings {
-        futures.push(Box::pin(process_end_settings(client, key, es)));
+        futures.push(Box::pin(process_end_settings(client, key, es, version)));
     };
 
     if let Some(hs) = candy_machine.data.hidden_settings {
-        futures.push(Box::pin(process_hidden_settings(client, key, hs)));
+        futures.push(Box::pin(process_hidden_settings(client, key, hs, version)));
     }
 
     if let Some(gk) = candy_machine.data.gatekeeper {
-        futures.push(Box::pin(process_gatekeeper_config(client, key, gk)));
+        futures.push(Box::pin(process_gatekeeper_config(
+            client, key, gk, version,
+        )));
     }
 
     if let Some(wlms) = candy_machine.data.whitelist_mint_settings {
-        futures.push(Box::pin(process_whitelist_mint_settings(client, key, wlms)));
+        futures.push(Box::pin(process_whitelist_mint_settings(
+            client, key, wlms, version,
+        )));
     }
 
     join_all(futures).await;
@@ -80,7 +97,12 @@ pub(crate) async fn process(
     Ok(())
 }
 
-async fn process_data(client: &Client, key: Pubkey, data: CandyMachineData) -> Result<()> {
+async fn process_data(
+    client: &Client,
+    key: Pubkey,
+    data: CandyMachineData,
+    version: AccountVersion,
+) -> Result<()> {
     let cm_data = CMData {
         candy_machine_address: Owned(bs58::encode(key).into_string()),
         uuid: Owned(data.uuid),
@@ -92,6 +114,8 @@ async fn process_data(client: &Client, key: Pubkey, data: CandyMachineData) -> R
         retain_authority: data.retain_authority,
         go_live_date: data.go_live_date,
         items_available: data.items_available.try_into()?,
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -109,13 +133,20 @@ async fn process_data(client: &Client, key: Pubkey, data: CandyMachineData) -> R
     Ok(())
 }
 
-async fn process_creators(client: &Client, key: Pubkey, creators: Vec<Creator>) -> Result<()> {
+async fn process_creators(
+    client: &Client,
+    key: Pubkey,
+    creators: Vec<Creator>,
+    version: AccountVersion,
+) -> Result<()> {
     for creator in creators {
         let c = CMCreator {
             candy_machine_address: Owned(bs58::encode(key).into_string()),
             creator_address: Owned(bs58::encode(creator.address).into_string()),
             verified: creator.verified,
             share: creator.share.into(),
+            slot: version.slot,
+            write_version: version.write_version,
         };
 
         client
@@ -134,7 +165,12 @@ async fn process_creators(client: &Client, key: Pubkey, creators: Vec<Creator>)
     Ok(())
 }
 
-async fn process_end_settings(client: &Client, key: Pubkey, es: EndSettings) -> Result<()> {
+async fn process_end_settings(
+    client: &Client,
+    key: Pubkey,
+    es: EndSettings,
+    version: AccountVersion,
+) -> Result<()> {
     let end_setting = CMEndSetting {
         candy_machine_address: Owned(bs58::encode(key).into_string()),
         end_setting_type: match es.end_setting_type {
@@ -142,6 +178,8 @@ async fn process_end_settings(client: &Client, key: Pubkey, es: EndSettings) ->
             EndSettingType::Amount => DbEndSettingType::Amount,
         },
         number: es.number.try_into()?,
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -159,12 +197,19 @@ async fn process_end_settings(client: &Client, key: Pubkey, es: EndSettings) ->
     Ok(())
 }
 
-async fn process_hidden_settings(client: &Client, key: Pubkey, hs: HiddenSettings) -> Result<()> {
+async fn process_hidden_settings(
+    client: &Client,
+    key: Pubkey,
+    hs: HiddenSettings,
+    version: AccountVersion,
+) -> Result<()> {
     let hidden_setting = CMHiddenSetting {
         candy_machine_address: Owned(bs58::encode(key).into_string()),
         name: Owned(hs.name),
         uri: Owned(hs.uri),
         hash: hs.hash.to_vec(),
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -186,11 +231,14 @@ async fn process_gatekeeper_config(
     client: &Client,
     key: Pubkey,
     gk: GatekeeperConfig,
+    version: AccountVersion,
 ) -> Result<()> {
     let gatekeeper = CMGateKeeperConfig {
         candy_machine_address: Owned(bs58::encode(key).into_string()),
         gatekeeper_network: Owned(bs58::encode(gk.gatekeeper_network).into_string()),
         expire_on_use: gk.expire_on_use,
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -211,6 +259,7 @@ async fn process_whitelist_mint_settings(
     client: &Client,
     key: Pubkey,
     wlms: WhitelistMintSettings,
+    version: AccountVersion,
 ) -> Result<()> {
     let whitelist_mint_setting = CMWhitelistMintSetting {
         candy_machine_address: Owned(bs58::encode(key).into_string()),
@@ -225,6 +274,8 @@ async fn process_whitelist_mint_settings(
             .map(TryInto::try_into)
             .transpose()
             .context("error casting u64 to i64!")?,
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -245,11 +296,14 @@ pub(crate) async fn process_config_line(
     client: &Client,
     key: Pubkey,
     config_line: ConfigLine,
+    version: AccountVersion,
 ) -> Result<()> {
     let row = CMConfigLine {
         address: Owned(bs58::encode(key).into_string()),
         name: Owned(config_line.name),
         uri: Owned(config_line.uri.trim_end_matches('\0').to_owned()),
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -271,11 +325,14 @@ pub(crate) async fn process_collection_pda(
     client: &Client,
     key: Pubkey,
     collection_pda: CollectionPDA,
+    version: AccountVersion,
 ) -> Result<()> {
     let row = CMCollectionPDA {
         address: Owned(bs58::encode(key).into_string()),
         mint: Owned(bs58::encode(collection_pda.mint).into_string()),
         candy_machine: Owned(bs58::encode(collection_pda.candy_machine).into_string()),
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
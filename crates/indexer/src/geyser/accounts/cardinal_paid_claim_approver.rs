@@ -4,13 +4,14 @@ use indexer_core::{
     prelude::*,
 };
 
-use super::Client;
+use super::{AccountVersion, Client};
 use crate::prelude::*;
 
 pub(crate) async fn process(
     client: &Client,
     key: Pubkey,
     paid_claim_approver: PaidClaimApproverAccount,
+    version: AccountVersion,
 ) -> Result<()> {
     let row = CardinalPaidClaimApprover {
         paid_claim_approver_address: Owned(bs58::encode(key).into_string()),
@@ -28,6 +29,8 @@ pub(crate) async fn process(
         paid_claim_approver_payment_mint: Owned(
             bs58::encode(paid_claim_approver.payment_mint).into_string(),
         ),
+        slot: version.slot,
+        write_version: version.write_version,
     };
     trace!("Paid Claim Approver {:?}", row);
     client
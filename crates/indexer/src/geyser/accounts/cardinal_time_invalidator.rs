@@ -5,13 +5,14 @@ use indexer_core::{
     util,
 };
 
-use super::Client;
+use super::{AccountVersion, Client};
 use crate::prelude::*;
 
 pub(crate) async fn process(
     client: &Client,
     key: Pubkey,
     time_invalidator: TimeInvalidatorAccount,
+    version: AccountVersion,
 ) -> Result<()> {
     let row = CardinalTimeInvalidator {
         time_invalidator_address: Owned(bs58::encode(key).into_string()),
@@ -47,6 +48,8 @@ pub(crate) async fn process(
             .disable_partial_extension
             .map(TryFrom::try_from)
             .transpose()?,
+        slot: version.slot,
+        write_version: version.write_version,
     };
     trace!("Time invalidator {:?}", row);
     client
@@ -16,7 +16,7 @@ use indexer_core::{
     util,
 };
 
-use super::Client;
+use super::{AccountVersion, Client};
 use crate::prelude::*;
 
 #[inline]
@@ -234,6 +234,7 @@ pub(crate) async fn process(
     client: &Client,
     key: Pubkey,
     token_manager: TokenManagerAccount,
+    version: AccountVersion,
 ) -> Result<()> {
     let current_token_managers = get_current_token_managers(client, key).await?;
 
@@ -267,6 +268,8 @@ pub(crate) async fn process(
         transfer_authority: token_manager
             .transfer_authority
             .map(|k| Owned(bs58::encode(k).into_string())),
+        slot: version.slot,
+        write_version: version.write_version,
     };
     trace!("Processing token manager {:?}", row);
 
@@ -289,7 +292,7 @@ pub(crate) async fn process(
         .map(|i| bs58::encode(i).into_string())
         .collect();
     // process invalidators into separate table
-    process_invalidators(client, key, invalidator_strings).await?;
+    process_invalidators(client, key, invalidator_strings, version).await?;
     Ok(())
 }
 
@@ -297,40 +300,53 @@ async fn process_invalidators(
     client: &Client,
     token_manager_address: Pubkey,
     invalidators: Vec<String>,
+    version: AccountVersion,
 ) -> Result<()> {
-    for invalidator in invalidators {
-        let row = CardinalTokenManagerInvalidator {
-            token_manager_address: Owned(bs58::encode(token_manager_address).into_string()),
-            invalidator: Owned(invalidator),
-        };
-        client
-            .db()
-            .run(move |db| {
-                delete(cardinal_token_manager_invalidators::table)
-                    .filter(
-                        cardinal_token_manager_invalidators::token_manager_address
-                            .eq(bs58::encode(token_manager_address).into_string()),
-                    )
-                    .execute(db)
-            })
-            .await
-            .context("failed to delete existing invalidators")?;
+    let address = bs58::encode(token_manager_address).into_string();
+    let rows = invalidators
+        .into_iter()
+        .map(|i| CardinalTokenManagerInvalidator {
+            token_manager_address: Owned(address.clone()),
+            invalidator: Owned(i),
+            slot: version.slot,
+            write_version: version.write_version,
+        })
+        .collect::<Vec<_>>();
 
-        client
-            .db()
-            .run(move |db| {
-                insert_into(cardinal_token_manager_invalidators::table)
-                    .values(&row)
-                    .on_conflict((
-                        cardinal_token_manager_invalidators::token_manager_address,
-                        cardinal_token_manager_invalidators::invalidator,
+    client
+        .db()
+        .run(move |db| {
+            upsert_children(
+                db,
+                version,
+                || {
+                    cardinal_token_managers::table
+                        .select((
+                            cardinal_token_managers::slot,
+                            cardinal_token_managers::write_version,
+                        ))
+                        .filter(cardinal_token_managers::address.eq(&address))
+                        .for_update()
+                        .first::<(i64, i64)>(db)
+                        .optional()
+                        .map(|v| v.map(Into::into))
+                },
+                || {
+                    delete(cardinal_token_manager_invalidators::table.filter(
+                        cardinal_token_manager_invalidators::token_manager_address.eq(&address),
                     ))
-                    .do_update()
-                    .set(&row)
-                    .execute(db)
-            })
-            .await
-            .context("failed to insert invalidator")?;
-    }
+                    .execute(db)?;
+
+                    insert_into(cardinal_token_manager_invalidators::table)
+                        .values(&rows)
+                        .on_conflict_do_nothing()
+                        .execute(db)
+                        .map(|_| ())
+                },
+            )
+        })
+        .await
+        .context("failed to insert invalidators")?;
+
     Ok(())
 }
@@ -4,13 +4,14 @@ use indexer_core::{
     prelude::*,
 };
 
-use super::Client;
+use super::{AccountVersion, Client};
 use crate::prelude::*;
 
 pub(crate) async fn process(
     client: &Client,
     key: Pubkey,
     use_invalidator: UseInvalidatorAccount,
+    version: AccountVersion,
 ) -> Result<()> {
     let row = CardinalUseInvalidator {
         use_invalidator_address: Owned(bs58::encode(key).into_string()),
@@ -45,6 +46,8 @@ pub(crate) async fn process(
             .max_usages
             .map(TryFrom::try_from)
             .transpose()?,
+        slot: version.slot,
+        write_version: version.write_version,
     };
     client
         .db()
@@ -8,14 +8,14 @@ use mpl_token_metadata::state::{
     MasterEditionV2 as MasterEditionV2Account,
 };
 
-use super::Client;
+use super::{AccountVersion, Client};
 use crate::prelude::*;
 
 pub(crate) async fn process(
     client: &Client,
     edition_key: Pubkey,
     edition: EditionAccount,
-    slot: u64,
+    version: AccountVersion,
 ) -> Result<()> {
     let row = Edition {
         address: Owned(bs58::encode(edition_key).into_string()),
@@ -24,10 +24,8 @@ pub(crate) async fn process(
             .edition
             .try_into()
             .context("Edition ID is too high to store")?,
-        slot: Some(
-            slot.try_into()
-                .context("Edition slot was too big to store")?,
-        ),
+        slot: Some(version.slot),
+        write_version: Some(version.write_version),
     };
 
     client
@@ -50,7 +48,7 @@ pub(crate) async fn process_master(
     client: &Client,
     master_key: Pubkey,
     master_edition: MasterEditionV2Account,
-    slot: u64,
+    version: AccountVersion,
 ) -> Result<()> {
     let row = MasterEdition {
         address: Owned(bs58::encode(master_key).into_string()),
@@ -65,10 +63,8 @@ pub(crate) async fn process_master(
                     .context("Master edition max supply is too high to store")
             })
             .transpose()?,
-        slot: Some(
-            slot.try_into()
-                .context("Master edition slot was too big to store")?,
-        ),
+        slot: Some(version.slot),
+        write_version: Some(version.write_version),
     };
 
     client
@@ -20,10 +20,15 @@ use tribeca_govern::{
     ProposalMeta, Vote,
 };
 
-use super::Client;
+use super::{AccountVersion, Client};
 use crate::prelude::*;
 
-pub(crate) async fn process_governor(client: &Client, key: Pubkey, g: Governor) -> Result<()> {
+pub(crate) async fn process_governor(
+    client: &Client,
+    key: Pubkey,
+    g: Governor,
+    version: AccountVersion,
+) -> Result<()> {
     let row = GovernorModel {
         address: Owned(key.to_string()),
         base: Owned(g.base.to_string()),
@@ -31,6 +36,8 @@ pub(crate) async fn process_governor(client: &Client, key: Pubkey, g: Governor)
         proposal_count: g.proposal_count.try_into()?,
         electorate: Owned(g.electorate.to_string()),
         smart_wallet: Owned(g.smart_wallet.to_string()),
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -46,13 +53,14 @@ pub(crate) async fn process_governor(client: &Client, key: Pubkey, g: Governor)
         .await
         .context("failed to insert governor ")?;
 
-    process_governance_params(client, key, g.params).await
+    process_governance_params(client, key, g.params, version).await
 }
 
 async fn process_governance_params(
     client: &Client,
     key: Pubkey,
     param: GovernanceParameters,
+    version: AccountVersion,
 ) -> Result<()> {
     let row = GovernanceParameterModel {
         governor_address: Owned(key.to_string()),
@@ -60,6 +68,8 @@ async fn process_governance_params(
         voting_period: param.voting_period.try_into()?,
         quorum_votes: param.quorum_votes.try_into()?,
         timelock_delay_seconds: param.timelock_delay_seconds,
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -78,7 +88,12 @@ async fn process_governance_params(
     Ok(())
 }
 
-pub(crate) async fn process_vote(client: &Client, key: Pubkey, v: Vote) -> Result<()> {
+pub(crate) async fn process_vote(
+    client: &Client,
+    key: Pubkey,
+    v: Vote,
+    version: AccountVersion,
+) -> Result<()> {
     let row = VoteModel {
         address: Owned(key.to_string()),
         proposal: Owned(v.proposal.to_string()),
@@ -86,6 +101,8 @@ pub(crate) async fn process_vote(client: &Client, key: Pubkey, v: Vote) -> Resul
         bump: v.bump.try_into()?,
         side: v.side.try_into()?,
         weight: v.weight.try_into()?,
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -104,12 +121,19 @@ pub(crate) async fn process_vote(client: &Client, key: Pubkey, v: Vote) -> Resul
     Ok(())
 }
 
-pub(crate) async fn process_meta(client: &Client, key: Pubkey, meta: ProposalMeta) -> Result<()> {
+pub(crate) async fn process_meta(
+    client: &Client,
+    key: Pubkey,
+    meta: ProposalMeta,
+    version: AccountVersion,
+) -> Result<()> {
     let row = ProposalMetaModel {
         address: Owned(key.to_string()),
         proposal: Owned(meta.proposal.to_string()),
         title: Owned(meta.title),
         description_link: Owned(meta.description_link),
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -132,6 +156,7 @@ pub(crate) async fn process_proposal(
     client: &Client,
     key: Pubkey,
     proposal: Proposal,
+    version: AccountVersion,
 ) -> Result<()> {
     let row = ProposalModel {
         address: Owned(key.to_string()),
@@ -149,6 +174,8 @@ pub(crate) async fn process_proposal(
         voting_ends_at: proposal.voting_ends_at,
         queued_at: proposal.queued_at,
         queued_transaction: Owned(proposal.queued_transaction.to_string()),
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -164,19 +191,22 @@ pub(crate) async fn process_proposal(
         .await
         .context("failed to insert proposal ")?;
 
-    process_instructions(client, key, proposal.instructions).await
+    process_instructions(client, key, proposal.instructions, version).await
 }
 
 pub(crate) async fn process_instructions(
     client: &Client,
     key: Pubkey,
     instructions: Vec<ProposalInstruction>,
+    version: AccountVersion,
 ) -> Result<()> {
     for ins in instructions {
         let i = ProposalInstructionModel {
             proposal_address: Owned(key.to_string()),
             program_id: Owned(ins.program_id.to_string()),
             data: ins.data,
+            slot: version.slot,
+            write_version: version.write_version,
         };
 
         client
@@ -194,7 +224,7 @@ pub(crate) async fn process_instructions(
             })
             .await
             .context("failed to insert proposal instruction ")?;
-        process_account_meta(client, key, ins.program_id, ins.keys).await?;
+        process_account_meta(client, key, ins.program_id, ins.keys, version).await?;
     }
 
     Ok(())
@@ -205,6 +235,7 @@ async fn process_account_meta(
     key: Pubkey,
     program_id: Pubkey,
     account_metas: Vec<ProposalAccountMeta>,
+    version: AccountVersion,
 ) -> Result<()> {
     for acc in account_metas {
         let row = ProposalAccountMetaModel {
@@ -213,6 +244,8 @@ async fn process_account_meta(
             pubkey: Owned(acc.pubkey.to_string()),
             is_signer: acc.is_signer,
             is_writable: acc.is_writable,
+            slot: version.slot,
+            write_version: version.write_version,
         };
 
         client
@@ -11,13 +11,14 @@ use indexer_core::{
     uuid::Uuid,
 };
 
-use super::Client;
+use super::{AccountVersion, Client};
 use crate::prelude::*;
 
 pub(crate) async fn process(
     client: &Client,
     key: Pubkey,
     account_data: ConnectionV2,
+    version: AccountVersion,
 ) -> Result<()> {
     let row = DbGraphConnection {
         address: Owned(bs58::encode(key).into_string()),
@@ -28,6 +29,8 @@ pub(crate) async fn process(
             .disconnected_at
             .map(util::unix_timestamp)
             .transpose()?,
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
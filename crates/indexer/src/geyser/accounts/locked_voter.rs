@@ -12,10 +12,15 @@ use indexer_core::{
 };
 use tribeca_locked_voter::{Escrow, Locker, LockerParams, LockerWhitelistEntry};
 
-use super::Client;
+use super::{AccountVersion, Client};
 use crate::prelude::*;
 
-pub(crate) async fn process_locker(client: &Client, key: Pubkey, l: Locker) -> Result<()> {
+pub(crate) async fn process_locker(
+    client: &Client,
+    key: Pubkey,
+    l: Locker,
+    version: AccountVersion,
+) -> Result<()> {
     let row = LockerModel {
         address: Owned(key.to_string()),
         base: Owned(l.base.to_string()),
@@ -23,6 +28,8 @@ pub(crate) async fn process_locker(client: &Client, key: Pubkey, l: Locker) -> R
         token_mint: Owned(l.token_mint.to_string()),
         locked_supply: l.locked_supply.try_into()?,
         governor: Owned(l.governor.to_string()),
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -38,10 +45,15 @@ pub(crate) async fn process_locker(client: &Client, key: Pubkey, l: Locker) -> R
         .await
         .context("failed to insert locker ")?;
 
-    process_locker_params(client, key, l.params).await
+    process_locker_params(client, key, l.params, version).await
 }
 
-async fn process_locker_params(client: &Client, key: Pubkey, params: LockerParams) -> Result<()> {
+async fn process_locker_params(
+    client: &Client,
+    key: Pubkey,
+    params: LockerParams,
+    version: AccountVersion,
+) -> Result<()> {
     let row = LockerParamModel {
         locker_address: Owned(key.to_string()),
         whitelist_enabled: params.whitelist_enabled,
@@ -49,6 +61,8 @@ async fn process_locker_params(client: &Client, key: Pubkey, params: LockerParam
         min_stake_duration: params.min_stake_duration.try_into()?,
         max_stake_duration: params.max_stake_duration.try_into()?,
         proposal_activation_min_votes: params.proposal_activation_min_votes.try_into()?,
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -67,7 +81,12 @@ async fn process_locker_params(client: &Client, key: Pubkey, params: LockerParam
     Ok(())
 }
 
-pub(crate) async fn process_escrow(client: &Client, key: Pubkey, es: Escrow) -> Result<()> {
+pub(crate) async fn process_escrow(
+    client: &Client,
+    key: Pubkey,
+    es: Escrow,
+    version: AccountVersion,
+) -> Result<()> {
     let row = EscrowModel {
         address: Owned(key.to_string()),
         locker: Owned(es.locker.to_string()),
@@ -78,6 +97,8 @@ pub(crate) async fn process_escrow(client: &Client, key: Pubkey, es: Escrow) ->
         escrow_started_at: es.escrow_started_at,
         escrow_ends_at: es.escrow_ends_at,
         vote_delegate: Owned(es.vote_delegate.to_string()),
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -100,6 +121,7 @@ pub(crate) async fn process_locker_whitelist_entry(
     client: &Client,
     key: Pubkey,
     wl: LockerWhitelistEntry,
+    version: AccountVersion,
 ) -> Result<()> {
     let row = LockerWhitelistEntryModel {
         address: Owned(key.to_string()),
@@ -107,6 +129,8 @@ pub(crate) async fn process_locker_whitelist_entry(
         locker: Owned(wl.locker.to_string()),
         program_id: Owned(wl.program_id.to_string()),
         owner: Owned(wl.owner.to_string()),
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
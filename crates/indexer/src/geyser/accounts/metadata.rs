@@ -5,18 +5,18 @@ use indexer_core::{
         models::{FeedEventWallet, Metadata, MetadataCollectionKey, MetadataCreator, MintEvent},
         select,
         tables::{
-            feed_event_wallets, feed_events, metadata_collection_keys, metadata_creators,
-            metadatas, mint_events,
+            feed_event_wallets, feed_events, metadata_collection_keys, metadatas, mint_events,
         },
     },
     prelude::*,
     pubkeys::find_edition,
     uuid::Uuid,
 };
+use indexer_rabbitmq::Priority;
 use mpl_token_metadata::state::{Collection, Metadata as MetadataAccount, TokenStandard};
 
-use super::Client;
-use crate::prelude::*;
+use super::{AccountVersion, Client};
+use crate::{db::MetadataCreators, prelude::*};
 
 pub(crate) async fn process(
     client: &Client,
@@ -24,7 +24,9 @@ pub(crate) async fn process(
     meta: MetadataAccount,
     slot: u64,
     write_version: u64,
+    priority: Priority,
 ) -> Result<()> {
+    let version = AccountVersion::new(slot, write_version)?;
     let addr = bs58::encode(key).into_string();
     let (edition_pda_key, _bump) = find_edition(meta.mint);
     let row = Metadata {
@@ -45,10 +47,8 @@ pub(crate) async fn process(
             TokenStandard::Fungible => TokenStandardEnum::Fungible,
             TokenStandard::NonFungibleEdition => TokenStandardEnum::NonFungibleEdition,
         }),
-        slot: Some(
-            slot.try_into()
-                .context("Metadata slot was too big to store")?,
-        ),
+        slot: Some(version.slot),
+        write_version: Some(version.write_version),
     };
 
     let first_verified_creator: Option<Pubkey> = meta
@@ -65,60 +65,72 @@ pub(crate) async fn process(
             first_verified_creator,
             meta.data.uri.trim_end_matches('\0').to_owned(),
             (slot, write_version),
+            priority,
         )
         .await
         .context("Failed to dispatch metadata JSON job")?;
 
-    for (position, creator) in meta.data.creators.iter().flatten().enumerate() {
-        let row = MetadataCreator {
-            metadata_address: Owned(addr.clone()),
-            creator_address: Owned(bs58::encode(creator.address).into_string()),
-            share: creator.share.into(),
-            verified: creator.verified,
-            position: Some(
-                position
-                    .try_into()
-                    .context("Position was too big to store")?,
-            ),
-        };
+    let creators = meta
+        .data
+        .creators
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(position, creator)| {
+            Ok(MetadataCreator {
+                metadata_address: Owned(addr.clone()),
+                creator_address: Owned(bs58::encode(creator.address).into_string()),
+                share: creator.share.into(),
+                verified: creator.verified,
+                position: Some(
+                    position
+                        .try_into()
+                        .context("Position was too big to store")?,
+                ),
+                slot: version.slot,
+                write_version: version.write_version,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if let Some(feed_event_id) = feed_event_id.filter(|_| !creators.is_empty()) {
+        let wallets = creators
+            .iter()
+            .map(|c| FeedEventWallet {
+                wallet_address: c.creator_address.clone(),
+                feed_event_id,
+            })
+            .collect::<Vec<_>>();
 
         client
             .db()
             .run(move |db| {
-                insert_into(metadata_creators::table)
-                    .values(&row)
+                insert_into(feed_event_wallets::table)
+                    .values(&wallets)
                     .on_conflict((
-                        metadata_creators::metadata_address,
-                        metadata_creators::creator_address,
+                        feed_event_wallets::wallet_address,
+                        feed_event_wallets::feed_event_id,
                     ))
-                    .do_update()
-                    .set(&row)
+                    .do_nothing()
                     .execute(db)
-                    .context("failed to insert metadata creators")?;
-
-                if let Some(feed_event_id) = feed_event_id {
-                    insert_into(feed_event_wallets::table)
-                        .values(&FeedEventWallet {
-                            wallet_address: row.creator_address,
-                            feed_event_id,
-                        })
-                        .on_conflict((
-                            feed_event_wallets::wallet_address,
-                            feed_event_wallets::feed_event_id,
-                        ))
-                        .do_nothing()
-                        .execute(db)
-                        .context("Failed to insert feed event wallet")?;
-                }
-
-                Result::<_>::Ok(())
             })
             .await
-            .context("Failed to insert metadata creator")?;
+            .context("Failed to insert feed event wallets")?;
     }
 
+    client
+        .db()
+        .upsert(MetadataCreators {
+            address: addr.clone(),
+            version,
+            creators,
+        })
+        .await
+        .context("Failed to insert metadata creators")?;
+
     if meta.collection.is_some() {
-        index_metadata_collection_key(client, addr, meta.collection.context("err!")?).await?;
+        index_metadata_collection_key(client, addr, meta.collection.context("err!")?, version)
+            .await?;
     }
 
     Ok(())
@@ -177,11 +189,14 @@ async fn index_metadata_collection_key(
     client: &Client,
     addr: String,
     collection: Collection,
+    version: AccountVersion,
 ) -> Result<()> {
     let row = MetadataCollectionKey {
         metadata_address: Owned(addr),
         collection_address: Owned(collection.key.to_string()),
         verified: collection.verified,
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
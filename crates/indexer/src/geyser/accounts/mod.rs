@@ -20,4 +20,4 @@ pub mod receipt;
 pub mod smart_wallet;
 pub mod token;
 
-pub(self) use super::Client;
+pub(self) use super::{upsert_children, AccountVersion, Client};
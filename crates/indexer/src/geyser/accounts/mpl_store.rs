@@ -10,16 +10,19 @@ use indexer_core::{
     prelude::*,
     pubkeys::find_store_config,
 };
+use indexer_rabbitmq::Priority;
 use metaplex::state::{Store, WhitelistedCreator};
 use mpl_metaplex::state::StoreConfig;
 
-use super::Client;
+use super::{AccountVersion, Client};
 use crate::prelude::*;
 
 pub(crate) async fn process_config(
     client: &Client,
     key: Pubkey,
     config: StoreConfig,
+    version: AccountVersion,
+    priority: Priority,
 ) -> Result<()> {
     trace!("{:?}", &config.settings_uri);
 
@@ -27,7 +30,7 @@ pub(crate) async fn process_config(
     if let Some(ref uri) = config.settings_uri {
         debug!("dispatching store config: {:?}", uri);
         client
-            .dispatch_store_config(key, uri.clone())
+            .dispatch_store_config(key, uri.clone(), priority)
             .await
             .context("Failed to dispatch store config job")?;
     }
@@ -35,6 +38,8 @@ pub(crate) async fn process_config(
     let row = DbStoreConfig {
         address: Owned(addr),
         settings_uri: config.settings_uri.map(Owned),
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -56,11 +61,14 @@ pub(crate) async fn process_whitelisted_creator(
     client: &Client,
     key: Pubkey,
     creator: WhitelistedCreator,
+    version: AccountVersion,
 ) -> Result<()> {
     let row = DbWhitelistedCreator {
         address: Owned(bs58::encode(key).into_string()),
         creator_address: Owned(bs58::encode(creator.address).into_string()),
         activated: creator.activated,
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -78,13 +86,20 @@ pub(crate) async fn process_whitelisted_creator(
     Ok(())
 }
 
-pub(crate) async fn process(client: &Client, key: Pubkey, store: Store) -> Result<()> {
+pub(crate) async fn process(
+    client: &Client,
+    key: Pubkey,
+    store: Store,
+    version: AccountVersion,
+) -> Result<()> {
     let (config_address, _bump) = find_store_config(&key);
 
     let row = DbStore {
         address: Owned(bs58::encode(key).into_string()),
         public: store.public,
         config_address: Owned(bs58::encode(config_address).into_string()),
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
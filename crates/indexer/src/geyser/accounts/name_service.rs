@@ -3,6 +3,7 @@ use indexer_core::{
     db::{insert_into, models::TwitterHandle, tables::twitter_handle_name_services, update},
     prelude::*,
 };
+use indexer_rabbitmq::Priority;
 
 use super::Client;
 use crate::{prelude::*, search_dispatch::TwitterHandleDocument};
@@ -20,6 +21,7 @@ pub(crate) async fn process(
     write_version: u64,
     wallet: Pubkey,
     data: Vec<u8>,
+    priority: Priority,
 ) -> Result<()> {
     let th = TwitterHandleAndRegistry::deserialize(&mut data.as_slice())
         .context("failed to deserialize registry key and handle!")?;
@@ -95,7 +97,7 @@ pub(crate) async fn process(
     if let Some(backfill) = search_backfill {
         client
             .search()
-            .upsert_twitter_handle(backfill, key, document)
+            .upsert_twitter_handle(backfill, priority, key, document)
             .await
             .context("Failed to dispatch upsert twitter handle document job")?;
     }
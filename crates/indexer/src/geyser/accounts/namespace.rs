@@ -1,6 +1,7 @@
 use indexer_core::db::{
     insert_into, models::TwitterHandle, tables::twitter_handle_name_services, update,
 };
+use indexer_rabbitmq::Priority;
 use namespaces::state::Entry;
 
 use super::Client;
@@ -12,6 +13,7 @@ pub(crate) async fn process(
     slot: u64,
     write_version: u64,
     entry: Entry,
+    priority: Priority,
 ) -> Result<()> {
     let slot = i64::try_from(slot)?;
     let write_version = i64::try_from(write_version)?;
@@ -93,7 +95,7 @@ pub(crate) async fn process(
     if let Some(backfill) = search_backfill {
         client
             .search()
-            .upsert_twitter_handle(backfill, key, document)
+            .upsert_twitter_handle(backfill, priority, key, document)
             .await
             .context("Failed to dispatch upsert twitter handle document job")?;
     }
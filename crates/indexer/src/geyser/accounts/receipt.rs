@@ -20,13 +20,14 @@ use indexer_core::{
 };
 use mpl_auction_house::receipt::{BidReceipt, ListingReceipt, PurchaseReceipt};
 
-use super::Client;
+use super::{AccountVersion, Client};
 use crate::prelude::*;
 
 pub(crate) async fn process_listing_receipt(
     client: &Client,
     key: Pubkey,
     listing: ListingReceipt,
+    version: AccountVersion,
 ) -> Result<()> {
     let row = DbListingReceipt {
         address: Owned(bs58::encode(key).into_string()),
@@ -44,6 +45,8 @@ pub(crate) async fn process_listing_receipt(
         trade_state_bump: listing.trade_state_bump.into(),
         created_at: util::unix_timestamp(listing.created_at)?,
         canceled_at: listing.canceled_at.map(util::unix_timestamp).transpose()?,
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -105,6 +108,7 @@ pub(crate) async fn process_purchase_receipt(
     client: &Client,
     key: Pubkey,
     purchase: PurchaseReceipt,
+    version: AccountVersion,
 ) -> Result<()> {
     let row = DbPurchaseReceipt {
         address: Owned(bs58::encode(key).into_string()),
@@ -117,6 +121,8 @@ pub(crate) async fn process_purchase_receipt(
         price: purchase.price.try_into()?,
         bump: purchase.bump.into(),
         created_at: util::unix_timestamp(purchase.created_at)?,
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -181,6 +187,7 @@ pub(crate) async fn process_bid_receipt(
     client: &Client,
     key: Pubkey,
     bid_receipt: BidReceipt,
+    version: AccountVersion,
 ) -> Result<()> {
     let row = DbBidReceipt {
         address: Owned(bs58::encode(key).into_string()),
@@ -204,6 +211,8 @@ pub(crate) async fn process_bid_receipt(
             .canceled_at
             .map(util::unix_timestamp)
             .transpose()?,
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     let offer_event = client
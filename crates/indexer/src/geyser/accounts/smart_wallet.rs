@@ -5,7 +5,7 @@ use goki_smart_wallet::{
 };
 use indexer_core::{
     db::{
-        insert_into,
+        delete, insert_into,
         models::{
             InsBufferBundle, InsBufferBundleInsKey, InsBuffferBundleInstruction,
             InstructionBuffer as InstructionBufferModel, SmartWallet as SmartWalletModel,
@@ -21,13 +21,14 @@ use indexer_core::{
     prelude::*,
 };
 
-use super::Client;
+use super::{upsert_children, AccountVersion, Client};
 use crate::prelude::*;
 
 pub(crate) async fn process_smart_wallet(
     client: &Client,
     key: Pubkey,
     sm: SmartWallet,
+    version: AccountVersion,
 ) -> Result<()> {
     let row = SmartWalletModel {
         address: Owned(key.to_string()),
@@ -38,6 +39,8 @@ pub(crate) async fn process_smart_wallet(
         grace_period: sm.grace_period,
         owner_set_seqno: sm.owner_set_seqno.try_into()?,
         num_transactions: sm.num_transactions.try_into()?,
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -53,37 +56,63 @@ pub(crate) async fn process_smart_wallet(
         .await
         .context("failed to insert smart wallet ")?;
 
-    process_smart_wallet_owner(client, key, sm.owners).await
+    process_smart_wallet_owner(client, key, sm.owners, version).await
 }
 
 async fn process_smart_wallet_owner(
     client: &Client,
     key: Pubkey,
     owners: Vec<Pubkey>,
+    version: AccountVersion,
 ) -> Result<()> {
-    for (i, owner) in owners.iter().enumerate() {
-        let o = SmartWalletOwner {
-            smart_wallet_address: Owned(key.to_string()),
-            owner_address: Owned(owner.to_string()),
-            index: i.try_into()?,
-        };
-
-        client
-            .db()
-            .run(move |db| {
-                insert_into(smart_wallet_owners::table)
-                    .values(&o)
-                    .on_conflict((
-                        smart_wallet_owners::smart_wallet_address,
-                        smart_wallet_owners::owner_address,
-                    ))
-                    .do_update()
-                    .set(&o)
-                    .execute(db)
+    let address = key.to_string();
+    let rows = owners
+        .iter()
+        .enumerate()
+        .map(|(i, owner)| {
+            Ok(SmartWalletOwner {
+                smart_wallet_address: Owned(address.clone()),
+                owner_address: Owned(owner.to_string()),
+                index: i.try_into()?,
+                slot: version.slot,
+                write_version: version.write_version,
             })
-            .await
-            .context("failed to insert smart wallet owner")?;
-    }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    client
+        .db()
+        .run(move |db| {
+            upsert_children(
+                db,
+                version,
+                || {
+                    smart_wallets::table
+                        .select((smart_wallets::slot, smart_wallets::write_version))
+                        .filter(smart_wallets::address.eq(&address))
+                        .for_update()
+                        .first::<(i64, i64)>(db)
+                        .optional()
+                        .map(|v| v.map(Into::into))
+                },
+                || {
+                    delete(
+                        smart_wallet_owners::table
+                            .filter(smart_wallet_owners::smart_wallet_address.eq(&address)),
+                    )
+                    .execute(db)?;
+
+                    insert_into(smart_wallet_owners::table)
+                        .values(&rows)
+                        .on_conflict_do_nothing()
+                        .execute(db)
+                        .map(|_| ())
+                },
+            )
+        })
+        .await
+        .context("failed to insert smart wallet owners")?;
+
     Ok(())
 }
 
@@ -91,6 +120,7 @@ pub(crate) async fn process_transaction(
     client: &Client,
     key: Pubkey,
     tx: Transaction,
+    version: AccountVersion,
 ) -> Result<()> {
     let row = TransactionModel {
         address: Owned(key.to_string()),
@@ -103,6 +133,8 @@ pub(crate) async fn process_transaction(
         eta: tx.eta,
         executor: Owned(tx.executor.to_string()),
         executed_at: tx.executed_at,
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -117,71 +149,89 @@ pub(crate) async fn process_transaction(
         })
         .await
         .context("failed to insert transaction ")?;
-    process_intructions(client, key, tx.instructions).await
+    process_intructions(client, key, tx.instructions, version).await
 }
 
-async fn process_intructions(client: &Client, key: Pubkey, ins: Vec<TXInstruction>) -> Result<()> {
+async fn process_intructions(
+    client: &Client,
+    key: Pubkey,
+    ins: Vec<TXInstruction>,
+    version: AccountVersion,
+) -> Result<()> {
+    let address = key.to_string();
+    let mut rows = Vec::with_capacity(ins.len());
+    let mut key_rows = Vec::new();
+
     for i in ins {
-        let row = TXInstructionModel {
-            transaction_address: Owned(key.to_string()),
-            program_id: Owned(i.program_id.to_string()),
-            data: i.data,
-        };
+        let program_id = i.program_id.to_string();
 
-        client
-            .db()
-            .run(move |db| {
-                insert_into(tx_instructions::table)
-                    .values(&row)
-                    .on_conflict((
-                        tx_instructions::transaction_address,
-                        tx_instructions::program_id,
-                    ))
-                    .do_update()
-                    .set(&row)
-                    .execute(db)
-            })
-            .await
-            .context("failed to insert transaction instruction")?;
+        key_rows.extend(i.keys.into_iter().map(|k| TXInstructionKey {
+            transaction_address: Owned(address.clone()),
+            program_id: Owned(program_id.clone()),
+            pubkey: Owned(k.pubkey.to_string()),
+            is_signer: k.is_signer,
+            is_writable: k.is_writable,
+            slot: version.slot,
+            write_version: version.write_version,
+        }));
 
-        process_tx_instruction_keys(client, key, i.program_id, i.keys).await?;
+        rows.push(TXInstructionModel {
+            transaction_address: Owned(address.clone()),
+            program_id: Owned(program_id),
+            data: i.data,
+            slot: version.slot,
+            write_version: version.write_version,
+        });
     }
 
-    Ok(())
-}
+    // Rows sharing a key are collapsed into one, and the last such row should
+    // win, so insert in reverse order and ignore conflicts
+    rows.reverse();
+    key_rows.reverse();
 
-async fn process_tx_instruction_keys(
-    client: &Client,
-    tx_addr: Pubkey,
-    id: Pubkey,
-    keys: Vec<TXAccountMeta>,
-) -> Result<()> {
-    for key in keys {
-        let k = TXInstructionKey {
-            transaction_address: Owned(tx_addr.to_string()),
-            program_id: Owned(id.to_string()),
-            pubkey: Owned(key.pubkey.to_string()),
-            is_signer: key.is_signer,
-            is_writable: key.is_writable,
-        };
+    client
+        .db()
+        .run(move |db| {
+            upsert_children(
+                db,
+                version,
+                || {
+                    transactions::table
+                        .select((transactions::slot, transactions::write_version))
+                        .filter(transactions::address.eq(&address))
+                        .for_update()
+                        .first::<(i64, i64)>(db)
+                        .optional()
+                        .map(|v| v.map(Into::into))
+                },
+                || {
+                    delete(
+                        tx_instruction_keys::table
+                            .filter(tx_instruction_keys::transaction_address.eq(&address)),
+                    )
+                    .execute(db)?;
 
-        client
-            .db()
-            .run(move |db| {
-                insert_into(tx_instruction_keys::table)
-                    .values(&k)
-                    .on_conflict((
-                        tx_instruction_keys::transaction_address,
-                        tx_instruction_keys::program_id,
-                        tx_instruction_keys::pubkey,
-                    ))
-                    .do_update()
-                    .set(&k)
-                    .execute(db)
-            })
-            .await
-            .context("failed to insert transaction instruction account metadata")?;
-    }
+                    delete(
+                        tx_instructions::table
+                            .filter(tx_instructions::transaction_address.eq(&address)),
+                    )
+                    .execute(db)?;
+
+                    insert_into(tx_instructions::table)
+                        .values(&rows)
+                        .on_conflict_do_nothing()
+                        .execute(db)?;
+
+                    insert_into(tx_instruction_keys::table)
+                        .values(&key_rows)
+                        .on_conflict_do_nothing()
+                        .execute(db)
+                        .map(|_| ())
+                },
+            )
+        })
+        .await
+        .context("failed to insert transaction instructions")?;
 
     Ok(())
 }
@@ -190,6 +240,7 @@ pub(crate) async fn process_subaccount_info(
     client: &Client,
     key: Pubkey,
     acc: SubaccountInfo,
+    version: AccountVersion,
 ) -> Result<()> {
     let s = SubaccountInfoModel {
         address: Owned(key.to_string()),
@@ -199,6 +250,8 @@ pub(crate) async fn process_subaccount_info(
             SubaccountType::OwnerInvoker => 1,
         },
         index: acc.index.try_into()?,
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -221,6 +274,7 @@ pub(crate) async fn process_instruction_buffer(
     client: &Client,
     ib_addr: Pubkey,
     ib: InstructionBuffer,
+    version: AccountVersion,
 ) -> Result<()> {
     let ins_buffer = InstructionBufferModel {
         address: Owned(ib_addr.to_string()),
@@ -229,6 +283,8 @@ pub(crate) async fn process_instruction_buffer(
         authority: Owned(ib.authority.to_string()),
         executor: Owned(ib.executor.to_string()),
         smart_wallet: Owned(ib.smart_wallet.to_string()),
+        slot: version.slot,
+        write_version: version.write_version,
     };
 
     client
@@ -244,18 +300,21 @@ pub(crate) async fn process_instruction_buffer(
         .await
         .context("failed to insert instruction buffer")?;
 
-    process_ins_buffer_bundles(client, ib_addr, ib.bundles).await
+    process_ins_buffer_bundles(client, ib_addr, ib.bundles, version).await
 }
 
 async fn process_ins_buffer_bundles(
     client: &Client,
     ib_addr: Pubkey,
     bundles: Vec<InstructionBundle>,
+    version: AccountVersion,
 ) -> Result<()> {
     for bundle in bundles {
         let b = InsBufferBundle {
             instruction_buffer_address: Owned(ib_addr.to_string()),
             is_executed: bundle.is_executed,
+            slot: version.slot,
+            write_version: version.write_version,
         };
 
         client
@@ -270,7 +329,8 @@ async fn process_ins_buffer_bundles(
             })
             .await
             .context("failed to insert instruction buffer bundle")?;
-        process_ins_buffer_bundle_instructions(client, ib_addr, bundle.instructions).await?;
+        process_ins_buffer_bundle_instructions(client, ib_addr, bundle.instructions, version)
+            .await?;
     }
 
     Ok(())
@@ -280,12 +340,15 @@ async fn process_ins_buffer_bundle_instructions(
     client: &Client,
     ib_addr: Pubkey,
     bundle_instructions: Vec<TXInstruction>,
+    version: AccountVersion,
 ) -> Result<()> {
     for ins in bundle_instructions {
         let bundle_ins = InsBuffferBundleInstruction {
             instruction_buffer_address: Owned(ib_addr.to_string()),
             program_id: Owned(ins.program_id.to_string()),
             data: ins.data,
+            slot: version.slot,
+            write_version: version.write_version,
         };
         client
             .db()
@@ -302,7 +365,8 @@ async fn process_ins_buffer_bundle_instructions(
             })
             .await
             .context("failed to insert instruction buffer bundle instruction")?;
-        process_ins_buffer_bundle_ins_keys(client, ib_addr, ins.program_id, ins.keys).await?;
+        process_ins_buffer_bundle_ins_keys(client, ib_addr, ins.program_id, ins.keys, version)
+            .await?;
     }
     Ok(())
 }
@@ -311,6 +375,7 @@ async fn process_ins_buffer_bundle_ins_keys(
     ib_addr: Pubkey,
     program_id: Pubkey,
     keys: Vec<TXAccountMeta>,
+    version: AccountVersion,
 ) -> Result<()> {
     for key in keys {
         let k = InsBufferBundleInsKey {
@@ -319,6 +384,8 @@ async fn process_ins_buffer_bundle_ins_keys(
             pubkey: Owned(key.pubkey.to_string()),
             is_signer: key.is_signer,
             is_writable: key.is_writable,
+            slot: version.slot,
+            write_version: version.write_version,
         };
 
         client
@@ -1,17 +1,58 @@
 use std::{sync::Arc, time::Duration};
 
 use indexer_core::clap;
-use indexer_rabbitmq::{http_indexer, search_indexer};
+use indexer_rabbitmq::{http_indexer, lapin, search_indexer, Priority};
 
-use crate::{db::Pool, prelude::*, reqwest, search_dispatch};
+use crate::{
+    db::Pool,
+    prelude::*,
+    publish::{BoxPublisher, ProducerArgs},
+    reqwest, search_dispatch,
+};
 
-struct HttpProducers {
-    metadata_json: http_indexer::Producer<http_indexer::MetadataJson>,
-    store_config: http_indexer::Producer<http_indexer::StoreConfig>,
+/// Publishers for the messages the Geyser indexer sends to other indexers
+#[allow(missing_debug_implementations)]
+pub struct Publishers {
+    /// Requests for the HTTP indexer to fetch metadata JSON
+    pub metadata_json: BoxPublisher<http_indexer::MetadataJson>,
+    /// Requests for the HTTP indexer to fetch store configs
+    pub store_config: BoxPublisher<http_indexer::StoreConfig>,
+    /// Documents for the search indexer
+    pub search: BoxPublisher<search_indexer::Message>,
+}
+
+impl Publishers {
+    /// Create AMQP publishers for the given queue types
+    ///
+    /// # Errors
+    /// This function fails if AMQP producers cannot be created for the given
+    /// queue types.
+    pub async fn amqp(
+        conn: &lapin::Connection,
+        meta_queue: http_indexer::QueueType<http_indexer::MetadataJson>,
+        store_cfg_queue: http_indexer::QueueType<http_indexer::StoreConfig>,
+        search_queue: search_indexer::QueueType,
+        args: ProducerArgs,
+    ) -> Result<Self> {
+        Ok(Self {
+            metadata_json: args
+                .publisher(conn, meta_queue)
+                .await
+                .context("Couldn't create AMQP metadata JSON producer")?,
+            store_config: args
+                .publisher(conn, store_cfg_queue)
+                .await
+                .context("Couldn't create AMQP store config producer")?,
+            search: args
+                .publisher(conn, search_queue)
+                .await
+                .context("Couldn't create AMQP search producer")?,
+        })
+    }
 }
 
 /// Common arguments for Geyser indexer usage
-#[derive(Debug, clap::Args)]
+#[derive(Debug, Clone, clap::Args)]
 pub struct Args {
     /// Dialect API endpoint
     #[clap(long, env, requires("dialect-api-key"))]
@@ -20,9 +61,6 @@ pub struct Args {
     /// Dialect API key
     #[clap(long, env, requires("dialect-api-endpoint"))]
     dialect_api_key: Option<String>,
-
-    #[clap(flatten)]
-    search: search_dispatch::Args,
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -41,7 +79,8 @@ enum DialectEvent {
 pub struct Client {
     db: Pool,
     http: reqwest::Client,
-    http_prod: HttpProducers,
+    metadata_json: BoxPublisher<http_indexer::MetadataJson>,
+    store_config: BoxPublisher<http_indexer::StoreConfig>,
     search: search_dispatch::Client,
     dialect_api_endpoint: Option<String>,
     dialect_api_key: Option<String>,
@@ -51,18 +90,17 @@ impl Client {
     /// Construct a new client, wrapped in an `Arc`.
     ///
     /// # Errors
-    /// This function fails if AMQP producers cannot be created for the given queue
-    /// types.
-    pub async fn new_rc(
+    /// This function fails if the HTTP client cannot be created.
+    pub fn new_rc(
         db: Pool,
-        conn: &indexer_rabbitmq::lapin::Connection,
-        meta_queue: http_indexer::QueueType<http_indexer::MetadataJson>,
-        store_cfg_queue: http_indexer::QueueType<http_indexer::StoreConfig>,
-        search_queue: search_indexer::QueueType,
+        Publishers {
+            metadata_json,
+            store_config,
+            search,
+        }: Publishers,
         Args {
             dialect_api_endpoint,
             dialect_api_key,
-            search,
         }: Args,
     ) -> Result<Arc<Self>> {
         if dialect_api_endpoint.is_none() {
@@ -74,15 +112,9 @@ impl Client {
         Ok(Arc::new(Self {
             db,
             http: reqwest::Client::new(Duration::from_millis(500))?,
-            http_prod: HttpProducers {
-                metadata_json: http_indexer::Producer::new(conn, meta_queue)
-                    .await
-                    .context("Couldn't create AMQP metadata JSON producer")?,
-                store_config: http_indexer::Producer::new(conn, store_cfg_queue)
-                    .await
-                    .context("Couldn't create AMQP store config producer")?,
-            },
-            search: search_dispatch::Client::new(conn, search_queue, search).await?,
+            metadata_json,
+            store_config,
+            search: search_dispatch::Client::new(search),
             dialect_api_endpoint,
             dialect_api_key,
         }))
@@ -100,44 +132,46 @@ impl Client {
         &self.search
     }
 
-    /// Dispatch an AMQP message to the HTTP indexer to request off-chain
-    /// metadata JSON
+    /// Dispatch a message to the HTTP indexer to request off-chain metadata
+    /// JSON
     ///
     /// # Errors
-    /// This function fails if the AMQP payload cannot be sent.
+    /// This function fails if the message cannot be sent.
     pub async fn dispatch_metadata_json(
         &self,
         meta_address: Pubkey,
         first_verified_creator: Option<Pubkey>,
         uri: String,
         slot_info: (u64, u64),
+        priority: Priority,
     ) -> Result<(), indexer_rabbitmq::Error> {
-        self.http_prod
-            .metadata_json
-            .write(http_indexer::MetadataJson {
+        self.metadata_json
+            .write(&http_indexer::MetadataJson {
                 meta_address,
                 uri,
                 first_verified_creator,
                 slot_info,
+                priority,
             })
             .await
     }
 
-    /// Dispatch an AMQP message to the HTTP indexer to request off-chain store
+    /// Dispatch a message to the HTTP indexer to request off-chain store
     /// config data
     ///
     /// # Errors
-    /// This function fails if the AMQP payload cannot be sent.
+    /// This function fails if the message cannot be sent.
     pub async fn dispatch_store_config(
         &self,
         config_address: Pubkey,
         uri: String,
+        priority: Priority,
     ) -> Result<(), indexer_rabbitmq::Error> {
-        self.http_prod
-            .store_config
-            .write(http_indexer::StoreConfig {
+        self.store_config
+            .write(&http_indexer::StoreConfig {
                 config_address,
                 uri,
+                priority,
             })
             .await
     }
@@ -0,0 +1,278 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, VecDeque},
+    hash::{Hash, Hasher},
+};
+
+use tokio::sync::Mutex;
+
+use super::AccountUpdate;
+use crate::prelude::*;
+
+/// The maximum number of distinct account states remembered for each account
+/// within its latest slot
+const STATES_PER_SLOT: usize = 8;
+
+#[derive(Debug, Clone)]
+struct Seen {
+    slot: u64,
+    /// Hashes of the states applied in `slot`, oldest first
+    hashes: VecDeque<u64>,
+    tick: u64,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    seen: HashMap<Pubkey, Seen>,
+    /// Access order of keys, oldest first.  An entry is only live if its tick
+    /// matches the tick stored in `seen`; stale entries are skipped on
+    /// eviction and periodically compacted away.
+    order: VecDeque<(Pubkey, u64)>,
+    tick: u64,
+}
+
+/// An update accepted by [`Dedup::check`], which should be passed to
+/// [`Dedup::record`] once it has been applied
+#[derive(Debug, Clone, Copy)]
+pub struct Accepted {
+    key: Pubkey,
+    slot: u64,
+    hash: u64,
+}
+
+/// Bounded LRU of the account states applied in the latest slot of each
+/// account, used to discard duplicate and stale updates when several Geyser
+/// plugin instances publish to the same exchange.
+///
+/// Write versions are assigned independently by each validator, so they are
+/// never compared.  Updates from slots older than the latest applied slot are
+/// discarded, as are updates carrying any state already applied in the latest
+/// slot, so that a late duplicate cannot revert a newer state from the same
+/// slot.  Accounts evicted from the cache are treated as unseen.
+///
+/// Updates must only be [recorded](Self::record) once they have been applied,
+/// so that updates which fail, or which are discarded with an abandoned fork,
+/// are not treated as seen.
+#[derive(Debug)]
+pub struct Dedup {
+    capacity: usize,
+    inner: Mutex<Inner>,
+}
+
+impl Dedup {
+    /// Construct a new deduplication cache tracking at most `capacity`
+    /// accounts
+    ///
+    /// # Errors
+    /// This function fails if `capacity` is zero.
+    pub fn new(capacity: usize) -> Result<Self> {
+        if capacity == 0 {
+            bail!("Deduplication cache capacity must be nonzero");
+        }
+
+        Ok(Self {
+            capacity,
+            inner: Mutex::default(),
+        })
+    }
+
+    /// Check an account update, returning `None` if the state it carries, or
+    /// a state from a newer slot, has already been applied for the same
+    /// account.  Accepted updates are not recorded until passed to
+    /// [`record`](Self::record).
+    pub async fn check(&self, update: &AccountUpdate) -> Option<Accepted> {
+        let hash = hash(update);
+        let inner = self.inner.lock().await;
+
+        let fresh = inner.seen.get(&update.key).map_or(true, |prev| {
+            update.slot > prev.slot || (update.slot == prev.slot && !prev.hashes.contains(&hash))
+        });
+
+        if !fresh {
+            trace!(
+                "Skipping duplicate or stale update for {} (slot {})",
+                update.key,
+                update.slot
+            );
+
+            return None;
+        }
+
+        Some(Accepted {
+            key: update.key,
+            slot: update.slot,
+            hash,
+        })
+    }
+
+    /// Record an accepted update which has been applied
+    pub async fn record(&self, accepted: Accepted) {
+        let Accepted { key, slot, hash } = accepted;
+        let mut inner = self.inner.lock().await;
+        let Inner {
+            ref mut seen,
+            ref mut order,
+            ref mut tick,
+        } = *inner;
+
+        *tick += 1;
+        let entry = seen.entry(key).or_insert_with(|| Seen {
+            slot,
+            hashes: VecDeque::new(),
+            tick: 0,
+        });
+
+        if slot > entry.slot {
+            entry.slot = slot;
+            entry.hashes.clear();
+        }
+
+        if slot == entry.slot && !entry.hashes.contains(&hash) {
+            if entry.hashes.len() >= STATES_PER_SLOT {
+                entry.hashes.pop_front();
+            }
+
+            entry.hashes.push_back(hash);
+        }
+
+        entry.tick = *tick;
+        order.push_back((key, *tick));
+
+        while seen.len() > self.capacity {
+            let (key, tick) = match order.pop_front() {
+                Some(e) => e,
+                None => break,
+            };
+
+            if seen.get(&key).map_or(false, |s| s.tick == tick) {
+                seen.remove(&key);
+            }
+        }
+
+        if order.len() > self.capacity * 2 {
+            order.retain(|(k, t)| seen.get(k).map_or(false, |s| s.tick == *t));
+        }
+    }
+}
+
+/// Hash the account state carried by an update, ignoring the write version
+fn hash(update: &AccountUpdate) -> u64 {
+    let mut h = DefaultHasher::new();
+    update.lamports.hash(&mut h);
+    update.owner.hash(&mut h);
+    update.executable.hash(&mut h);
+    update.rent_epoch.hash(&mut h);
+    update.data.hash(&mut h);
+
+    h.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(slot: u64, write_version: u64, data: &[u8]) -> AccountUpdate {
+        AccountUpdate {
+            key: Pubkey::new_from_array([1; 32]),
+            lamports: 1,
+            owner: Pubkey::default(),
+            executable: false,
+            rent_epoch: 0,
+            data: data.to_vec(),
+            write_version,
+            slot,
+            is_startup: false,
+        }
+    }
+
+    /// Check an update, recording it as applied if it was accepted
+    async fn apply(dedup: &Dedup, update: &AccountUpdate) -> bool {
+        match dedup.check(update).await {
+            Some(accepted) => {
+                dedup.record(accepted).await;
+                true
+            },
+            None => false,
+        }
+    }
+
+    #[tokio::test]
+    async fn skips_duplicates_across_validators() {
+        let dedup = Dedup::new(16).unwrap();
+
+        assert!(apply(&dedup, &update(10, 500, &[1])).await);
+        // The same write as seen by another validator, with its own write
+        // version
+        assert!(!apply(&dedup, &update(10, 7, &[1])).await);
+        assert!(!apply(&dedup, &update(10, 900, &[1])).await);
+    }
+
+    #[tokio::test]
+    async fn orders_by_slot() {
+        let dedup = Dedup::new(16).unwrap();
+
+        assert!(apply(&dedup, &update(10, 500, &[1])).await);
+        assert!(!apply(&dedup, &update(9, 900, &[2])).await);
+        assert!(apply(&dedup, &update(11, 1, &[3])).await);
+        assert!(!apply(&dedup, &update(10, 501, &[4])).await);
+    }
+
+    #[tokio::test]
+    async fn accepts_new_state_in_same_slot() {
+        let dedup = Dedup::new(16).unwrap();
+
+        assert!(apply(&dedup, &update(10, 900, &[1])).await);
+        // A lower write version from a different validator is not stale
+        assert!(apply(&dedup, &update(10, 3, &[2])).await);
+        assert!(!apply(&dedup, &update(10, 4, &[2])).await);
+    }
+
+    #[tokio::test]
+    async fn skips_earlier_state_in_same_slot() {
+        let dedup = Dedup::new(16).unwrap();
+
+        // A late copy of the first state must not revert the second
+        assert!(apply(&dedup, &update(10, 1, &[1])).await);
+        assert!(apply(&dedup, &update(10, 2, &[2])).await);
+        assert!(!apply(&dedup, &update(10, 1, &[1])).await);
+
+        // States from older slots are forgotten
+        assert!(apply(&dedup, &update(11, 3, &[2])).await);
+        assert!(apply(&dedup, &update(11, 4, &[1])).await);
+    }
+
+    #[tokio::test]
+    async fn only_records_applied_updates() {
+        let dedup = Dedup::new(16).unwrap();
+        let u = update(10, 1, &[1]);
+
+        // An update which failed or was discarded is accepted again
+        assert!(dedup.check(&u).await.is_some());
+        let accepted = dedup.check(&u).await.unwrap();
+
+        dedup.record(accepted).await;
+        assert!(dedup.check(&u).await.is_none());
+
+        // Recording an older update applied concurrently does not regress the
+        // latest slot
+        let older = dedup.check(&update(11, 2, &[2])).await.unwrap();
+        let newer = dedup.check(&update(12, 3, &[3])).await.unwrap();
+        dedup.record(newer).await;
+        dedup.record(older).await;
+        assert!(dedup.check(&update(11, 4, &[4])).await.is_none());
+        assert!(dedup.check(&update(12, 3, &[3])).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn evicts_least_recent() {
+        let dedup = Dedup::new(1).unwrap();
+        let a = update(10, 1, &[1]);
+        let b = AccountUpdate {
+            key: Pubkey::new_from_array([2; 32]),
+            ..update(10, 1, &[1])
+        };
+
+        assert!(apply(&dedup, &a).await);
+        assert!(apply(&dedup, &b).await);
+        assert!(apply(&dedup, &a).await);
+    }
+}
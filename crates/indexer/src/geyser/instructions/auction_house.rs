@@ -0,0 +1,345 @@
+use borsh::BorshDeserialize;
+use indexer_core::{
+    db::{
+        insert_into,
+        models::{BuyInstruction, CancelInstruction, ExecuteSaleInstruction, SellInstruction},
+        tables::{
+            buy_instructions, cancel_instructions, execute_sale_instructions, sell_instructions,
+        },
+    },
+    prelude::*,
+};
+
+use super::{Client, InstructionContext};
+use crate::prelude::*;
+
+// Anchor instruction discriminators, i.e. sha256("global:<name>")[..8]
+const BUY: &[u8] = &[102, 6, 61, 18, 1, 218, 235, 234];
+const SELL: &[u8] = &[51, 230, 133, 164, 1, 127, 131, 173];
+const CANCEL: &[u8] = &[232, 219, 223, 41, 219, 236, 220, 190];
+const EXECUTE_SALE: &[u8] = &[37, 74, 217, 157, 79, 49, 35, 6];
+
+#[derive(BorshDeserialize, Debug, Clone, Copy)]
+struct Buy {
+    trade_state_bump: u8,
+    escrow_payment_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+}
+
+#[derive(BorshDeserialize, Debug, Clone, Copy)]
+struct Sell {
+    trade_state_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+}
+
+#[derive(BorshDeserialize, Debug, Clone, Copy)]
+struct Cancel {
+    buyer_price: u64,
+    token_size: u64,
+}
+
+#[derive(BorshDeserialize, Debug, Clone, Copy)]
+struct ExecuteSale {
+    escrow_payment_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+}
+
+#[inline]
+fn addr(key: &Pubkey) -> Cow<'static, str> {
+    Owned(bs58::encode(key).into_string())
+}
+
+async fn process_buy(
+    client: &Client,
+    args: Buy,
+    accounts: &[Pubkey],
+    ctx: InstructionContext<'_>,
+) -> Result<()> {
+    let row = match accounts {
+        [
+            wallet,
+            payment_account,
+            transfer_authority,
+            treasury_mint,
+            token_account,
+            metadata,
+            escrow_payment_account,
+            authority,
+            auction_house,
+            auction_house_fee_account,
+            buyer_trade_state,
+            ..,
+        ] => BuyInstruction {
+            wallet: addr(wallet),
+            payment_account: addr(payment_account),
+            transfer_authority: addr(transfer_authority),
+            treasury_mint: addr(treasury_mint),
+            token_account: addr(token_account),
+            metadata: addr(metadata),
+            escrow_payment_account: addr(escrow_payment_account),
+            authority: addr(authority),
+            auction_house: addr(auction_house),
+            auction_house_fee_account: addr(auction_house_fee_account),
+            buyer_trade_state: addr(buyer_trade_state),
+            trade_state_bump: args.trade_state_bump.into(),
+            escrow_payment_bump: args.escrow_payment_bump.into(),
+            buyer_price: args.buyer_price.try_into()?,
+            token_size: args.token_size.try_into()?,
+            slot: ctx.slot()?,
+            tx_signature: Owned(ctx.signature.to_owned()),
+            instruction_index: ctx.index()?,
+        },
+        _ => bail!("Missing accounts for buy instruction"),
+    };
+
+    client
+        .db()
+        .run(move |db| {
+            insert_into(buy_instructions::table)
+                .values(&row)
+                .on_conflict((
+                    buy_instructions::tx_signature,
+                    buy_instructions::instruction_index,
+                ))
+                .do_nothing()
+                .execute(db)
+        })
+        .await
+        .context("Failed to insert buy instruction")?;
+
+    Ok(())
+}
+
+async fn process_sell(
+    client: &Client,
+    args: Sell,
+    accounts: &[Pubkey],
+    ctx: InstructionContext<'_>,
+) -> Result<()> {
+    let row = match accounts {
+        [
+            wallet,
+            token_account,
+            metadata,
+            authority,
+            auction_house,
+            auction_house_fee_account,
+            seller_trade_state,
+            free_seller_trade_state,
+            _token_program,
+            _system_program,
+            program_as_signer,
+            ..,
+        ] => SellInstruction {
+            wallet: addr(wallet),
+            token_account: addr(token_account),
+            metadata: addr(metadata),
+            authority: addr(authority),
+            auction_house: addr(auction_house),
+            auction_house_fee_account: addr(auction_house_fee_account),
+            seller_trade_state: addr(seller_trade_state),
+            free_seller_trade_state: addr(free_seller_trade_state),
+            program_as_signer: addr(program_as_signer),
+            trade_state_bump: args.trade_state_bump.into(),
+            free_trade_state_bump: args.free_trade_state_bump.into(),
+            program_as_signer_bump: args.program_as_signer_bump.into(),
+            buyer_price: args.buyer_price.try_into()?,
+            token_size: args.token_size.try_into()?,
+            slot: ctx.slot()?,
+            tx_signature: Owned(ctx.signature.to_owned()),
+            instruction_index: ctx.index()?,
+        },
+        _ => bail!("Missing accounts for sell instruction"),
+    };
+
+    client
+        .db()
+        .run(move |db| {
+            insert_into(sell_instructions::table)
+                .values(&row)
+                .on_conflict((
+                    sell_instructions::tx_signature,
+                    sell_instructions::instruction_index,
+                ))
+                .do_nothing()
+                .execute(db)
+        })
+        .await
+        .context("Failed to insert sell instruction")?;
+
+    Ok(())
+}
+
+async fn process_cancel(
+    client: &Client,
+    args: Cancel,
+    accounts: &[Pubkey],
+    ctx: InstructionContext<'_>,
+) -> Result<()> {
+    let row = match accounts {
+        [
+            wallet,
+            token_account,
+            token_mint,
+            authority,
+            auction_house,
+            auction_house_fee_account,
+            trade_state,
+            ..,
+        ] => CancelInstruction {
+            wallet: addr(wallet),
+            token_account: addr(token_account),
+            token_mint: addr(token_mint),
+            authority: addr(authority),
+            auction_house: addr(auction_house),
+            auction_house_fee_account: addr(auction_house_fee_account),
+            trade_state: addr(trade_state),
+            buyer_price: args.buyer_price.try_into()?,
+            token_size: args.token_size.try_into()?,
+            slot: ctx.slot()?,
+            tx_signature: Owned(ctx.signature.to_owned()),
+            instruction_index: ctx.index()?,
+        },
+        _ => bail!("Missing accounts for cancel instruction"),
+    };
+
+    client
+        .db()
+        .run(move |db| {
+            insert_into(cancel_instructions::table)
+                .values(&row)
+                .on_conflict((
+                    cancel_instructions::tx_signature,
+                    cancel_instructions::instruction_index,
+                ))
+                .do_nothing()
+                .execute(db)
+        })
+        .await
+        .context("Failed to insert cancel instruction")?;
+
+    Ok(())
+}
+
+async fn process_execute_sale(
+    client: &Client,
+    args: ExecuteSale,
+    accounts: &[Pubkey],
+    ctx: InstructionContext<'_>,
+) -> Result<()> {
+    let row = match accounts {
+        [
+            buyer,
+            seller,
+            token_account,
+            token_mint,
+            metadata,
+            treasury_mint,
+            escrow_payment_account,
+            seller_payment_receipt_account,
+            buyer_receipt_token_account,
+            authority,
+            auction_house,
+            auction_house_fee_account,
+            auction_house_treasury,
+            buyer_trade_state,
+            seller_trade_state,
+            free_trade_state,
+            _token_program,
+            _system_program,
+            _ata_program,
+            program_as_signer,
+            ..,
+        ] => ExecuteSaleInstruction {
+            buyer: addr(buyer),
+            seller: addr(seller),
+            token_account: addr(token_account),
+            token_mint: addr(token_mint),
+            metadata: addr(metadata),
+            treasury_mint: addr(treasury_mint),
+            escrow_payment_account: addr(escrow_payment_account),
+            seller_payment_receipt_account: addr(seller_payment_receipt_account),
+            buyer_receipt_token_account: addr(buyer_receipt_token_account),
+            authority: addr(authority),
+            auction_house: addr(auction_house),
+            auction_house_fee_account: addr(auction_house_fee_account),
+            auction_house_treasury: addr(auction_house_treasury),
+            buyer_trade_state: addr(buyer_trade_state),
+            seller_trade_state: addr(seller_trade_state),
+            free_trade_state: addr(free_trade_state),
+            program_as_signer: addr(program_as_signer),
+            escrow_payment_bump: args.escrow_payment_bump.into(),
+            free_trade_state_bump: args.free_trade_state_bump.into(),
+            program_as_signer_bump: args.program_as_signer_bump.into(),
+            buyer_price: args.buyer_price.try_into()?,
+            token_size: args.token_size.try_into()?,
+            slot: ctx.slot()?,
+            tx_signature: Owned(ctx.signature.to_owned()),
+            instruction_index: ctx.index()?,
+        },
+        _ => bail!("Missing accounts for execute sale instruction"),
+    };
+
+    client
+        .db()
+        .run(move |db| {
+            insert_into(execute_sale_instructions::table)
+                .values(&row)
+                .on_conflict((
+                    execute_sale_instructions::tx_signature,
+                    execute_sale_instructions::instruction_index,
+                ))
+                .do_nothing()
+                .execute(db)
+        })
+        .await
+        .context("Failed to insert execute sale instruction")?;
+
+    Ok(())
+}
+
+pub(crate) async fn process(
+    client: &Client,
+    data: &[u8],
+    accounts: &[Pubkey],
+    ctx: InstructionContext<'_>,
+) -> Result<()> {
+    if data.len() < 8 {
+        return Ok(());
+    }
+
+    let (discriminator, mut args) = data.split_at(8);
+
+    match discriminator {
+        BUY => {
+            let args = Buy::deserialize(&mut args).context("Failed to deserialize buy args")?;
+
+            process_buy(client, args, accounts, ctx).await
+        },
+        SELL => {
+            let args = Sell::deserialize(&mut args).context("Failed to deserialize sell args")?;
+
+            process_sell(client, args, accounts, ctx).await
+        },
+        CANCEL => {
+            let args =
+                Cancel::deserialize(&mut args).context("Failed to deserialize cancel args")?;
+
+            process_cancel(client, args, accounts, ctx).await
+        },
+        EXECUTE_SALE => {
+            let args = ExecuteSale::deserialize(&mut args)
+                .context("Failed to deserialize execute sale args")?;
+
+            process_execute_sale(client, args, accounts, ctx).await
+        },
+        _ => Ok(()),
+    }
+}
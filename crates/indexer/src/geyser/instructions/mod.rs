@@ -0,0 +1,25 @@
+pub mod auction_house;
+
+pub(self) use super::Client;
+
+/// The transaction an instruction was received in, which identifies it
+#[derive(Debug, Clone, Copy)]
+pub struct InstructionContext<'a> {
+    /// The slot the containing transaction was processed in
+    pub slot: u64,
+    /// The Base58 signature of the containing transaction
+    pub signature: &'a str,
+    /// The position of this instruction within the transaction's
+    /// instructions, including inner instructions
+    pub index: usize,
+}
+
+impl<'a> InstructionContext<'a> {
+    pub(crate) fn slot(&self) -> Result<i64, std::num::TryFromIntError> {
+        self.slot.try_into()
+    }
+
+    pub(crate) fn index(&self) -> Result<i32, std::num::TryFromIntError> {
+        self.index.try_into()
+    }
+}
@@ -2,16 +2,25 @@
 
 mod accounts;
 mod client;
+mod dedup;
+mod instructions;
 mod programs;
+mod slot_buffer;
+mod slots;
 
 use std::{collections::HashSet, sync::Arc};
 
-pub use client::{Args as ClientArgs, Client};
+pub use client::{Args as ClientArgs, Client, Publishers};
+pub use dedup::{Accepted, Dedup};
+use futures_util::future;
 use indexer_core::pubkeys;
 pub(self) use indexer_rabbitmq::geyser::AccountUpdate;
 use indexer_rabbitmq::geyser::Message;
+use instructions::InstructionContext;
+pub use slot_buffer::{Pushed, Released, SlotBuffer};
 
-use crate::prelude::*;
+pub(self) use crate::db::{upsert_children, AccountVersion};
+use crate::{prelude::*, Processed};
 
 /// A value indicating a specific topic to ignore
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::EnumString, strum::Display)]
@@ -25,84 +34,256 @@ pub enum IgnoreType {
     Tokens,
 }
 
+/// The key Geyser messages are sharded across workers by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Shard {
+    /// Updates to a single account, which are applied in the order received
+    Account(Pubkey),
+    /// Slot status notifications, which are all handled by a single worker so
+    /// that the statuses for a slot are recorded in order
+    Slots,
+}
+
+impl crate::ShardKey for Message {
+    type Key = Shard;
+
+    fn shard_key(&self) -> Option<Shard> {
+        match self {
+            Self::AccountUpdate(u) => Some(Shard::Account(u.key)),
+            Self::SlotStatus { .. } => Some(Shard::Slots),
+            Self::InstructionNotify { .. } | Self::Transaction(_) => None,
+        }
+    }
+}
+
 /// Process a message from a Geyser RabbitMQ queue
 ///
+/// If a [`Dedup`] cache is provided, account updates already applied from
+/// another Geyser plugin instance, or older than one already applied, are
+/// skipped.  Buffered updates are checked again when their slot is confirmed,
+/// and only updates which were applied are recorded.
+///
+/// If a [`SlotBuffer`] is provided, live account updates are held until their
+/// slot is confirmed, and updates from abandoned forks are discarded.  The
+/// messages carrying buffered updates are not settled until the update has
+/// been applied, and updates received while the buffer is full wait for space
+/// without failing.
+///
 /// # Errors
 /// This function fails if an error occurs processing the message body.
 pub async fn process_message<H: std::hash::BuildHasher>(
     msg: Message,
     client: &Client,
     ignore_on_startup: Arc<HashSet<IgnoreType, H>>,
-) -> Result<()> {
-    let check_ignore =
-        |ty, update: &AccountUpdate| !(update.is_startup && ignore_on_startup.contains(&ty));
-
+    dedup: Option<&Arc<Dedup>>,
+    slot_buffer: Option<&SlotBuffer>,
+) -> Result<Processed> {
     match msg {
-        Message::AccountUpdate(update)
-            if update.owner == pubkeys::METADATA && check_ignore(IgnoreType::Metadata, &update) =>
-        {
-            programs::metadata::process(client, update).await
+        Message::AccountUpdate(update) => {
+            let accepted = match dedup {
+                Some(dedup) => match dedup.check(&update).await {
+                    Some(a) => Some((&**dedup, a)),
+                    None => return Ok(Processed::Done),
+                },
+                None => None,
+            };
+
+            match slot_buffer {
+                Some(buf) if !update.is_startup => match buf.push(update).await {
+                    Pushed::Ready(update) => {
+                        apply_account_update(update, client, &ignore_on_startup, accepted)
+                            .await
+                            .map(Into::into)
+                    },
+                    Pushed::Buffered(fut) => Ok(Processed::Deferred(fut)),
+                    Pushed::Discarded => Ok(Processed::Done),
+                },
+                _ => apply_account_update(update, client, &ignore_on_startup, accepted)
+                    .await
+                    .map(Into::into),
+            }
         },
-        Message::AccountUpdate(update) if update.owner == pubkeys::AUCTION => {
-            programs::auction::process(client, update).await
+        Message::InstructionNotify { program, .. } => {
+            // Instruction history rows are keyed by their transaction, so
+            // only full transaction messages can be recorded without
+            // duplicating rows on redelivery
+            trace!(
+                "Skipping instruction for {} received without its transaction",
+                program
+            );
+
+            Ok(Processed::Done)
         },
-        Message::AccountUpdate(update) if update.owner == pubkeys::METAPLEX => {
-            programs::metaplex::process(client, update).await
+        Message::Transaction(tx) => {
+            if let Some(ref err) = tx.error {
+                trace!("Skipping failed transaction {}: {}", tx.signature, err);
+
+                return Ok(Processed::Done);
+            }
+
+            for (index, ins) in tx.instructions.iter().enumerate() {
+                let ctx = InstructionContext {
+                    slot: tx.slot,
+                    signature: &tx.signature,
+                    index,
+                };
+
+                process_instruction(client, ins.program, &ins.data, &ins.accounts, ctx).await?;
+            }
+
+            Ok(Processed::Done)
+        },
+        Message::SlotStatus {
+            slot,
+            parent,
+            status,
+        } => {
+            slots::process(client, slot, parent, status).await?;
+
+            if let Some(buf) = slot_buffer {
+                let lanes = buf.update_status(slot, parent, status).await;
+
+                // Each lane holds every released update for its accounts, so
+                // lanes are applied concurrently and each lane in order.
+                // Failures are reported to the message each update arrived
+                // in, so that it is retried rather than this one.
+                future::join_all(lanes.into_iter().map(|lane| {
+                    let ignore_on_startup = &ignore_on_startup;
+
+                    async move {
+                        for Released { update, tx } in lane {
+                            // Copies of an update from other plugin instances
+                            // are buffered alongside it until it is applied
+                            let accepted = match dedup {
+                                Some(dedup) => match dedup.check(&update).await {
+                                    Some(a) => Some((&**dedup, a)),
+                                    None => {
+                                        tx.send(Ok(())).ok();
+                                        continue;
+                                    },
+                                },
+                                None => None,
+                            };
+
+                            let key = update.key;
+                            let res =
+                                apply_account_update(update, client, ignore_on_startup, accepted)
+                                    .await;
+
+                            if let Err(ref e) = res {
+                                warn!("Failed to apply buffered update for {}: {:?}", key, e);
+                            }
+
+                            tx.send(res).ok();
+                        }
+                    }
+                }))
+                .await;
+            }
+
+            Ok(Processed::Done)
         },
-        Message::AccountUpdate(update) if update.owner == pubkeys::AUCTION_HOUSE => {
+    }
+}
+
+/// Apply an account update, recording it in the deduplication cache it was
+/// accepted by once it succeeds
+async fn apply_account_update<H: std::hash::BuildHasher>(
+    update: AccountUpdate,
+    client: &Client,
+    ignore_on_startup: &HashSet<IgnoreType, H>,
+    accepted: Option<(&Dedup, Accepted)>,
+) -> Result<()> {
+    process_account_update(update, client, ignore_on_startup).await?;
+
+    if let Some((dedup, accepted)) = accepted {
+        dedup.record(accepted).await;
+    }
+
+    Ok(())
+}
+
+async fn process_account_update<H: std::hash::BuildHasher>(
+    update: AccountUpdate,
+    client: &Client,
+    ignore_on_startup: &HashSet<IgnoreType, H>,
+) -> Result<()> {
+    let is_startup = update.is_startup;
+    let check_ignore = |ty| !(is_startup && ignore_on_startup.contains(&ty));
+
+    match update.owner {
+        owner if owner == pubkeys::METADATA && check_ignore(IgnoreType::Metadata) => {
+            programs::metadata::process(client, update).await
+        },
+        owner if owner == pubkeys::AUCTION => programs::auction::process(client, update).await,
+        owner if owner == pubkeys::METAPLEX => programs::metaplex::process(client, update).await,
+        owner if owner == pubkeys::AUCTION_HOUSE => {
             programs::auction_house::process(client, update).await
         },
-        Message::AccountUpdate(update)
-            if update.owner == pubkeys::TOKEN && check_ignore(IgnoreType::Tokens, &update) =>
-        {
+        owner if owner == pubkeys::TOKEN && check_ignore(IgnoreType::Tokens) => {
             programs::token::process(client, update).await
         },
-        Message::AccountUpdate(update) if update.owner == pubkeys::GRAPH_PROGRAM => {
-            programs::graph::process(client, update).await
-        },
-        Message::AccountUpdate(update)
-            if update.owner == pubkeys::CANDY_MACHINE
-                && check_ignore(IgnoreType::CandyMachine, &update) =>
-        {
+        owner if owner == pubkeys::GRAPH_PROGRAM => programs::graph::process(client, update).await,
+        owner if owner == pubkeys::CANDY_MACHINE && check_ignore(IgnoreType::CandyMachine) => {
             programs::candy_machine::process(client, update).await
         },
-        Message::AccountUpdate(update) if update.owner == pubkeys::NAME_SERVICE => {
+        owner if owner == pubkeys::NAME_SERVICE => {
             programs::name_service::process(client, update).await
         },
-        Message::AccountUpdate(update) if update.owner == pubkeys::CARDINAL_TOKEN_MANAGER => {
+        owner if owner == pubkeys::CARDINAL_TOKEN_MANAGER => {
             programs::cardinal_token_manager::process(client, update).await
         },
-        Message::AccountUpdate(update) if update.owner == pubkeys::CARDINAL_TIME_INVALIDATOR => {
+        owner if owner == pubkeys::CARDINAL_TIME_INVALIDATOR => {
             programs::cardinal_time_invalidator::process(client, update).await
         },
-        Message::AccountUpdate(update) if update.owner == pubkeys::CARDINAL_USE_INVALIDATOR => {
+        owner if owner == pubkeys::CARDINAL_USE_INVALIDATOR => {
             programs::cardinal_use_invalidator::process(client, update).await
         },
-        Message::AccountUpdate(update) if update.owner == pubkeys::CARDINAL_PAID_CLAIM_APPROVER => {
+        owner if owner == pubkeys::CARDINAL_PAID_CLAIM_APPROVER => {
             programs::cardinal_paid_claim_approver::process(client, update).await
         },
-        Message::AccountUpdate(update) if update.owner == pubkeys::GOKI_SMART_WALLET => {
+        owner if owner == pubkeys::GOKI_SMART_WALLET => {
             programs::goki_smart_wallet::process(client, update).await
         },
-        Message::AccountUpdate(update) if update.owner == pubkeys::TRIBECA_LOCKED_VOTER => {
+        owner if owner == pubkeys::TRIBECA_LOCKED_VOTER => {
             programs::tribeca_locked_voter::process(client, update).await
         },
-        Message::AccountUpdate(update) if update.owner == pubkeys::TRIBECA_GOVERN => {
+        owner if owner == pubkeys::TRIBECA_GOVERN => {
             programs::tribeca_govern::process(client, update).await
         },
-        Message::AccountUpdate(update) if update.owner == pubkeys::NAMESPACES => {
+        owner if owner == pubkeys::NAMESPACES => {
             programs::namespaces::process(client, update).await
         },
-        Message::AccountUpdate(update) if update.owner == pubkeys::TOKEN_BONDING => {
+        owner if owner == pubkeys::TOKEN_BONDING => {
             programs::token_bonding::process(client, update).await
         },
-        Message::AccountUpdate(update) => {
+        owner => {
             debug!(
                 "Unhandled account update for program {}",
-                bs58::encode(update.owner).into_string()
+                bs58::encode(owner).into_string()
+            );
+            Ok(())
+        },
+    }
+}
+
+async fn process_instruction(
+    client: &Client,
+    program: Pubkey,
+    data: &[u8],
+    accounts: &[Pubkey],
+    ctx: InstructionContext<'_>,
+) -> Result<()> {
+    match program {
+        program if program == pubkeys::AUCTION_HOUSE => {
+            instructions::auction_house::process(client, data, accounts, ctx).await
+        },
+        program => {
+            trace!(
+                "Unhandled instruction for program {}",
+                bs58::encode(program).into_string()
             );
             Ok(())
         },
-        Message::InstructionNotify { .. } => Ok(()),
     }
 }
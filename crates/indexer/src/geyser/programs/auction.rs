@@ -5,11 +5,12 @@ use metaplex_auction::processor::{
 
 use super::{
     accounts::{auction_data, bidder_metadata},
-    AccountUpdate, Client,
+    AccountUpdate, AccountVersion, Client,
 };
 use crate::{prelude::*, util};
 
 pub(crate) async fn process(client: &Client, update: AccountUpdate) -> Result<()> {
+    let version = AccountVersion::new(update.slot, update.write_version)?;
     let accounts = util::account_data_as_info(
         update.key,
         update.data,
@@ -31,9 +32,11 @@ pub(crate) async fn process(client: &Client, update: AccountUpdate) -> Result<()
     .await?;
 
     match accounts {
-        (Ok(a), Err(_), Err(_)) => auction_data::process(client, update.key, a).await,
-        (Err(_), Ok(e), Err(_)) => auction_data::process_extended(client, update.key, e).await,
-        (Err(_), Err(_), Ok(b)) => bidder_metadata::process(client, update.key, b).await,
+        (Ok(a), Err(_), Err(_)) => auction_data::process(client, update.key, a, version).await,
+        (Err(_), Ok(e), Err(_)) => {
+            auction_data::process_extended(client, update.key, e, version).await
+        },
+        (Err(_), Err(_), Ok(b)) => bidder_metadata::process(client, update.key, b, version).await,
         (Err(_), Err(_), Err(_)) => {
             debug!(
                 "Account at {} was not AuctionData(Extended) or BidderMetadata",
@@ -9,7 +9,7 @@ use mpl_auction_house::{
 
 use super::{
     accounts::{auction_house, receipt},
-    AccountUpdate, Client,
+    AccountUpdate, AccountVersion, Client,
 };
 use crate::prelude::*;
 
@@ -17,7 +17,13 @@ async fn process_auction_house(client: &Client, update: AccountUpdate) -> Result
     let house: AuctionHouse = AuctionHouse::try_deserialize(&mut update.data.as_slice())
         .context("Failed to deserialize auction house data")?;
 
-    auction_house::process(client, update.key, house).await
+    auction_house::process(
+        client,
+        update.key,
+        house,
+        AccountVersion::new(update.slot, update.write_version)?,
+    )
+    .await
 }
 
 async fn process_listing_receipt(client: &Client, update: AccountUpdate) -> Result<()> {
@@ -25,20 +31,38 @@ async fn process_listing_receipt(client: &Client, update: AccountUpdate) -> Resu
         ListingReceipt::try_deserialize(&mut update.data.as_slice())
             .context("Failed to deserialize listing receipt data")?;
 
-    receipt::process_listing_receipt(client, update.key, listing_receipt).await
+    receipt::process_listing_receipt(
+        client,
+        update.key,
+        listing_receipt,
+        AccountVersion::new(update.slot, update.write_version)?,
+    )
+    .await
 }
 async fn process_bid_receipt(client: &Client, update: AccountUpdate) -> Result<()> {
     let bid_receipt: BidReceipt = BidReceipt::try_deserialize(&mut update.data.as_slice())
         .context("Failed to deserialize bid receipt data")?;
 
-    receipt::process_bid_receipt(client, update.key, bid_receipt).await
+    receipt::process_bid_receipt(
+        client,
+        update.key,
+        bid_receipt,
+        AccountVersion::new(update.slot, update.write_version)?,
+    )
+    .await
 }
 async fn process_purchase_receipt(client: &Client, update: AccountUpdate) -> Result<()> {
     let purchase_receipt: PurchaseReceipt =
         PurchaseReceipt::try_deserialize(&mut update.data.as_slice())
             .context("Failed to deserialize purchase receipt data")?;
 
-    receipt::process_purchase_receipt(client, update.key, purchase_receipt).await
+    receipt::process_purchase_receipt(
+        client,
+        update.key,
+        purchase_receipt,
+        AccountVersion::new(update.slot, update.write_version)?,
+    )
+    .await
 }
 pub(crate) async fn process(client: &Client, update: AccountUpdate) -> Result<()> {
     match update.data.len() {
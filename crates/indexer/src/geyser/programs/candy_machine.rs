@@ -1,7 +1,7 @@
 use anchor_lang_v0_21::{AccountDeserialize, AnchorDeserialize};
 use mpl_candy_machine::{CandyMachine, CollectionPDA, ConfigLine, CONFIG_LINE_SIZE};
 
-use super::{accounts::candy_machine, AccountUpdate, Client};
+use super::{accounts::candy_machine, AccountUpdate, AccountVersion, Client};
 use crate::prelude::*;
 
 const COLLECTION_PDA_SIZE: usize = 8 + 64;
@@ -10,21 +10,39 @@ pub async fn process_collection_pda(client: &Client, update: AccountUpdate) -> R
     let collection_pda: CollectionPDA = CollectionPDA::try_deserialize(&mut update.data.as_slice())
         .context("Failed to deserialize collection pda")?;
 
-    candy_machine::process_collection_pda(client, update.key, collection_pda).await
+    candy_machine::process_collection_pda(
+        client,
+        update.key,
+        collection_pda,
+        AccountVersion::new(update.slot, update.write_version)?,
+    )
+    .await
 }
 
 pub async fn process_config_line(client: &Client, update: AccountUpdate) -> Result<()> {
     let config_line: ConfigLine = ConfigLine::deserialize(&mut update.data.as_slice())
         .context("Failed to deserialize config line")?;
 
-    candy_machine::process_config_line(client, update.key, config_line).await
+    candy_machine::process_config_line(
+        client,
+        update.key,
+        config_line,
+        AccountVersion::new(update.slot, update.write_version)?,
+    )
+    .await
 }
 
 pub async fn process_cm(client: &Client, update: AccountUpdate) -> Result<()> {
     let candy_machine: CandyMachine = CandyMachine::try_deserialize(&mut update.data.as_slice())
         .context("Failed to deserialize candy_machine")?;
 
-    candy_machine::process(client, update.key, candy_machine).await
+    candy_machine::process(
+        client,
+        update.key,
+        candy_machine,
+        AccountVersion::new(update.slot, update.write_version)?,
+    )
+    .await
 }
 
 pub(crate) async fn process(client: &Client, update: AccountUpdate) -> Result<()> {
@@ -1,7 +1,7 @@
 use ::cardinal_paid_claim_approver::state::PaidClaimApprover;
 use anchor_lang_v0_22::{AccountDeserialize, Discriminator};
 
-use super::{accounts::cardinal_paid_claim_approver, AccountUpdate, Client};
+use super::{accounts::cardinal_paid_claim_approver, AccountUpdate, AccountVersion, Client};
 use crate::prelude::*;
 
 pub(crate) async fn process(client: &Client, update: AccountUpdate) -> Result<()> {
@@ -12,7 +12,13 @@ pub(crate) async fn process(client: &Client, update: AccountUpdate) -> Result<()
             PaidClaimApprover::try_deserialize(&mut update.data.as_slice())
                 .context("Failed to deserialize claim_approver")?;
 
-        cardinal_paid_claim_approver::process(client, update.key, claim_approver).await?;
+        cardinal_paid_claim_approver::process(
+            client,
+            update.key,
+            claim_approver,
+            AccountVersion::new(update.slot, update.write_version)?,
+        )
+        .await?;
     }
 
     Ok(())
@@ -1,7 +1,7 @@
 use ::cardinal_time_invalidator::state::TimeInvalidator;
 use anchor_lang_v0_22::{AccountDeserialize, Discriminator};
 
-use super::{accounts::cardinal_time_invalidator, AccountUpdate, Client};
+use super::{accounts::cardinal_time_invalidator, AccountUpdate, AccountVersion, Client};
 use crate::prelude::*;
 
 pub(crate) async fn process(client: &Client, update: AccountUpdate) -> Result<()> {
@@ -12,7 +12,13 @@ pub(crate) async fn process(client: &Client, update: AccountUpdate) -> Result<()
             TimeInvalidator::try_deserialize(&mut update.data.as_slice())
                 .context("Failed to deserialize time_invalidator")?;
 
-        cardinal_time_invalidator::process(client, update.key, time_invalidator).await?;
+        cardinal_time_invalidator::process(
+            client,
+            update.key,
+            time_invalidator,
+            AccountVersion::new(update.slot, update.write_version)?,
+        )
+        .await?;
     }
 
     Ok(())
@@ -1,7 +1,7 @@
 use ::cardinal_token_manager::state::TokenManager;
 use anchor_lang_v0_22::{AccountDeserialize, Discriminator};
 
-use super::{accounts::cardinal_token_manager, AccountUpdate, Client};
+use super::{accounts::cardinal_token_manager, AccountUpdate, AccountVersion, Client};
 use crate::prelude::*;
 
 pub(crate) async fn process(client: &Client, update: AccountUpdate) -> Result<()> {
@@ -12,7 +12,13 @@ pub(crate) async fn process(client: &Client, update: AccountUpdate) -> Result<()
             TokenManager::try_deserialize(&mut update.data.as_slice())
                 .context("Failed to deserialize token_manager")?;
 
-        cardinal_token_manager::process(client, update.key, token_manager).await?;
+        cardinal_token_manager::process(
+            client,
+            update.key,
+            token_manager,
+            AccountVersion::new(update.slot, update.write_version)?,
+        )
+        .await?;
     }
 
     Ok(())
@@ -1,7 +1,7 @@
 use ::cardinal_use_invalidator::state::UseInvalidator;
 use anchor_lang_v0_22::{AccountDeserialize, Discriminator};
 
-use super::{accounts::cardinal_use_invalidator, AccountUpdate, Client};
+use super::{accounts::cardinal_use_invalidator, AccountUpdate, AccountVersion, Client};
 use crate::prelude::*;
 
 pub(crate) async fn process(client: &Client, update: AccountUpdate) -> Result<()> {
@@ -12,7 +12,13 @@ pub(crate) async fn process(client: &Client, update: AccountUpdate) -> Result<()
             UseInvalidator::try_deserialize(&mut update.data.as_slice())
                 .context("Failed to deserialize use_invalidator")?;
 
-        cardinal_use_invalidator::process(client, update.key, use_invalidator).await?;
+        cardinal_use_invalidator::process(
+            client,
+            update.key,
+            use_invalidator,
+            AccountVersion::new(update.slot, update.write_version)?,
+        )
+        .await?;
     }
 
     Ok(())
@@ -12,7 +12,7 @@ use mpl_token_metadata::{
 
 use super::{
     accounts::{edition, metadata},
-    AccountUpdate, Client,
+    AccountUpdate, AccountVersion, Client,
 };
 use crate::prelude::*;
 
@@ -30,6 +30,7 @@ async fn process_metadata(client: &Client, update: AccountUpdate) -> Result<()>
             metadata,
             update.slot,
             update.write_version,
+            update.priority(),
         )
         .await;
     }
@@ -72,6 +73,7 @@ async fn process_metadata(client: &Client, update: AccountUpdate) -> Result<()>
         metaplex_metadata,
         update.slot,
         update.write_version,
+        update.priority(),
     )
     .await
 }
@@ -80,7 +82,13 @@ async fn process_edition(client: &Client, update: AccountUpdate) -> Result<()> {
     let edition: Edition = try_from_slice_checked(&update.data, Key::EditionV1, MAX_EDITION_LEN)
         .context("Failed to parse edition data")?;
 
-    edition::process(client, update.key, edition, update.slot).await
+    edition::process(
+        client,
+        update.key,
+        edition,
+        AccountVersion::new(update.slot, update.write_version)?,
+    )
+    .await
 }
 
 async fn process_master_edition_v1(client: &Client, update: AccountUpdate) -> Result<()> {
@@ -98,7 +106,13 @@ async fn process_master_edition_v1(client: &Client, update: AccountUpdate) -> Re
         max_supply,
     };
 
-    edition::process_master(client, update.key, master_edition, update.slot).await
+    edition::process_master(
+        client,
+        update.key,
+        master_edition,
+        AccountVersion::new(update.slot, update.write_version)?,
+    )
+    .await
 }
 
 async fn process_master_edition_v2(client: &Client, update: AccountUpdate) -> Result<()> {
@@ -106,7 +120,13 @@ async fn process_master_edition_v2(client: &Client, update: AccountUpdate) -> Re
         try_from_slice_checked(&update.data, Key::MasterEditionV2, MAX_MASTER_EDITION_LEN)
             .context("Failed to parse master edition v2 data")?;
 
-    edition::process_master(client, update.key, master_edition, update.slot).await
+    edition::process_master(
+        client,
+        update.key,
+        master_edition,
+        AccountVersion::new(update.slot, update.write_version)?,
+    )
+    .await
 }
 
 pub(crate) async fn process(client: &Client, update: AccountUpdate) -> Result<()> {
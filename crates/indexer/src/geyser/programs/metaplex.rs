@@ -17,7 +17,7 @@ use mpl_metaplex::{
 
 use super::{
     accounts::{auction_cache, mpl_store},
-    AccountUpdate, Client,
+    AccountUpdate, AccountVersion, Client,
 };
 use crate::prelude::*;
 
@@ -38,7 +38,13 @@ async fn process_store(client: &Client, update: AccountUpdate) -> Result<()> {
     let store: Store = try_from_slice_checked(&update.data, Key::StoreV1, MAX_STORE_SIZE)
         .context("Failed to parse store data")?;
 
-    mpl_store::process(client, update.key, store).await
+    mpl_store::process(
+        client,
+        update.key,
+        store,
+        AccountVersion::new(update.slot, update.write_version)?,
+    )
+    .await
 }
 
 async fn process_whitelisted_creator(client: &Client, update: AccountUpdate) -> Result<()> {
@@ -54,7 +60,13 @@ async fn process_whitelisted_creator(client: &Client, update: AccountUpdate) ->
     )
     .context("Failed to parse whitelisted creator data")?;
 
-    mpl_store::process_whitelisted_creator(client, update.key, creator).await
+    mpl_store::process_whitelisted_creator(
+        client,
+        update.key,
+        creator,
+        AccountVersion::new(update.slot, update.write_version)?,
+    )
+    .await
 }
 
 async fn process_store_config(client: &Client, update: AccountUpdate) -> Result<()> {
@@ -65,7 +77,14 @@ async fn process_store_config(client: &Client, update: AccountUpdate) -> Result<
     )
     .context("Failed to parse store config data")?;
 
-    mpl_store::process_config(client, update.key, config).await
+    mpl_store::process_config(
+        client,
+        update.key,
+        config,
+        AccountVersion::new(update.slot, update.write_version)?,
+        update.priority(),
+    )
+    .await
 }
 
 async fn process_auction_cache(client: &Client, update: AccountUpdate) -> Result<()> {
@@ -73,7 +92,13 @@ async fn process_auction_cache(client: &Client, update: AccountUpdate) -> Result
         try_from_slice_checked(&update.data, Key::AuctionCacheV1, MAX_AUCTION_CACHE_SIZE)
             .context("Failed to parse auction cache data")?;
 
-    auction_cache::process(client, update.key, cache).await
+    auction_cache::process(
+        client,
+        update.key,
+        cache,
+        AccountVersion::new(update.slot, update.write_version)?,
+    )
+    .await
 }
 
 pub(crate) async fn process(client: &Client, update: AccountUpdate) -> Result<()> {
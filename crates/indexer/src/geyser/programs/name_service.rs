@@ -46,6 +46,7 @@ pub(crate) async fn process(client: &Client, update: AccountUpdate) -> Result<()
         update.write_version,
         wallet,
         data,
+        update.priority(),
     )
     .await
 }
@@ -9,7 +9,15 @@ pub(crate) async fn process(client: &Client, update: AccountUpdate) -> Result<()
         let entry: Entry = Entry::try_deserialize(&mut update.data.as_slice())
             .context("Failed to deserialize cardinal entry")?;
 
-        namespace::process(client, update.key, update.slot, update.write_version, entry).await?;
+        namespace::process(
+            client,
+            update.key,
+            update.slot,
+            update.write_version,
+            entry,
+            update.priority(),
+        )
+        .await?;
     }
 
     Ok(())
@@ -1,13 +1,19 @@
 use solana_program::program_pack::Pack;
 use spl_token::state::Account as TokenAccount;
 
-use super::{accounts::token, AccountUpdate, Client};
+use super::{accounts::token, AccountUpdate, AccountVersion, Client};
 use crate::prelude::*;
 
 async fn process_token(client: &Client, update: AccountUpdate) -> Result<()> {
     let token_account = TokenAccount::unpack_unchecked(&update.data)
         .context("Failed to deserialize token account data!")?;
-    token::process(client, update.key, token_account, update.slot).await
+    token::process(
+        client,
+        update.key,
+        token_account,
+        AccountVersion::new(update.slot, update.write_version)?,
+    )
+    .await
 }
 
 pub(crate) async fn process(client: &Client, update: AccountUpdate) -> Result<()> {
@@ -1,7 +1,7 @@
 use anchor_lang_v0_22::AccountDeserialize;
 use tribeca_govern::{Governor, Proposal, ProposalMeta, Vote};
 
-use super::{accounts::govern, AccountUpdate, Client};
+use super::{accounts::govern, AccountUpdate, AccountVersion, Client};
 use crate::prelude::*;
 
 const GOVERNOR_SIZE: usize = 8 + 32 + 1 + 8 + 32 + 32 + GOVERNANCE_PARAMS_SIZE;
@@ -12,24 +12,38 @@ async fn process_governor(client: &Client, update: AccountUpdate) -> Result<()>
     let governor = Governor::try_deserialize_unchecked(&mut update.data.as_slice())
         .context("failed to deserialize governor account!")?;
 
-    govern::process_governor(client, update.key, governor).await
+    govern::process_governor(
+        client,
+        update.key,
+        governor,
+        AccountVersion::new(update.slot, update.write_version)?,
+    )
+    .await
 }
 
 async fn process_vote(client: &Client, update: AccountUpdate) -> Result<()> {
     let vote = Vote::try_deserialize_unchecked(&mut update.data.as_slice())
         .context("failed to deserialize vote account!")?;
 
-    govern::process_vote(client, update.key, vote).await
+    govern::process_vote(
+        client,
+        update.key,
+        vote,
+        AccountVersion::new(update.slot, update.write_version)?,
+    )
+    .await
 }
 
 async fn process_proposal_or_meta(client: &Client, update: AccountUpdate) -> Result<()> {
+    let version = AccountVersion::new(update.slot, update.write_version)?;
+
     if let Ok(proposal) = Proposal::try_deserialize_unchecked(&mut update.data.as_slice()) {
-        govern::process_proposal(client, update.key, proposal).await?;
+        govern::process_proposal(client, update.key, proposal, version).await?;
     } else {
         let proposal_meta = ProposalMeta::try_deserialize_unchecked(&mut update.data.as_slice())
             .context("failed to deserialize proposal meta account!")?;
 
-        govern::process_meta(client, update.key, proposal_meta).await?;
+        govern::process_meta(client, update.key, proposal_meta, version).await?;
     }
 
     Ok(())
@@ -1,7 +1,7 @@
 use anchor_lang_v0_22::AccountDeserialize;
 use tribeca_locked_voter::{Escrow, Locker, LockerWhitelistEntry};
 
-use super::{accounts::locked_voter, AccountUpdate, Client};
+use super::{accounts::locked_voter, AccountUpdate, AccountVersion, Client};
 use crate::prelude::*;
 
 const LOCKER_SIZE: usize = 8 + 32 + 1 + 32 + 8 + 32 + LOCKER_PARAMS_SIZE;
@@ -13,14 +13,26 @@ async fn process_locker(client: &Client, update: AccountUpdate) -> Result<()> {
     let locker = Locker::try_deserialize_unchecked(&mut update.data.as_slice())
         .context("failed to deserialize locker account!")?;
 
-    locked_voter::process_locker(client, update.key, locker).await
+    locked_voter::process_locker(
+        client,
+        update.key,
+        locker,
+        AccountVersion::new(update.slot, update.write_version)?,
+    )
+    .await
 }
 
 async fn process_escrow(client: &Client, update: AccountUpdate) -> Result<()> {
     let escrow = Escrow::try_deserialize_unchecked(&mut update.data.as_slice())
         .context("failed to deserialize escrow account!")?;
 
-    locked_voter::process_escrow(client, update.key, escrow).await
+    locked_voter::process_escrow(
+        client,
+        update.key,
+        escrow,
+        AccountVersion::new(update.slot, update.write_version)?,
+    )
+    .await
 }
 
 async fn process_locker_whitelist_entry(client: &Client, update: AccountUpdate) -> Result<()> {
@@ -28,7 +40,13 @@ async fn process_locker_whitelist_entry(client: &Client, update: AccountUpdate)
         LockerWhitelistEntry::try_deserialize_unchecked(&mut update.data.as_slice())
             .context("failed to deserialize locker whitelist entry account!")?;
 
-    locked_voter::process_locker_whitelist_entry(client, update.key, whitelist_entry).await
+    locked_voter::process_locker_whitelist_entry(
+        client,
+        update.key,
+        whitelist_entry,
+        AccountVersion::new(update.slot, update.write_version)?,
+    )
+    .await
 }
 
 pub(crate) async fn process(client: &Client, update: AccountUpdate) -> Result<()> {
@@ -0,0 +1,445 @@
+use std::{
+    collections::{hash_map::RandomState, BTreeMap, BTreeSet},
+    hash::{BuildHasher, Hash, Hasher},
+    sync::{Arc, Weak},
+};
+
+use futures_util::{future::BoxFuture, FutureExt};
+use indexer_rabbitmq::geyser::SlotStatus;
+use tokio::sync::{oneshot, Mutex, OwnedSemaphorePermit, Semaphore};
+
+use super::AccountUpdate;
+use crate::prelude::*;
+
+/// The number of slots behind the latest root to retain confirmation and
+/// ancestry information for, to handle updates arriving late.
+const RETAIN_SLOTS: u64 = 512;
+
+/// A buffered update, along with the channel used to report the result of
+/// applying it
+#[derive(Debug)]
+pub struct Released {
+    /// The account update to apply
+    pub update: AccountUpdate,
+    /// Sender for the result of applying the update, which settles the
+    /// message it was received in
+    pub tx: oneshot::Sender<Result<()>>,
+}
+
+/// The result of submitting an account update to a [`SlotBuffer`]
+pub enum Pushed {
+    /// The update's slot is already confirmed, so it can be applied now
+    Ready(AccountUpdate),
+    /// The update was accepted by the buffer, and the future resolves once it
+    /// has been applied or discarded
+    Buffered(BoxFuture<'static, Result<()>>),
+    /// The update belongs to an abandoned or expired slot and was discarded
+    Discarded,
+}
+
+impl std::fmt::Debug for Pushed {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Ready(u) => f.debug_tuple("Ready").field(u).finish(),
+            Self::Buffered(_) => f.write_str("Buffered(..)"),
+            Self::Discarded => f.write_str("Discarded"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    pending: BTreeMap<u64, Vec<Released>>,
+    /// Updates which were admitted after their slot had been confirmed, to be
+    /// released with the next status notification
+    late: Vec<Released>,
+    parents: BTreeMap<u64, u64>,
+    confirmed: BTreeSet<u64>,
+    root: Option<u64>,
+}
+
+impl Inner {
+    fn admit(&mut self, update: AccountUpdate, permit: OwnedSemaphorePermit) -> Receiver {
+        let key = update.key;
+        let (tx, rx) = oneshot::channel();
+
+        if self.root.map_or(false, |r| update.slot <= r) && !self.confirmed.contains(&update.slot) {
+            debug!(
+                "Discarding update for {} from abandoned or expired slot {}",
+                key, update.slot
+            );
+
+            tx.send(Ok(())).ok();
+        } else {
+            // The permit is returned when the update is released or discarded
+            permit.forget();
+
+            let released = Released { update, tx };
+
+            if self.confirmed.contains(&released.update.slot) {
+                self.late.push(released);
+            } else {
+                self.pending
+                    .entry(released.update.slot)
+                    .or_default()
+                    .push(released);
+            }
+        }
+
+        Receiver { key, rx }
+    }
+}
+
+struct Receiver {
+    key: Pubkey,
+    rx: oneshot::Receiver<Result<()>>,
+}
+
+impl Receiver {
+    async fn recv(self) -> Result<()> {
+        let Self { key, rx } = self;
+
+        rx.await
+            .unwrap_or_else(|_| Err(anyhow!("Buffered update for {} was dropped", key)))
+    }
+}
+
+/// Buffer holding account updates until their slot is confirmed or rooted,
+/// discarding any updates belonging to abandoned forks
+///
+/// Buffered updates are not acknowledged until they have been applied.  When
+/// the buffer is full, further updates wait for space without holding up the
+/// caller, so the broker stops delivering once its prefetch limit is reached.
+/// The capacity must therefore be well below the queue's prefetch limit, or
+/// slot status notifications will not be delivered.
+///
+/// A buffer belongs to a single broker session.  Dropping it drops every
+/// update it holds, failing the messages they were received in.
+#[derive(Debug)]
+pub struct SlotBuffer {
+    inner: Arc<Mutex<Inner>>,
+    space: Arc<Semaphore>,
+    lanes: usize,
+    hasher: RandomState,
+}
+
+impl Drop for SlotBuffer {
+    fn drop(&mut self) {
+        self.space.close();
+    }
+}
+
+impl SlotBuffer {
+    /// Construct a new buffer holding at most `capacity` pending updates,
+    /// releasing them in up to `lanes` groups which may be applied
+    /// concurrently
+    ///
+    /// # Errors
+    /// This function fails if `capacity` or `lanes` is zero.
+    pub fn new(capacity: usize, lanes: usize) -> Result<Self> {
+        if capacity == 0 {
+            bail!("Slot buffer capacity must be nonzero");
+        }
+
+        if lanes == 0 {
+            bail!("Slot buffer lane count must be nonzero");
+        }
+
+        Ok(Self {
+            inner: Arc::default(),
+            space: Arc::new(Semaphore::new(capacity)),
+            lanes,
+            hasher: RandomState::new(),
+        })
+    }
+
+    /// Submit an account update to the buffer
+    ///
+    /// If the buffer is full, the returned future first waits for space to
+    /// free up, as the slot status notifications which free it are processed
+    /// by the caller.  Updates waiting for space may be admitted out of
+    /// order.
+    pub async fn push(&self, update: AccountUpdate) -> Pushed {
+        let mut inner = self.inner.lock().await;
+
+        if inner.confirmed.contains(&update.slot) {
+            return Pushed::Ready(update);
+        }
+
+        if inner.root.map_or(false, |r| update.slot <= r) {
+            debug!(
+                "Discarding update for {} from abandoned or expired slot {}",
+                update.key, update.slot
+            );
+
+            return Pushed::Discarded;
+        }
+
+        if let Ok(permit) = Arc::clone(&self.space).try_acquire_owned() {
+            let rx = inner.admit(update, permit);
+
+            return Pushed::Buffered(rx.recv().boxed());
+        }
+
+        std::mem::drop(inner);
+        trace!(
+            "Slot buffer is full, waiting to buffer update for {} in slot {}",
+            update.key,
+            update.slot
+        );
+
+        let inner = Arc::downgrade(&self.inner);
+        let space = Arc::clone(&self.space);
+
+        Pushed::Buffered(
+            async move {
+                let permit = space
+                    .acquire_owned()
+                    .await
+                    .map_err(|_| anyhow!("Slot buffer was closed"))?;
+                let inner =
+                    Weak::upgrade(&inner).ok_or_else(|| anyhow!("Slot buffer was closed"))?;
+                let rx = inner.lock().await.admit(update, permit);
+
+                rx.recv().await
+            }
+            .boxed(),
+        )
+    }
+
+    /// Record a slot status notification, returning any buffered updates that
+    /// are now safe to apply, split into lanes by account key.
+    ///
+    /// Every update for a given account is in the same lane, in write order,
+    /// so lanes may be applied concurrently as long as each lane is applied
+    /// in order.  The caller must send the result of applying each returned
+    /// update to its [`tx`](Released::tx).
+    pub async fn update_status(
+        &self,
+        slot: u64,
+        parent: Option<u64>,
+        status: SlotStatus,
+    ) -> Vec<Vec<Released>> {
+        let mut inner = self.inner.lock().await;
+        let mut ready = std::mem::take(&mut inner.late);
+
+        if let Some(parent) = parent {
+            inner.parents.insert(slot, parent);
+        }
+
+        // Confirming a slot implicitly confirms all of its ancestors
+        let mut next = match status {
+            SlotStatus::Processed => None,
+            SlotStatus::Confirmed | SlotStatus::Rooted => Some(slot),
+        };
+
+        while let Some(slot) = next {
+            if inner.root.map_or(false, |r| slot <= r) || !inner.confirmed.insert(slot) {
+                break;
+            }
+
+            ready.extend(inner.pending.remove(&slot).into_iter().flatten());
+            next = inner.parents.get(&slot).copied();
+        }
+
+        self.space.add_permits(ready.len());
+
+        if matches!(status, SlotStatus::Rooted) && inner.root.map_or(true, |r| slot > r) {
+            inner.root = Some(slot);
+
+            // Anything left at or below the new root was never confirmed, and
+            // therefore belongs to a dead fork.
+            let live = inner.pending.split_off(&(slot + 1));
+
+            for (dead, updates) in std::mem::replace(&mut inner.pending, live) {
+                debug!(
+                    "Discarding {} update(s) from abandoned slot {}",
+                    updates.len(),
+                    dead
+                );
+
+                self.space.add_permits(updates.len());
+
+                // Discarded updates are settled successfully, as there is
+                // nothing left to apply
+                for Released { tx, .. } in updates {
+                    tx.send(Ok(())).ok();
+                }
+            }
+
+            let horizon = slot.saturating_sub(RETAIN_SLOTS);
+            inner.confirmed = inner.confirmed.split_off(&horizon);
+            inner.parents = inner.parents.split_off(&horizon);
+        }
+
+        ready.sort_by_key(|r| (r.update.slot, r.update.write_version));
+
+        let mut lanes: Vec<Vec<Released>> = (0..self.lanes).map(|_| vec![]).collect();
+
+        for released in ready {
+            let mut h = self.hasher.build_hasher();
+            released.update.key.hash(&mut h);
+
+            // Truncating the hash on 32-bit targets is harmless
+            #[allow(clippy::cast_possible_truncation)]
+            lanes[h.finish() as usize % self.lanes].push(released);
+        }
+
+        lanes.retain(|l| !l.is_empty());
+
+        lanes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(key: u8, slot: u64, write_version: u64) -> AccountUpdate {
+        AccountUpdate {
+            key: Pubkey::new_from_array([key; 32]),
+            lamports: 1,
+            owner: Pubkey::default(),
+            executable: false,
+            rent_epoch: 0,
+            data: vec![],
+            write_version,
+            slot,
+            is_startup: false,
+        }
+    }
+
+    fn buffered(pushed: Pushed) -> BoxFuture<'static, Result<()>> {
+        match pushed {
+            Pushed::Buffered(f) => f,
+            p => panic!("Expected a buffered update, got {:?}", p),
+        }
+    }
+
+    fn released(lanes: Vec<Vec<Released>>) -> Vec<(u64, u64)> {
+        let mut released: Vec<_> = lanes
+            .into_iter()
+            .flatten()
+            .map(|Released { update, tx }| {
+                tx.send(Ok(())).unwrap();
+                (update.slot, update.write_version)
+            })
+            .collect();
+
+        released.sort_unstable();
+        released
+    }
+
+    #[tokio::test]
+    async fn confirm_releases_slot_and_ancestors() {
+        let buf = SlotBuffer::new(16, 2).unwrap();
+
+        let a = buffered(buf.push(update(1, 10, 1)).await);
+        let b = buffered(buf.push(update(2, 11, 2)).await);
+        let c = buffered(buf.push(update(1, 12, 3)).await);
+
+        buf.update_status(11, Some(10), SlotStatus::Processed).await;
+        assert!(
+            buf.update_status(12, Some(11), SlotStatus::Processed)
+                .await
+                .is_empty()
+        );
+
+        let lanes = buf.update_status(11, Some(10), SlotStatus::Confirmed).await;
+        assert_eq!(released(lanes), vec![(10, 1), (11, 2)]);
+        a.await.unwrap();
+        b.await.unwrap();
+
+        // Updates for confirmed slots no longer need buffering
+        assert!(matches!(buf.push(update(3, 11, 4)).await, Pushed::Ready(_)));
+
+        let lanes = buf.update_status(12, Some(11), SlotStatus::Confirmed).await;
+        assert_eq!(released(lanes), vec![(12, 3)]);
+        c.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn lanes_keep_account_order() {
+        let buf = SlotBuffer::new(16, 4).unwrap();
+        let mut futs = vec![];
+
+        for (key, wv) in [(1, 3), (2, 2), (1, 1), (2, 4)] {
+            futs.push(buffered(buf.push(update(key, 10, wv)).await));
+        }
+
+        for lane in buf.update_status(10, None, SlotStatus::Confirmed).await {
+            let key = lane[0].update.key;
+            assert!(lane.iter().all(|r| r.update.key == key));
+            assert!(
+                lane.windows(2)
+                    .all(|w| w[0].update.write_version < w[1].update.write_version)
+            );
+
+            for Released { tx, .. } in lane {
+                tx.send(Ok(())).unwrap();
+            }
+        }
+
+        for f in futs {
+            f.await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn root_discards_dead_forks() {
+        let buf = SlotBuffer::new(16, 1).unwrap();
+
+        let live = buffered(buf.push(update(1, 10, 1)).await);
+        let dead = buffered(buf.push(update(1, 11, 2)).await);
+        let next = buffered(buf.push(update(1, 13, 3)).await);
+
+        // Slot 12 is built on 10, abandoning 11
+        let lanes = buf.update_status(12, Some(10), SlotStatus::Rooted).await;
+        assert_eq!(released(lanes), vec![(10, 1)]);
+        live.await.unwrap();
+        dead.await.unwrap();
+
+        // Late updates at or below the root are discarded unless confirmed
+        assert!(matches!(
+            buf.push(update(1, 11, 4)).await,
+            Pushed::Discarded
+        ));
+        assert!(matches!(buf.push(update(1, 12, 5)).await, Pushed::Ready(_)));
+
+        let lanes = buf.update_status(13, Some(12), SlotStatus::Confirmed).await;
+        assert_eq!(released(lanes), vec![(13, 3)]);
+        next.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn full_buffer_waits_for_space() {
+        let buf = SlotBuffer::new(1, 1).unwrap();
+
+        let first = buffered(buf.push(update(1, 10, 1)).await);
+        let mut second = buffered(buf.push(update(2, 11, 2)).await);
+        assert!((&mut second).now_or_never().is_none());
+
+        // Releasing the first update makes room for the second
+        let lanes = buf.update_status(10, None, SlotStatus::Confirmed).await;
+        assert_eq!(released(lanes), vec![(10, 1)]);
+        first.await.unwrap();
+        assert!((&mut second).now_or_never().is_none());
+
+        let lanes = buf.update_status(11, Some(10), SlotStatus::Confirmed).await;
+        assert_eq!(released(lanes), vec![(11, 2)]);
+        second.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn dropping_buffer_fails_waiting_updates() {
+        let buf = SlotBuffer::new(1, 1).unwrap();
+
+        let first = buffered(buf.push(update(1, 10, 1)).await);
+        let second = buffered(buf.push(update(2, 10, 2)).await);
+
+        std::mem::drop(buf);
+
+        assert!(first.await.is_err());
+        assert!(second.await.is_err());
+    }
+}
@@ -0,0 +1,43 @@
+use indexer_core::{
+    db::{custom_types::SlotStatusEnum, insert_into, models::Slot, tables::slots},
+    prelude::*,
+};
+use indexer_rabbitmq::geyser::SlotStatus;
+
+use super::Client;
+use crate::prelude::*;
+
+pub(crate) async fn process(
+    client: &Client,
+    slot: u64,
+    parent: Option<u64>,
+    status: SlotStatus,
+) -> Result<()> {
+    let row = Slot {
+        slot: slot.try_into().context("Slot was too big to store")?,
+        parent_slot: parent
+            .map(TryInto::try_into)
+            .transpose()
+            .context("Parent slot was too big to store")?,
+        status: match status {
+            SlotStatus::Processed => SlotStatusEnum::Processed,
+            SlotStatus::Confirmed => SlotStatusEnum::Confirmed,
+            SlotStatus::Rooted => SlotStatusEnum::Rooted,
+        },
+    };
+
+    client
+        .db()
+        .run(move |db| {
+            insert_into(slots::table)
+                .values(&row)
+                .on_conflict(slots::slot)
+                .do_update()
+                .set(&row)
+                .execute(db)
+        })
+        .await
+        .context("Failed to insert slot status")?;
+
+    Ok(())
+}
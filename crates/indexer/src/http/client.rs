@@ -3,18 +3,15 @@ use std::{sync::Arc, time::Duration};
 use indexer_core::{assets::AssetProxyArgs, clap};
 use indexer_rabbitmq::search_indexer;
 
-use crate::{db::Pool, prelude::*, reqwest, search_dispatch};
+use crate::{db::Pool, prelude::*, publish::BoxPublisher, reqwest, search_dispatch};
 
 /// Common arguments for internal HTTP indexer usage
-#[derive(Debug, clap::Args)]
+#[derive(Debug, Clone, clap::Args)]
 #[allow(missing_copy_implementations)]
 pub struct Args {
     #[clap(flatten)]
     asset_proxy: AssetProxyArgs,
 
-    #[clap(flatten)]
-    search: search_dispatch::Args,
-
     /// HTTP request timeout, in seconds
     #[clap(long, env = "HTTP_INDEXER_TIMEOUT")]
     timeout: f64,
@@ -35,16 +32,14 @@ impl Client {
     /// # Errors
     /// This function fails if an invalid URL is given for `ipfs_cdn` or
     /// `arweave_cdn`.
-    pub async fn new_rc(
+    pub fn new_rc(
         db: Pool,
-        conn: &indexer_rabbitmq::lapin::Connection,
+        search_prod: BoxPublisher<search_indexer::Message>,
         args: Args,
-        search_queue: search_indexer::QueueType,
     ) -> Result<Arc<Self>> {
         let Args {
             asset_proxy,
             timeout,
-            search,
         } = args;
 
         let timeout = Duration::from_secs_f64(timeout);
@@ -53,7 +48,7 @@ impl Client {
             db,
             http: reqwest::Client::new(timeout)?,
             asset_proxy,
-            search: search_dispatch::Client::new(conn, search_queue, search).await?,
+            search: search_dispatch::Client::new(search_prod),
         }))
     }
 
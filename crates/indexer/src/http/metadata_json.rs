@@ -16,6 +16,7 @@ use indexer_core::{
     },
     hash::HashMap,
 };
+use indexer_rabbitmq::Priority;
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -261,11 +262,12 @@ async fn process_full(
     json: MetadataJson,
     fingerprint: Vec<u8>,
     slot_info: SlotInfo,
+    priority: Priority,
 ) -> Result<()> {
     let raw_content: Value =
         serde_json::value::to_value(&json).context("Failed to upcast metadata JSON")?;
 
-    dispatch_metadata_document(client, false, addr.clone(), raw_content.clone())
+    dispatch_metadata_document(client, false, priority, addr.clone(), raw_content.clone())
         .await
         .context("Failed to dispatch upsert metadata document job")?;
 
@@ -303,6 +305,24 @@ async fn process_full(
         write_version,
     };
 
+    let attributes = attribute_rows(
+        &addr,
+        first_verified_creator.as_deref(),
+        json.attributes,
+        slot_info,
+    );
+
+    // Attributes go through the batch writer, so they can't share a
+    // transaction with the rows below.  They are written first because a
+    // stored metadata_jsons row causes retries to skip this function, and
+    // reprocess_attributes only updates attributes that already exist.  If
+    // anything below fails, the retry rewrites the attributes idempotently.
+    client
+        .db()
+        .upsert_all(attributes)
+        .await
+        .context("Failed to insert attributes")?;
+
     client
         .db()
         .run(move |db| {
@@ -318,13 +338,6 @@ async fn process_full(
             //       previous rows from the old metadata JSON:
 
             process_files(db, &addr, files, slot_info)?;
-            process_attributes(
-                db,
-                &addr,
-                first_verified_creator.as_deref(),
-                json.attributes,
-                slot_info,
-            )?;
             process_collection(db, &addr, json.collection, slot_info)
         })
         .await
@@ -338,6 +351,7 @@ async fn process_minimal(
     fingerprint: Vec<u8>,
     full_err: serde_json::Error,
     (slot, write_version): SlotInfo,
+    priority: Priority,
 ) -> Result<()> {
     fn to_opt_string(v: &Value) -> Option<Cow<'static, str>> {
         v.as_str().map(|s| Owned(s.to_owned())).or_else(|| {
@@ -352,7 +366,7 @@ async fn process_minimal(
     let raw_content: Value =
         serde_json::value::to_value(&json).context("Failed to upcast minimal metadata JSON")?;
 
-    dispatch_metadata_document(client, false, addr.clone(), raw_content.clone())
+    dispatch_metadata_document(client, false, priority, addr.clone(), raw_content.clone())
         .await
         .context("Failed to dispatch upsert metadata document job")?;
 
@@ -432,38 +446,24 @@ fn process_files(
 }
 
 #[inline]
-fn process_attributes(
-    db: &Connection,
+fn attribute_rows(
     addr: &str,
     first_verified_creator: Option<&str>,
     attributes: Option<Vec<Attribute>>,
-    slot_info: SlotInfo,
-) -> Result<()> {
-    for Attribute { trait_type, value } in attributes.unwrap_or_else(Vec::new) {
-        let (slot, write_version) = slot_info;
-        let row = MetadataAttributeWrite {
-            metadata_address: Borrowed(addr),
+    (slot, write_version): SlotInfo,
+) -> Vec<MetadataAttributeWrite<'static>> {
+    attributes
+        .unwrap_or_else(Vec::new)
+        .into_iter()
+        .map(|Attribute { trait_type, value }| MetadataAttributeWrite {
+            metadata_address: Owned(addr.to_owned()),
             trait_type: trait_type.map(Owned),
             value: value.as_ref().map(|v| Owned(v.to_string())),
-            first_verified_creator: first_verified_creator.map(Borrowed),
+            first_verified_creator: first_verified_creator.map(|c| Owned(c.to_owned())),
             slot,
             write_version,
-        };
-
-        insert_into(attributes::table)
-            .values(&row)
-            .on_conflict((
-                attributes::metadata_address,
-                attributes::value,
-                attributes::trait_type,
-            ))
-            .do_update()
-            .set(&row)
-            .execute(db)
-            .context("Failed to insert attribute!")?;
-    }
-
-    Ok(())
+        })
+        .collect()
 }
 
 #[inline]
@@ -526,6 +526,7 @@ pub async fn process<'a>(
     first_verified_creator: Option<Pubkey>,
     uri_str: String,
     (slot, write_version): (u64, u64),
+    priority: Priority,
 ) -> Result<()> {
     let slot_info = (
         i64::try_from(slot).context("Slot was too big to store")?,
@@ -576,7 +577,7 @@ pub async fn process<'a>(
 
             reprocess_attributes(client, addr.clone(), first_verified_creator, slot_info).await?;
 
-            dispatch_metadata_document(client, true, addr, json).await?;
+            dispatch_metadata_document(client, true, priority, addr, json).await?;
 
             return Ok(());
         }
@@ -595,6 +596,7 @@ pub async fn process<'a>(
                     value,
                     fingerprint,
                     slot_info,
+                    priority,
                 )
                 .await?;
             },
@@ -607,6 +609,7 @@ pub async fn process<'a>(
                     fingerprint,
                     full_err,
                     slot_info,
+                    priority,
                 )
                 .await?;
             },
@@ -619,6 +622,7 @@ pub async fn process<'a>(
 async fn dispatch_metadata_document(
     client: &Client,
     is_for_backfill: bool,
+    priority: Priority,
     addr: String,
     raw: Value,
 ) -> Result<()> {
@@ -676,7 +680,7 @@ async fn dispatch_metadata_document(
     {
         client
             .search()
-            .upsert_metadata(is_for_backfill, addr, MetadataDocument {
+            .upsert_metadata(is_for_backfill, priority, addr, MetadataDocument {
                 name,
                 mint_address,
                 image,
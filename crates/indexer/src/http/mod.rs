@@ -7,11 +7,27 @@ mod store_config;
 pub use client::{Args as ClientArgs, Client};
 use indexer_rabbitmq::http_indexer::{Entity, MetadataJson, StoreConfig};
 
-use crate::prelude::*;
+use crate::{prelude::*, ShardKey};
+
+impl ShardKey for MetadataJson {
+    type Key = Pubkey;
+
+    fn shard_key(&self) -> Option<Pubkey> {
+        Some(self.meta_address)
+    }
+}
+
+impl ShardKey for StoreConfig {
+    type Key = Pubkey;
+
+    fn shard_key(&self) -> Option<Pubkey> {
+        Some(self.config_address)
+    }
+}
 
 /// Define processing logic for an incoming entity type
 #[async_trait::async_trait]
-pub trait Process: Entity {
+pub trait Process: Entity + ShardKey {
     /// Process and consume an incoming entity
     async fn process(self, client: &Client) -> Result<()>;
 }
@@ -24,9 +40,18 @@ impl Process for MetadataJson {
             first_verified_creator,
             uri,
             slot_info,
+            priority,
         } = self;
 
-        metadata_json::process(client, meta_address, first_verified_creator, uri, slot_info).await
+        metadata_json::process(
+            client,
+            meta_address,
+            first_verified_creator,
+            uri,
+            slot_info,
+            priority,
+        )
+        .await
     }
 }
 
@@ -36,6 +61,7 @@ impl Process for StoreConfig {
         let StoreConfig {
             config_address,
             uri,
+            priority: _,
         } = self;
 
         store_config::process(client, config_address, uri).await
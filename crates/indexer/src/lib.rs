@@ -16,6 +16,9 @@ pub mod geyser;
 pub mod http;
 #[cfg(feature = "http")]
 pub mod legacy_storefronts;
+pub mod metrics;
+#[cfg(feature = "search-dispatch")]
+pub mod publish;
 #[cfg(feature = "reqwest-client")]
 pub(crate) mod reqwest;
 #[cfg(feature = "search")]
@@ -34,23 +37,34 @@ pub mod prelude {
 }
 
 mod runtime {
-    use std::{fmt::Debug, future::Future};
+    use std::{
+        collections::hash_map::RandomState,
+        fmt::Debug,
+        future::Future,
+        hash::{BuildHasher, Hash, Hasher},
+        net::SocketAddr,
+        sync::Arc,
+        time::Instant,
+    };
 
-    use futures_util::{stream::FuturesUnordered, FutureExt, StreamExt};
+    use futures_util::{future::BoxFuture, stream::FuturesUnordered, FutureExt, StreamExt};
     use indexer_core::{
         clap,
         clap::{Args, Parser},
         db,
     };
     use indexer_rabbitmq::{
+        broker::{Acknowledge, Failure, Subscriber},
         consumer::Consumer,
-        lapin,
-        lapin::options::{BasicAckOptions, BasicRejectOptions},
-        QueueType,
+        lapin, QueueType,
     };
-    use tokio::sync::{broadcast, broadcast::error::RecvError};
+    use tokio::sync::{broadcast, broadcast::error::RecvError, mpsc};
 
-    use super::{db::Pool, prelude::*};
+    use super::{
+        db::{BatchArgs, Pool},
+        metrics::{Metrics, ProcessorMetrics},
+        prelude::*,
+    };
 
     #[derive(Debug, Parser)]
     struct Opts<T: Debug + Args> {
@@ -58,9 +72,16 @@ mod runtime {
         #[clap(short = 'j', env)]
         thread_count: Option<usize>,
 
+        /// Address to serve health checks and Prometheus metrics on
+        #[clap(long, env)]
+        metrics_addr: Option<SocketAddr>,
+
         #[clap(flatten)]
         db: db::ConnectArgs,
 
+        #[clap(flatten)]
+        batch: BatchArgs,
+
         #[clap(flatten)]
         extra: T,
     }
@@ -70,6 +91,16 @@ mod runtime {
     #[derive(Debug)]
     pub struct Params {
         concurrency: usize,
+        metrics: Arc<Metrics>,
+    }
+
+    impl Params {
+        /// The number of messages to process concurrently, which defaults to
+        /// the number of CPUs
+        #[must_use]
+        pub fn concurrency(&self) -> usize {
+            self.concurrency
+        }
     }
 
     /// Entrypoint for `holaplex-indexer` binaries
@@ -83,14 +114,12 @@ mod runtime {
 
             let Opts {
                 thread_count,
+                metrics_addr,
                 db,
+                batch,
                 extra,
             } = opts;
 
-            let db = Pool::new(
-                db::connect(db, db::ConnectMode::Write).context("Failed to connect to Postgres")?,
-            );
-
             let rt = {
                 let mut b = tokio::runtime::Builder::new_multi_thread();
 
@@ -103,9 +132,28 @@ mod runtime {
                     .context("Failed to initialize async runtime")?
             };
 
+            let db =
+                Pool::connect(&db, batch, rt.handle()).context("Failed to connect to Postgres")?;
+
             let concurrency = thread_count.unwrap_or_else(indexer_core::num_cpus::get);
+            let metrics =
+                Metrics::new_rc(db.clone()).context("Failed to initialize metrics registry")?;
 
-            rt.block_on(f(extra, Params { concurrency }, db))
+            rt.block_on(async move {
+                if let Some(addr) = metrics_addr {
+                    tokio::spawn(metrics.serve(addr)?);
+                }
+
+                f(
+                    extra,
+                    Params {
+                        concurrency,
+                        metrics,
+                    },
+                    db,
+                )
+                .await
+            })
         })
     }
 
@@ -120,17 +168,7 @@ mod runtime {
         lapin::Connection::connect(
             addr.as_ref(),
             lapin::ConnectionProperties::default()
-                .with_connection_name(
-                    format!(
-                        "{}@{}",
-                        name,
-                        hostname::get()
-                            .context("Failed to get system hostname")?
-                            .into_string()
-                            .map_err(|_| anyhow!("Failed to parse system hostname"))?,
-                    )
-                    .into(),
-                )
+                .with_connection_name(client_name(name)?.into())
                 .with_executor(tokio_executor_trait::Tokio::current())
                 .with_reactor(tokio_reactor_trait::Tokio),
         )
@@ -138,118 +176,392 @@ mod runtime {
         .context("Failed to connect to the AMQP server")
     }
 
+    /// Create a Kafka client configuration for the given comma-separated list
+    /// of bootstrap servers
+    ///
+    /// # Errors
+    /// This function fails if the system hostname cannot be determined
+    #[cfg(feature = "kafka")]
+    pub fn kafka_config(
+        brokers: impl Into<String>,
+        name: &'static str,
+    ) -> Result<indexer_rabbitmq::kafka::ClientConfig> {
+        let mut config = indexer_rabbitmq::kafka::ClientConfig::new();
+
+        config
+            .set("bootstrap.servers", brokers)
+            .set("client.id", client_name(name)?);
+
+        Ok(config)
+    }
+
+    fn client_name(name: &str) -> Result<String> {
+        Ok(format!(
+            "{}@{}",
+            name,
+            hostname::get()
+                .context("Failed to get system hostname")?
+                .into_string()
+                .map_err(|_| anyhow!("Failed to parse system hostname"))?,
+        ))
+    }
+
+    /// The number of messages that may be queued for each worker
+    const SHARD_CAPACITY: usize = 64;
+
+    /// A message which can be routed to a consistent worker, so that messages
+    /// sharing a key are processed in the order they were received
+    pub trait ShardKey {
+        /// The type of key messages are sharded by
+        type Key: Hash;
+
+        /// Get the key to shard this message by, or `None` if the message may
+        /// be processed by any worker
+        fn shard_key(&self) -> Option<Self::Key>;
+    }
+
+    /// The outcome of successfully processing a message
+    pub enum Processed {
+        /// The message was handled and may be acknowledged immediately
+        Done,
+        /// The message was accepted, but must not be acknowledged until the
+        /// given future resolves.  If it resolves to an error the message is
+        /// dead-lettered as if processing had failed.
+        Deferred(BoxFuture<'static, Result<()>>),
+    }
+
+    impl From<()> for Processed {
+        fn from((): ()) -> Self {
+            Self::Done
+        }
+    }
+
+    impl Debug for Processed {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            match self {
+                Self::Done => f.write_str("Done"),
+                Self::Deferred(_) => f.write_str("Deferred(..)"),
+            }
+        }
+    }
+
     enum StopType {
         Hangup,
         Stopped,
     }
 
-    async fn consume_one<Q: QueueType, F: Future<Output = Result<()>>>(
-        worker_id: usize,
-        mut consumer: Consumer<Q>,
-        process: impl Fn(Q::Message) -> F,
+    // Ideally T would be ! but ! is unstable.
+    fn handle_stop<T>(r: Result<(), RecvError>) -> Result<Option<T>> {
+        match r {
+            Ok(()) | Err(RecvError::Closed) => Ok(None),
+            Err(e) => Err(e).context("Error receiving stop signal"),
+        }
+    }
+
+    /// Read messages from a consumer, dispatching each to the worker selected
+    /// by its shard key
+    async fn dispatch<S: Subscriber>(
+        mut consumer: S,
+        shards: Vec<mpsc::Sender<(S::Message, S::Acker)>>,
         mut stop_rx: broadcast::Receiver<()>,
     ) -> Result<StopType>
     where
-        Q::Message: Debug + for<'de> serde::Deserialize<'de>,
+        S::Message: ShardKey,
     {
-        enum Delivery<T> {
-            Message(Option<(T, lapin::acker::Acker)>),
-            Stop,
-        }
+        let hasher = RandomState::new();
+        let mut next = 0;
+
+        loop {
+            let del = tokio::select! {
+                r = consumer.read() => match r {
+                    Ok(d) => Some(d),
+                    Err(e) if !e.is_connection_error() => {
+                        warn!("Skipping unreadable message: {:?}", e);
+                        continue;
+                    },
+                    Err(e) => return Err(e).context("Failed to read message"),
+                },
+                r = stop_rx.recv() => handle_stop(r)?,
+            };
+
+            let (msg, acker) = match del {
+                Some(Some(d)) => d,
+                Some(None) => break Ok(StopType::Hangup),
+                None => break Ok(StopType::Stopped),
+            };
+
+            // Truncating the hash on 32-bit targets is harmless
+            #[allow(clippy::cast_possible_truncation)]
+            let shard = if let Some(key) = msg.shard_key() {
+                let mut h = hasher.build_hasher();
+                key.hash(&mut h);
 
-        // Ideally T would be ! but ! is unstable.
-        fn handle_stop<T>(r: Result<(), RecvError>) -> Result<Delivery<T>> {
-            match r {
-                Ok(()) | Err(RecvError::Closed) => Ok(Delivery::Stop),
-                Err(e) => Err(e).context("Error receiving stop signal"),
+                h.finish() as usize % shards.len()
+            } else {
+                next = (next + 1) % shards.len();
+                next
+            };
+
+            tokio::select! {
+                r = shards[shard].send((msg, acker)) => {
+                    r.map_err(|_| anyhow!("Worker {} hung up", shard))?;
+                },
+                r = stop_rx.recv() => {
+                    handle_stop::<()>(r)?;
+                    break Ok(StopType::Stopped);
+                },
             }
         }
+    }
 
+    async fn consume_one<M, A, O, F>(
+        worker_id: usize,
+        tag: Arc<str>,
+        metrics: ProcessorMetrics,
+        mut rx: mpsc::Receiver<(M, A)>,
+        process: impl Fn(M) -> F,
+        mut stop_rx: broadcast::Receiver<()>,
+    ) -> Result<()>
+    where
+        M: Debug,
+        A: Acknowledge + 'static,
+        O: Into<Processed>,
+        F: Future<Output = Result<O>>,
+    {
         loop {
             let del = tokio::select! {
-                r = consumer.read() => {
-                    Delivery::Message(r.context("Failed to read AMQP message")?)
-                },
+                d = rx.recv() => Some(d),
                 r = stop_rx.recv() => handle_stop(r)?,
             };
 
             let (msg, acker) = match del {
-                Delivery::Message(Some(d)) => d,
-                Delivery::Message(None) => break Ok(StopType::Hangup),
-                Delivery::Stop => break Ok(StopType::Stopped),
+                Some(Some(d)) => d,
+                Some(None) | None => break Ok(()),
             };
 
             trace!("Worker {}: {:?}", worker_id, msg);
 
-            match process(msg).await {
-                Ok(()) => acker
-                    .ack(BasicAckOptions::default())
-                    .await
-                    .context("Failed to send ACK for delivery")?,
-                Err(e) => {
-                    warn!("Failed to process message: {:?}", e);
+            let start = Instant::now();
+            let res = match process(msg).await.map(Into::into) {
+                Ok(Processed::Done) => Ok(()),
+                Ok(Processed::Deferred(fut)) => {
+                    // Settle the message in the background so this worker can
+                    // move on to the next one
+                    let tag = Arc::clone(&tag);
+                    let metrics = metrics.clone();
+
+                    tokio::spawn(async move {
+                        let res = fut.await;
+                        metrics.processed(start.elapsed(), res.is_ok());
+
+                        if let Err(e) = settle(worker_id, &tag, acker, res).await {
+                            error!("Failed to settle deferred delivery: {:?}", e);
+                        }
+                    });
+
+                    continue;
+                },
+                Err(e) => Err(e),
+            };
+            metrics.processed(start.elapsed(), res.is_ok());
 
-                    acker
-                        .reject(BasicRejectOptions { requeue: false })
-                        .await
-                        .context("Failed to send NAK for delivery")?;
+            match settle(worker_id, &tag, acker, res).await {
+                Ok(()) => (),
+                Err(e) if !is_connection_error(&e) => {
+                    error!("Failed to settle delivery: {:?}", e);
                 },
+                Err(e) => break Err(e),
             }
         }
     }
 
-    /// Consume messages from an AMQP consumer until the connection closes
+    /// Returns true if `err` was caused by the broker connection failing, in
+    /// which case the session must be restarted
+    fn is_connection_error(err: &Error) -> bool {
+        err.downcast_ref::<indexer_rabbitmq::Error>()
+            .map_or(false, indexer_rabbitmq::Error::is_connection_error)
+    }
+
+    /// Acknowledge or dead-letter a message according to the result of
+    /// processing it
+    async fn settle<A: Acknowledge>(
+        worker_id: usize,
+        tag: &str,
+        acker: A,
+        res: Result<()>,
+    ) -> Result<()> {
+        match res {
+            Ok(()) => acker.ack().await.context("Failed to send ACK for delivery"),
+            Err(e) => {
+                warn!("Failed to process message: {:?}", e);
+
+                let errors: Vec<_> = e.chain().map(ToString::to_string).collect();
+
+                acker
+                    .fail(Failure {
+                        processor: tag,
+                        worker: worker_id,
+                        errors: &errors,
+                    })
+                    .await
+                    .context("Failed to dead-letter delivery")
+            },
+        }
+    }
+
+    /// Delay before the first attempt to reconnect to the AMQP server
+    const MIN_RECONNECT_DELAY: StdDuration = StdDuration::from_secs(1);
+    /// Maximum delay between attempts to reconnect to the AMQP server
+    const MAX_RECONNECT_DELAY: StdDuration = StdDuration::from_secs(60);
+
+    /// The reason [`consume_until`] returned
+    enum Shutdown {
+        Signal,
+        Disconnected,
+    }
+
+    /// Consume messages from an AMQP queue until a stop signal is received,
+    /// retrying failed messages with the dead-letter consumer
+    ///
+    /// The connection is supervised: if the AMQP server hangs up or a worker
+    /// fails, the runtime reconnects with exponential backoff, re-declares the
+    /// queue, and calls `init` to create a new message processor (along with
+    /// any producers it uses) for the new connection.  Errors reading or
+    /// settling a single message are logged and skipped, and only channel or
+    /// connection errors trigger a reconnect.
     ///
     /// # Errors
-    /// This function fails if a message cannot be received, but _does not_ fail
-    /// if a received message fails to process.
+    /// This function fails if the stop signal cannot be hooked, but _does not_
+    /// fail if the connection is lost or a received message fails to process.
     ///
     /// # Panics
     /// This function will panic if the internal scheduler enters a deadlock
     /// state.
-    pub async fn amqp_consume<
-        Q: QueueType + Send + Sync + 'static,
-        F: Send + Future<Output = Result<()>> + 'static,
-    >(
+    pub async fn amqp_consume<Q, I, P, O, F>(
         params: &Params,
-        conn: indexer_rabbitmq::lapin::Connection,
-        consumer: Consumer<Q>,
+        addr: impl AsRef<str>,
+        name: &'static str,
         queue_type: Q,
+        tag: &'static str,
         grace_period: StdDuration,
-        process: impl Fn(Q::Message) -> F + Send + Sync + Clone + 'static,
+        init: impl Fn(Arc<lapin::Connection>) -> I,
     ) -> Result<()>
     where
-        Q::Message: Debug + Send + for<'a> serde::Deserialize<'a>,
+        Q: QueueType + Clone + Send + Sync + 'static,
+        Q::Message: Debug + Send + ShardKey + for<'a> serde::Deserialize<'a>,
+        I: Future<Output = Result<P>>,
+        P: Fn(Q::Message) -> F + Send + Sync + Clone + 'static,
+        O: Into<Processed> + Send + 'static,
+        F: Send + Future<Output = Result<O>> + 'static,
     {
-        let Params { concurrency } = *params;
+        let signal = stop_signal()?;
+        tokio::pin!(signal);
 
-        let dl_task = tokio::spawn(indexer_rabbitmq::dl_consumer::run(
-            conn,
-            queue_type,
-            tokio::time::sleep,
-        ));
+        let mut delay = MIN_RECONNECT_DELAY;
 
-        let (stop_tx, _stop_rx) = broadcast::channel(1);
+        loop {
+            let session = async {
+                let conn = Arc::new(amqp_connect(addr.as_ref(), name).await?);
+                let consumer = Consumer::new(&conn, queue_type.clone(), tag)
+                    .await
+                    .context("Failed to create queue consumer")?;
+                let process = init(Arc::clone(&conn)).await?;
 
-        let mut q_tasks = (0..concurrency)
-            .map(|i| {
-                tokio::spawn(consume_one(
-                    i,
-                    consumer.clone(),
-                    process.clone(),
-                    stop_tx.subscribe(),
-                ))
-                .map(|r| match r {
-                    Ok(Ok(StopType::Hangup)) => warn!("AMQP server hung up!"),
-                    Ok(Ok(StopType::Stopped)) => (),
-                    Ok(Err(e)) => error!("Fatal error in worker: {:?}", e),
-                    Err(e) => error!("Worker terminated unexpectedly: {:?}", e),
-                })
-            })
-            .collect::<FuturesUnordered<_>>();
+                Result::<_>::Ok((conn, consumer, process))
+            };
+
+            let session = tokio::select! {
+                s = session => s,
+                s = &mut signal => return stopped(s),
+            };
+
+            match session {
+                Ok((conn, consumer, process)) => {
+                    let started = Instant::now();
+                    let dl_task = tokio::spawn(indexer_rabbitmq::dl_consumer::run(
+                        conn,
+                        queue_type.clone(),
+                        tokio::time::sleep,
+                    ));
+
+                    let res =
+                        consume_until(params, consumer, grace_period, process, &mut signal).await;
+
+                    dl_task.abort();
+
+                    // NB: this shouldn't need the grace period because we abort the task
+                    dl_task
+                        .await
+                        .map_err(|e| {
+                            if !e.is_cancelled() {
+                                error!("DLX consumer cleanup failed: {:?}", e);
+                            }
+                        })
+                        .unwrap_or(());
+
+                    if let Shutdown::Signal = res? {
+                        return Ok(());
+                    }
+
+                    // Only back off further if the connection keeps dropping
+                    if started.elapsed() > MAX_RECONNECT_DELAY {
+                        delay = MIN_RECONNECT_DELAY;
+                    }
+                },
+                Err(e) => error!("Failed to start AMQP session: {:?}", e),
+            }
 
-        let signal;
+            warn!("Reconnecting to the AMQP server in {:?}...", delay);
+
+            tokio::select! {
+                () = tokio::time::sleep(delay) => (),
+                s = &mut signal => return stopped(s),
+            }
+
+            delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+        }
+    }
 
+    /// Consume messages from any broker [`Subscriber`] until the broker hangs
+    /// up or a stop signal is received
+    ///
+    /// Messages are dispatched to workers by their [`ShardKey`], so messages
+    /// sharing a key are processed one at a time in the order they were
+    /// received.  Messages which fail to process may be retried out of order.
+    /// A processor may return [`Processed::Deferred`] to hold a message's
+    /// acknowledgement until it has been fully handled.
+    ///
+    /// # Errors
+    /// This function fails if a message cannot be received, but _does not_ fail
+    /// if a received message fails to process.
+    ///
+    /// # Panics
+    /// This function will panic if the internal scheduler enters a deadlock
+    /// state.
+    pub async fn consume<S, O, F>(
+        params: &Params,
+        consumer: S,
+        grace_period: StdDuration,
+        process: impl Fn(S::Message) -> F + Send + Sync + Clone + 'static,
+    ) -> Result<()>
+    where
+        S: Subscriber,
+        S::Message: Debug + ShardKey,
+        S::Acker: 'static,
+        O: Into<Processed> + Send + 'static,
+        F: Send + Future<Output = Result<O>> + 'static,
+    {
+        let signal = stop_signal()?;
+        tokio::pin!(signal);
+
+        consume_until(params, consumer, grace_period, process, &mut signal)
+            .await
+            .map(|_| ())
+    }
+
+    /// Hook the signals used to stop an indexer, returning a future resolving
+    /// to the first signal received
+    fn stop_signal() -> Result<impl Future<Output = Result<Option<impl Debug>>>> {
         #[cfg(unix)]
         {
             use tokio::signal::unix::SignalKind;
@@ -271,15 +583,13 @@ mod runtime {
             })
             .collect::<Result<FuturesUnordered<_>>>()?;
 
-            signal = async move { stream.next().await.transpose() }
+            Ok(async move { stream.next().await.transpose() })
         }
 
         #[cfg(not(unix))]
         {
             use std::fmt;
 
-            use futures_util::TryFutureExt;
-
             struct CtrlC;
 
             impl fmt::Debug for CtrlC {
@@ -288,25 +598,117 @@ mod runtime {
                 }
             }
 
-            signal = tokio::signal::ctrl_c().map_ok(|()| Some(CtrlC));
+            Ok(tokio::signal::ctrl_c().map(|r| {
+                r.map(|()| Some(CtrlC))
+                    .context("Failed to hook interrupt signal")
+            }))
         }
+    }
 
-        let signal = tokio::select! {
-            _ = q_tasks.next() => Ok(None),
-            s = signal => s,
+    fn stopped(signal: Result<Option<impl Debug>>) -> Result<()> {
+        if let Some(signal) = signal.context("Failed to wait for stop signal")? {
+            warn!("{:?} received, shutting down...", signal);
         }
-        .context("Failed to wait for stop signal")?;
 
-        //////// Everything past this point is graceful failure! ////////
+        Ok(())
+    }
 
-        if let Some(signal) = signal {
-            warn!("{:?} received, shutting down...", signal);
-        } else {
-            warn!("Worker terminated unexpectedly, shutting down...");
-        }
+    async fn consume_until<S, O, F>(
+        params: &Params,
+        consumer: S,
+        grace_period: StdDuration,
+        process: impl Fn(S::Message) -> F + Send + Sync + Clone + 'static,
+        signal: &mut (impl Future<Output = Result<Option<impl Debug>>> + Unpin),
+    ) -> Result<Shutdown>
+    where
+        S: Subscriber,
+        S::Message: Debug + ShardKey,
+        S::Acker: 'static,
+        O: Into<Processed> + Send + 'static,
+        F: Send + Future<Output = Result<O>> + 'static,
+    {
+        let Params {
+            concurrency,
+            ref metrics,
+        } = *params;
+
+        let (stop_tx, _stop_rx) = broadcast::channel(1);
+        let tag: Arc<str> = consumer.tag().into();
+        let processor_metrics = metrics.processor(&tag);
+
+        let (shards, mut q_tasks): (Vec<_>, FuturesUnordered<_>) = (0..concurrency.max(1))
+            .map(|i| {
+                let (tx, rx) = mpsc::channel(SHARD_CAPACITY);
+
+                let task = tokio::spawn(consume_one(
+                    i,
+                    Arc::clone(&tag),
+                    processor_metrics.clone(),
+                    rx,
+                    process.clone(),
+                    stop_tx.subscribe(),
+                ))
+                .map(|r| match r {
+                    Ok(Ok(())) => (),
+                    Ok(Err(e)) => error!("Fatal error in worker: {:?}", e),
+                    Err(e) => error!("Worker terminated unexpectedly: {:?}", e),
+                });
+
+                (tx, task)
+            })
+            .unzip();
+
+        metrics.consumer_up(&tag, true);
+
+        let mut dispatcher = tokio::spawn(dispatch(consumer, shards, stop_tx.subscribe())).map({
+            let metrics = Arc::clone(metrics);
+            let tag = Arc::clone(&tag);
+
+            move |r| {
+                metrics.consumer_up(&tag, false);
+
+                match r {
+                    Ok(Ok(StopType::Hangup)) => {
+                        warn!("Message broker hung up!");
+                        metrics.hangup(&tag);
+                    },
+                    Ok(Ok(StopType::Stopped)) => (),
+                    Ok(Err(e)) => error!("Fatal error in dispatcher: {:?}", e),
+                    Err(e) => error!("Dispatcher terminated unexpectedly: {:?}", e),
+                }
+            }
+        });
+
+        let stop = tokio::select! {
+            () = &mut dispatcher => None,
+            Some(()) = q_tasks.next() => {
+                warn!("Worker terminated unexpectedly, stopping consumer...");
+                Some(Shutdown::Disconnected)
+            },
+            s = &mut *signal => {
+                stopped(s)?;
+                Some(Shutdown::Signal)
+            },
+        };
+
+        let stop = match stop {
+            Some(s) => s,
+            // The dispatcher has stopped, so let the workers finish any
+            // messages already dispatched to them
+            None => tokio::select! {
+                () = async { while let Some(()) = q_tasks.next().await {} } => {
+                    Shutdown::Disconnected
+                },
+                s = &mut *signal => {
+                    stopped(s)?;
+                    Shutdown::Signal
+                },
+            },
+        };
+
+        //////// Everything past this point is graceful failure! ////////
 
         stop_tx.send(()).unwrap();
-        dl_task.abort();
 
         if !q_tasks.is_empty() {
             info!("Waiting for additional jobs to finish...");
@@ -319,16 +721,121 @@ mod runtime {
 
         std::mem::drop(stop_tx);
 
-        // NB: this shouldn't need the grace period because we abort the task
-        dl_task
-            .await
-            .map_err(|e| {
-                if !e.is_cancelled() {
-                    error!("DLX consumer cleanup failed: {:?}", e);
+        Ok(stop)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::collections::VecDeque;
+
+        use super::*;
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        struct Msg {
+            key: Option<u8>,
+            seq: usize,
+        }
+
+        impl ShardKey for Msg {
+            type Key = u8;
+
+            fn shard_key(&self) -> Option<u8> {
+                self.key
+            }
+        }
+
+        #[derive(Debug, Clone)]
+        struct Fake(VecDeque<Msg>);
+
+        #[derive(Debug)]
+        struct Acker;
+
+        #[async_trait::async_trait]
+        impl Acknowledge for Acker {
+            async fn ack(self) -> indexer_rabbitmq::Result<()> {
+                Ok(())
+            }
+
+            async fn fail(self, _: Failure<'_>) -> indexer_rabbitmq::Result<()> {
+                Ok(())
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl Subscriber for Fake {
+            type Acker = Acker;
+            type Message = Msg;
+
+            fn tag(&self) -> &str {
+                "test"
+            }
+
+            async fn read(&mut self) -> indexer_rabbitmq::Result<Option<(Msg, Acker)>> {
+                Ok(self.0.pop_front().map(|m| (m, Acker)))
+            }
+        }
+
+        /// Dispatch `msgs` across `n` shards, returning what each shard
+        /// received in the order it received it
+        async fn run(msgs: Vec<Msg>, n: usize) -> Vec<Vec<Msg>> {
+            let (stop_tx, stop_rx) = broadcast::channel(1);
+            let (shards, rxs): (Vec<_>, Vec<_>) =
+                (0..n).map(|_| mpsc::channel(SHARD_CAPACITY)).unzip();
+
+            let stop = dispatch(Fake(msgs.into()), shards, stop_rx).await.unwrap();
+            assert!(matches!(stop, StopType::Hangup));
+            std::mem::drop(stop_tx);
+
+            let mut out = vec![];
+
+            for mut rx in rxs {
+                let mut shard = vec![];
+
+                while let Some((msg, Acker)) = rx.recv().await {
+                    shard.push(msg);
                 }
-            })
-            .unwrap_or(());
 
-        Ok(())
+                out.push(shard);
+            }
+
+            out
+        }
+
+        #[tokio::test]
+        async fn keeps_keyed_messages_in_order_on_one_shard() {
+            let msgs: Vec<_> = (0..48)
+                .map(|seq| Msg {
+                    key: Some(u8::try_from(seq % 6).unwrap()),
+                    seq,
+                })
+                .collect();
+
+            let out = run(msgs.clone(), 4).await;
+
+            for key in 0..6 {
+                let holders: Vec<_> = out
+                    .iter()
+                    .filter(|s| s.iter().any(|m| m.key == Some(key)))
+                    .collect();
+                assert_eq!(holders.len(), 1, "key {} was split across shards", key);
+
+                let received: Vec<_> = holders[0].iter().filter(|m| m.key == Some(key)).collect();
+                let sent: Vec<_> = msgs.iter().filter(|m| m.key == Some(key)).collect();
+                assert_eq!(received, sent);
+            }
+        }
+
+        #[tokio::test]
+        async fn spreads_keyless_messages_round_robin() {
+            let msgs: Vec<_> = (0..8).map(|seq| Msg { key: None, seq }).collect();
+
+            let out = run(msgs, 4).await;
+
+            let seqs: Vec<Vec<_>> = out
+                .iter()
+                .map(|s| s.iter().map(|m| m.seq).collect())
+                .collect();
+            assert_eq!(seqs, vec![vec![3, 7], vec![0, 4], vec![1, 5], vec![2, 6]]);
+        }
     }
 }
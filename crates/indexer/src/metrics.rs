@@ -0,0 +1,238 @@
+//! Health checks and Prometheus metrics for indexer consumers
+
+use std::{convert::Infallible, future::Future, net::SocketAddr, sync::Arc, time::Duration};
+
+use hyper::{
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use indexer_core::hash::DashMap;
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec, Opts, Registry, TextEncoder,
+};
+
+use crate::{db::Pool, prelude::*};
+
+/// Metrics recorded by every indexer binary
+#[derive(Debug)]
+pub struct Metrics {
+    messages: IntCounterVec,
+    failures: IntCounterVec,
+    latency: HistogramVec,
+    consumer_up: IntGaugeVec,
+    hangups: IntCounterVec,
+    db_connections: IntGauge,
+    db_idle_connections: IntGauge,
+    db_max_connections: IntGauge,
+    consumers: DashMap<String, bool>,
+    db: Pool,
+    registry: Registry,
+}
+
+/// Metric handles for a single message processor
+#[derive(Debug, Clone)]
+pub(crate) struct ProcessorMetrics {
+    messages: IntCounter,
+    failures: IntCounter,
+    latency: Histogram,
+}
+
+impl ProcessorMetrics {
+    /// Record a message processed in the given time
+    pub fn processed(&self, elapsed: Duration, ok: bool) {
+        self.messages.inc();
+        self.latency.observe(elapsed.as_secs_f64());
+
+        if !ok {
+            self.failures.inc();
+        }
+    }
+}
+
+impl Metrics {
+    pub(crate) fn new_rc(db: Pool) -> Result<Arc<Self>> {
+        let registry = Registry::new();
+
+        let messages = IntCounterVec::new(
+            Opts::new("indexer_messages_total", "Messages processed"),
+            &["processor"],
+        )?;
+        let failures = IntCounterVec::new(
+            Opts::new("indexer_failures_total", "Messages which failed to process"),
+            &["processor"],
+        )?;
+        let latency = HistogramVec::new(
+            HistogramOpts::new(
+                "indexer_processing_seconds",
+                "Time taken to process a message",
+            )
+            .buckets(vec![
+                0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0,
+            ]),
+            &["processor"],
+        )?;
+        let consumer_up = IntGaugeVec::new(
+            Opts::new(
+                "indexer_consumer_up",
+                "Whether a consumer is receiving messages from its broker",
+            ),
+            &["processor"],
+        )?;
+        let hangups = IntCounterVec::new(
+            Opts::new(
+                "indexer_broker_hangups_total",
+                "Consumers hung up on by their broker",
+            ),
+            &["processor"],
+        )?;
+        let db_connections = IntGauge::new(
+            "indexer_db_connections",
+            "Open connections in the database pool",
+        )?;
+        let db_idle_connections = IntGauge::new(
+            "indexer_db_idle_connections",
+            "Idle connections in the database pool",
+        )?;
+        let db_max_connections = IntGauge::new(
+            "indexer_db_max_connections",
+            "Maximum size of the database pool",
+        )?;
+
+        registry.register(Box::new(messages.clone()))?;
+        registry.register(Box::new(failures.clone()))?;
+        registry.register(Box::new(latency.clone()))?;
+        registry.register(Box::new(consumer_up.clone()))?;
+        registry.register(Box::new(hangups.clone()))?;
+        registry.register(Box::new(db_connections.clone()))?;
+        registry.register(Box::new(db_idle_connections.clone()))?;
+        registry.register(Box::new(db_max_connections.clone()))?;
+
+        Ok(Arc::new(Self {
+            messages,
+            failures,
+            latency,
+            consumer_up,
+            hangups,
+            db_connections,
+            db_idle_connections,
+            db_max_connections,
+            consumers: DashMap::default(),
+            db,
+            registry,
+        }))
+    }
+
+    /// Get the metric handles for the processor with the given tag
+    pub(crate) fn processor(&self, tag: &str) -> ProcessorMetrics {
+        ProcessorMetrics {
+            messages: self.messages.with_label_values(&[tag]),
+            failures: self.failures.with_label_values(&[tag]),
+            latency: self.latency.with_label_values(&[tag]),
+        }
+    }
+
+    /// Record whether the consumer with the given tag is receiving messages
+    pub(crate) fn consumer_up(&self, tag: &str, up: bool) {
+        self.consumer_up
+            .with_label_values(&[tag])
+            .set(i64::from(up));
+        self.consumers.insert(tag.into(), up);
+    }
+
+    /// Record that the consumer with the given tag was hung up on
+    pub(crate) fn hangup(&self, tag: &str) {
+        self.hangups.with_label_values(&[tag]).inc();
+    }
+
+    /// Returns true if at least one consumer has started and every consumer
+    /// is receiving messages
+    fn ready(&self) -> bool {
+        !self.consumers.is_empty() && self.consumers.iter().all(|c| *c.value())
+    }
+
+    fn render(&self) -> Result<Vec<u8>> {
+        let (connections, idle, max) = self.db.utilization();
+
+        self.db_connections.set(connections.into());
+        self.db_idle_connections.set(idle.into());
+        self.db_max_connections.set(max.into());
+
+        let mut buf = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buf)
+            .context("Failed to encode metrics")?;
+
+        Ok(buf)
+    }
+
+    /// Bind a listener serving `/healthz`, `/readyz` and Prometheus
+    /// `/metrics` to the given address, returning the server future.  This
+    /// must be called from within a Tokio runtime.
+    ///
+    /// # Errors
+    /// This function fails if the listener cannot be bound.
+    pub(crate) fn serve(
+        self: &Arc<Self>,
+        addr: SocketAddr,
+    ) -> Result<impl Future<Output = ()> + Send + 'static> {
+        let this = Arc::clone(self);
+
+        let server = Server::try_bind(&addr)
+            .with_context(|| format!("Failed to bind metrics listener to {}", addr))?
+            .serve(make_service_fn(move |_| {
+                let this = Arc::clone(&this);
+
+                async move {
+                    Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                        let this = Arc::clone(&this);
+
+                        async move { Ok::<_, Infallible>(this.handle(&req)) }
+                    }))
+                }
+            }));
+
+        info!("Serving health checks and metrics on {}", addr);
+
+        Ok(async move {
+            if let Err(e) = server.await {
+                error!("Metrics listener failed: {:?}", e);
+            }
+        })
+    }
+
+    fn handle(&self, req: &Request<Body>) -> Response<Body> {
+        let mut res = Response::new(Body::empty());
+
+        match (req.method(), req.uri().path()) {
+            (&Method::GET, "/healthz") => *res.body_mut() = "ok".into(),
+            (&Method::GET, "/readyz") => {
+                if self.ready() {
+                    *res.body_mut() = "ok".into();
+                } else {
+                    *res.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+                }
+            },
+            (&Method::GET, "/metrics") => match self.render() {
+                Ok(buf) => {
+                    res.headers_mut().insert(
+                        CONTENT_TYPE,
+                        TextEncoder::new()
+                            .format_type()
+                            .parse()
+                            .unwrap_or_else(|_| unreachable!()),
+                    );
+                    *res.body_mut() = buf.into();
+                },
+                Err(e) => {
+                    error!("{:?}", e);
+                    *res.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                },
+            },
+            _ => *res.status_mut() = StatusCode::NOT_FOUND,
+        }
+
+        res
+    }
+}
@@ -0,0 +1,75 @@
+//! Support for publishing messages to other indexers
+
+use indexer_core::clap;
+use indexer_rabbitmq::{
+    broker::Publisher,
+    compression::{self, Policy},
+    lapin,
+    producer::{Confirms, Producer},
+    serialize::Format,
+    QueueType,
+};
+
+use crate::prelude::*;
+
+/// A type-erased message publisher for any broker
+pub type BoxPublisher<M> = Box<dyn Publisher<Message = M>>;
+
+/// Common arguments for indexers publishing messages over AMQP
+#[derive(Debug, Clone, Copy, clap::Args)]
+pub struct ProducerArgs {
+    /// Wait for the broker to confirm messages sent to other indexers
+    #[clap(long, env)]
+    amqp_confirms: bool,
+
+    /// The wire format to send messages to other indexers in
+    #[clap(long, env, default_value_t)]
+    amqp_wire_format: Format,
+
+    /// Compress the payloads of messages sent to other indexers
+    #[clap(long, env)]
+    amqp_compression: Option<compression::Encoding>,
+
+    /// The size in bytes below which message payloads are sent uncompressed
+    #[clap(long, env, default_value_t = compression::DEFAULT_THRESHOLD)]
+    amqp_compression_threshold: usize,
+}
+
+impl ProducerArgs {
+    /// Create an AMQP publisher for the given queue type configured by these
+    /// arguments
+    ///
+    /// # Errors
+    /// This function fails if the AMQP producer cannot be created.
+    pub async fn publisher<Q: QueueType + Send + Sync + 'static>(
+        self,
+        conn: &lapin::Connection,
+        ty: Q,
+    ) -> Result<BoxPublisher<Q::Message>>
+    where
+        Q::Message: serde::Serialize + Sync,
+    {
+        let Self {
+            amqp_confirms,
+            amqp_wire_format,
+            amqp_compression,
+            amqp_compression_threshold,
+        } = self;
+
+        let confirms = if amqp_confirms {
+            Confirms::enabled()
+        } else {
+            Confirms::Disabled
+        };
+
+        let producer = Producer::with_confirms(conn, ty, confirms)
+            .await?
+            .with_format(amqp_wire_format)
+            .with_compression(amqp_compression.map(|encoding| Policy {
+                encoding,
+                threshold: amqp_compression_threshold,
+            }));
+
+        Ok(Box::new(producer))
+    }
+}
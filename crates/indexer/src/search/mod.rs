@@ -30,13 +30,25 @@ impl meilisearch::document::Document for Document {
     }
 }
 
+impl crate::ShardKey for Message {
+    type Key = String;
+
+    fn shard_key(&self) -> Option<String> {
+        match self {
+            Self::Upsert { document, .. } => Some(document.id.clone()),
+        }
+    }
+}
+
 /// Process a message from a search RabbitMQ queue
 ///
 /// # Errors
 /// This function fails if an error occurs processing the message body.
 pub async fn process_message(msg: Message, client: &Client) -> Result<()> {
     match msg {
-        Message::Upsert { index, document } => {
+        Message::Upsert {
+            index, document, ..
+        } => {
             client
                 .upsert_documents(index, Some(document.into()))
                 .await?;
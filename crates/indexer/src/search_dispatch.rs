@@ -1,8 +1,12 @@
-use indexer_core::clap;
-use indexer_rabbitmq::search_indexer::{Document, Message, Producer, QueueType};
+use std::fmt;
+
+use indexer_rabbitmq::{
+    search_indexer::{Document, Message},
+    Priority,
+};
 use serde::Serialize;
 
-use crate::prelude::*;
+use crate::{prelude::*, publish::BoxPublisher};
 
 #[derive(Debug, Serialize)]
 pub struct TwitterHandleDocument {
@@ -19,54 +23,42 @@ pub struct MetadataDocument {
     pub creator_twitter_handle: Option<String>,
 }
 
-#[derive(Debug, Clone, Copy, clap::Parser)]
-pub struct Args {
-    /// Pass this flag to run backfill search upsert jobs
-    ///
-    /// Be aware that this can have severe performance implications.
-    #[clap(long, env)]
-    backfill_search: bool,
+pub struct Client {
+    producer: BoxPublisher<Message>,
 }
 
-#[derive(Debug)]
-pub struct Client {
-    producer: Producer,
-    backfill: bool,
+impl fmt::Debug for Client {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Client").finish_non_exhaustive()
+    }
 }
 
 impl Client {
-    pub async fn new(
-        conn: &indexer_rabbitmq::lapin::Connection,
-        queue: QueueType,
-        Args { backfill_search }: Args,
-    ) -> Result<Self> {
-        Ok(Self {
-            producer: Producer::new(conn, queue)
-                .await
-                .context("Couldn't create AMQP search producer")?,
-            backfill: backfill_search,
-        })
+    pub fn new(producer: BoxPublisher<Message>) -> Self {
+        Self { producer }
     }
 
     #[inline]
     async fn dispatch_upsert(
         &self,
         is_for_backfill: bool,
+        priority: Priority,
         index: &'static str,
         id: impl std::fmt::Display,
         body: impl Serialize,
     ) -> Result<()> {
-        if is_for_backfill && !self.backfill {
-            return Ok(());
-        }
-
         self.producer
-            .write(Message::Upsert {
+            .write(&Message::Upsert {
                 index: index.to_owned(),
                 document: Document {
                     id: id.to_string(),
                     body: serde_json::to_value(body).context("Failed to upcast document body")?,
                 },
+                priority: if is_for_backfill {
+                    Priority::Backfill
+                } else {
+                    priority
+                },
             })
             .await
             .context("Failed to send upsert message")
@@ -75,22 +67,24 @@ impl Client {
     pub async fn upsert_metadata(
         &self,
         is_for_backfill: bool,
+        priority: Priority,
         key: String,
         body: MetadataDocument,
     ) -> Result<()> {
         debug_assert!(key.parse::<Pubkey>().is_ok());
 
-        self.dispatch_upsert(is_for_backfill, "metadatas", key, body)
+        self.dispatch_upsert(is_for_backfill, priority, "metadatas", key, body)
             .await
     }
 
     pub async fn upsert_twitter_handle(
         &self,
         is_for_backfill: bool,
+        priority: Priority,
         key: Pubkey,
         body: TwitterHandleDocument,
     ) -> Result<()> {
-        self.dispatch_upsert(is_for_backfill, "name_service", key, body)
+        self.dispatch_upsert(is_for_backfill, priority, "name_service", key, body)
             .await
     }
 }
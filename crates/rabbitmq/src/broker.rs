@@ -0,0 +1,80 @@
+//! Broker-agnostic interfaces for publishing and consuming messages
+//!
+//! The AMQP [`Producer`](crate::producer::Producer) and
+//! [`Consumer`](crate::consumer::Consumer) implement these traits, as do the
+//! Kafka types in `kafka` when the `kafka` feature is enabled.  Code written
+//! against these traits can switch brokers without changes.
+
+#[cfg(feature = "consumer")]
+pub use crate::consumer::Failure;
+use crate::Result;
+
+/// A handle for publishing messages of a single type to a broker
+#[cfg(feature = "producer")]
+#[async_trait::async_trait]
+pub trait Publisher: Send + Sync {
+    /// The type of message published
+    type Message: Sync;
+
+    /// Publish a batch of messages, waiting until the broker has accepted all
+    /// of them
+    ///
+    /// # Errors
+    /// This function fails if any message cannot be serialized or is not
+    /// accepted by the broker.  Messages preceding the failed one may have
+    /// already been published.
+    async fn write_batch(&self, msgs: &[Self::Message]) -> Result<()>;
+
+    /// Publish a single message, waiting until the broker has accepted it
+    ///
+    /// # Errors
+    /// This function fails if the message cannot be serialized or is not
+    /// accepted by the broker.
+    async fn write(&self, msg: &Self::Message) -> Result<()> {
+        self.write_batch(std::slice::from_ref(msg)).await
+    }
+}
+
+/// A handle for receiving messages of a single type from a broker
+///
+/// Subscribers are cloned once per worker, with every clone reading from the
+/// same underlying subscription.
+#[cfg(feature = "consumer")]
+#[async_trait::async_trait]
+pub trait Subscriber: Clone + Send + Sync + 'static {
+    /// The type of message received
+    type Message: Send;
+    /// The handle used to settle each received message
+    type Acker: Acknowledge;
+
+    /// The tag this subscriber was created with
+    fn tag(&self) -> &str;
+
+    /// Receive a single message, returning `None` if the broker hung up
+    ///
+    /// Messages whose payload cannot be decoded are dead-lettered by the
+    /// subscriber and skipped, so a single malformed message cannot stop the
+    /// consumer.
+    ///
+    /// # Errors
+    /// This function fails if the message cannot be received or an
+    /// undecodable message cannot be dead-lettered.
+    async fn read(&mut self) -> Result<Option<(Self::Message, Self::Acker)>>;
+}
+
+/// A handle for settling a message received from a [`Subscriber`]
+#[cfg(feature = "consumer")]
+#[async_trait::async_trait]
+pub trait Acknowledge: Send + Sized {
+    /// Mark the message as successfully processed
+    ///
+    /// # Errors
+    /// This function fails if the broker cannot be notified.
+    async fn ack(self) -> Result<()>;
+
+    /// Dead-letter the message, recording why it failed
+    ///
+    /// # Errors
+    /// This function fails if the message cannot be dead-lettered.
+    async fn fail(self, failure: Failure<'_>) -> Result<()>;
+}
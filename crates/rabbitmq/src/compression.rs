@@ -0,0 +1,230 @@
+//! Optional compression of message payloads
+//!
+//! Compressed messages are marked with the AMQP `content-encoding` property,
+//! so compression can be enabled per producer and payloads under a size
+//! threshold can be left uncompressed.  Consumers decompress any message
+//! whose encoding they recognize.
+
+#[cfg(feature = "consumer")]
+use std::{borrow::Cow, io::Read};
+
+#[cfg(feature = "consumer")]
+use crate::Error;
+use crate::Result;
+
+/// The default size in bytes below which payloads are sent uncompressed
+pub const DEFAULT_THRESHOLD: usize = 1024;
+
+/// The maximum size in bytes a consumer will decompress a payload to
+pub const MAX_DECOMPRESSED_LEN: usize = 64 << 20;
+
+/// A compression algorithm applied to a message payload, named as it appears
+/// in the `content-encoding` property
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    serde::Deserialize,
+    strum::EnumString,
+    strum::Display,
+    strum::IntoStaticStr,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Encoding {
+    /// Zstandard, favoring compression ratio
+    Zstd,
+    /// LZ4 with a prepended size, favoring speed
+    Lz4,
+}
+
+/// Settings for compressing the payloads of published messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Policy {
+    /// The algorithm to compress payloads with
+    pub encoding: Encoding,
+    /// The size in bytes below which payloads are sent uncompressed
+    #[serde(default = "default_threshold")]
+    pub threshold: usize,
+}
+
+fn default_threshold() -> usize {
+    DEFAULT_THRESHOLD
+}
+
+impl Policy {
+    /// Construct a policy compressing payloads of at least
+    /// [`DEFAULT_THRESHOLD`] bytes with the given algorithm
+    #[must_use]
+    pub fn new(encoding: Encoding) -> Self {
+        Self {
+            encoding,
+            threshold: DEFAULT_THRESHOLD,
+        }
+    }
+
+    /// Compress a payload if it meets the size threshold and compression
+    /// shrinks it, returning the encoding that was applied, if any
+    #[cfg(feature = "producer")]
+    pub(crate) fn compress(self, data: Vec<u8>) -> Result<(Vec<u8>, Option<Encoding>)> {
+        if data.len() < self.threshold {
+            return Ok((data, None));
+        }
+
+        let compressed = match self.encoding {
+            // Consumers read zstd payloads as a stream, so they must be
+            // framed rather than compressed as a raw block
+            Encoding::Zstd => zstd::stream::encode_all(data.as_slice(), 0)?,
+            Encoding::Lz4 => lz4_flex::compress_prepend_size(&data),
+        };
+
+        Ok(if compressed.len() < data.len() {
+            (compressed, Some(self.encoding))
+        } else {
+            (data, None)
+        })
+    }
+}
+
+/// Decompress a payload according to its `content-encoding` property
+///
+/// # Errors
+/// This function fails if the encoding is not recognized, the payload cannot
+/// be decompressed, or it would decompress to more than
+/// [`MAX_DECOMPRESSED_LEN`] bytes.
+#[cfg(feature = "consumer")]
+pub fn decompress<'a>(data: &'a [u8], content_encoding: Option<&str>) -> Result<Cow<'a, [u8]>> {
+    let encoding = match content_encoding {
+        None | Some("" | "identity") => return Ok(Cow::Borrowed(data)),
+        Some(e) => e
+            .parse()
+            .map_err(|_| Error::UnsupportedEncoding(e.into()))?,
+    };
+
+    Ok(Cow::Owned(match encoding {
+        Encoding::Zstd => {
+            let mut buf = vec![];
+            zstd::stream::read::Decoder::new(data)?
+                .take(MAX_DECOMPRESSED_LEN as u64 + 1)
+                .read_to_end(&mut buf)?;
+
+            if buf.len() > MAX_DECOMPRESSED_LEN {
+                return Err(Error::DecompressedTooLarge);
+            }
+
+            buf
+        },
+        Encoding::Lz4 => {
+            // Check the prepended size before lz4_flex allocates a buffer for it
+            let len = data
+                .get(..4)
+                .map_or(0, |b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+
+            if len as usize > MAX_DECOMPRESSED_LEN {
+                return Err(Error::DecompressedTooLarge);
+            }
+
+            lz4_flex::decompress_size_prepended(data)?
+        },
+    }))
+}
+
+#[cfg(all(test, feature = "producer", feature = "consumer"))]
+mod tests {
+    use super::*;
+
+    fn policy(encoding: Encoding) -> Policy {
+        Policy {
+            encoding,
+            threshold: 64,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let data: Vec<u8> = (0..4096).map(|i| b"abcdefg"[i % 7]).collect();
+
+        for encoding in [Encoding::Zstd, Encoding::Lz4] {
+            let (compressed, applied) = policy(encoding).compress(data.clone()).unwrap();
+
+            assert_eq!(applied, Some(encoding));
+            assert!(compressed.len() < data.len());
+
+            let encoding = encoding.to_string();
+            let decompressed = decompress(&compressed, Some(&encoding)).unwrap();
+            assert_eq!(decompressed.as_ref(), data.as_slice());
+        }
+    }
+
+    #[test]
+    fn skips_small_and_incompressible_payloads() {
+        let small = vec![0; 63];
+        assert_eq!(
+            policy(Encoding::Zstd).compress(small.clone()).unwrap(),
+            (small, None)
+        );
+
+        // Bytes from a xorshift generator do not compress
+        let mut x = 0x2545_f491_u32;
+        let noise: Vec<u8> = (0..256)
+            .map(|_| {
+                x ^= x << 13;
+                x ^= x >> 17;
+                x ^= x << 5;
+                x.to_le_bytes()[0]
+            })
+            .collect();
+
+        for encoding in [Encoding::Zstd, Encoding::Lz4] {
+            assert_eq!(
+                policy(encoding).compress(noise.clone()).unwrap(),
+                (noise.clone(), None)
+            );
+        }
+    }
+
+    #[test]
+    fn passes_through_identity() {
+        for encoding in [None, Some(""), Some("identity")] {
+            assert!(matches!(
+                decompress(b"abc", encoding).unwrap(),
+                Cow::Borrowed(b"abc")
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_encoding() {
+        assert!(matches!(
+            decompress(b"abc", Some("gzip")),
+            Err(Error::UnsupportedEncoding(e)) if e == "gzip"
+        ));
+    }
+
+    #[test]
+    fn caps_decompressed_size() {
+        let data = vec![0; MAX_DECOMPRESSED_LEN + 1];
+        let (compressed, _) = policy(Encoding::Zstd).compress(data).unwrap();
+
+        assert!(matches!(
+            decompress(&compressed, Some("zstd")),
+            Err(Error::DecompressedTooLarge)
+        ));
+
+        // The size prepended to an LZ4 payload is checked before allocating
+        let mut lz4 = u32::try_from(MAX_DECOMPRESSED_LEN + 1)
+            .unwrap()
+            .to_le_bytes()
+            .to_vec();
+        lz4.extend_from_slice(&[0; 16]);
+
+        assert!(matches!(
+            decompress(&lz4, Some("lz4")),
+            Err(Error::DecompressedTooLarge)
+        ));
+    }
+}
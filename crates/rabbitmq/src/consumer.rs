@@ -1,27 +1,77 @@
 //! An AMQP consumer configured from a [`QueueType`]
 
-use std::marker::PhantomData;
+use std::{borrow::Cow, marker::PhantomData, ops::RangeInclusive, sync::Arc};
 
 use futures_util::StreamExt;
-use lapin::{acker::Acker, Connection};
+use lapin::{
+    message::Delivery,
+    options::{BasicAckOptions, BasicPublishOptions, BasicRejectOptions, ConfirmSelectOptions},
+    publisher_confirm::Confirmation,
+    types::{AMQPValue, FieldTable, ShortString},
+    BasicProperties, Channel, Connection,
+};
 
-use crate::{serialize::deserialize, QueueType, Result};
+use crate::{
+    broker::{Acknowledge, Subscriber},
+    compression::decompress,
+    dlq::{Reason, REASON_HEADER},
+    queue_type::{DLX_FAILED_KEY, DLX_TRIAGE_KEY},
+    serialize::{deserialize, DEFAULT_VERSIONS},
+    Error, QueueType, Result,
+};
+
+/// Header containing the chain of errors a message failed with, outermost
+/// first
+pub const ERROR_CHAIN_HEADER: &str = "x-error-chain";
+/// Header containing the innermost error a message failed with
+pub const ROOT_CAUSE_HEADER: &str = "x-error-root-cause";
+/// Header containing the name of the processor a message failed in
+pub const PROCESSOR_HEADER: &str = "x-processor";
+/// Header containing the ID of the worker a message failed in
+pub const WORKER_HEADER: &str = "x-worker-id";
+/// Header counting the times a message has failed to process in its live
+/// queue.  Consumers dead-letter failed messages themselves, so the broker
+/// does not record these failures in `x-death`.
+pub const FAILURES_HEADER: &str = "x-failures";
+
+/// The most errors from a failure's error chain to record in its headers
+const MAX_ERROR_CHAIN: usize = 16;
+/// The longest error message to record in a failure's headers.  Longer
+/// messages are truncated so the headers fit in a single AMQP frame.
+const MAX_ERROR_LEN: usize = 1024;
+
+#[derive(Debug)]
+struct Names {
+    tag: String,
+    queue: String,
+    dl_exchange: String,
+}
 
 /// A consumer consisting of a configured AMQP consumer and queue config
 #[derive(Debug)]
 pub struct Consumer<Q> {
-    // chan: Channel,
+    chan: Channel,
     consumer: lapin::Consumer,
-    // ty: Q,
+    names: Arc<Names>,
+    versions: RangeInclusive<u16>,
     _p: PhantomData<Q>,
 }
 
 impl<Q> Clone for Consumer<Q> {
     fn clone(&self) -> Self {
-        let Self { consumer, .. } = self;
+        let Self {
+            chan,
+            consumer,
+            names,
+            versions,
+            ..
+        } = self;
 
         Self {
+            chan: chan.clone(),
             consumer: consumer.clone(),
+            names: Arc::clone(names),
+            versions: versions.clone(),
             ..*self
         }
     }
@@ -38,30 +88,347 @@ where
     /// successfully.
     pub async fn new(conn: &Connection, ty: Q, tag: impl AsRef<str>) -> Result<Self> {
         let chan = conn.create_channel().await?;
+        let info = ty.info();
+
+        let consumer = info.init_consumer(&chan, tag.as_ref()).await?;
 
-        let consumer = ty.info().init_consumer(&chan, tag).await?;
+        // Failed messages are republished to the dead-letter exchange, and
+        // must not be acknowledged until the broker has accepted the copy
+        chan.confirm_select(ConfirmSelectOptions::default()).await?;
 
         Ok(Self {
-            // chan,
+            chan,
             consumer,
-            // ty,
+            names: Arc::new(Names {
+                tag: tag.as_ref().into(),
+                queue: info.queue().into(),
+                dl_exchange: info.dl_exchange(),
+            }),
+            versions: DEFAULT_VERSIONS,
             _p: PhantomData::default(),
         })
     }
 
+    /// Set the range of message schema versions this consumer accepts
+    ///
+    /// Consumers default to [`DEFAULT_VERSIONS`].  Widening the range allows
+    /// a consumer to read messages from producers on either side of a schema
+    /// migration.  Messages outside the range fail to deserialize.
+    #[must_use]
+    pub fn accept_versions(self, versions: RangeInclusive<u16>) -> Self {
+        Self { versions, ..self }
+    }
+
+    /// The tag this consumer was created with
+    #[must_use]
+    pub fn tag(&self) -> &str {
+        &self.names.tag
+    }
+
     /// Receive a single message from this consumer
     ///
+    /// Messages whose payload cannot be decoded will never process
+    /// successfully, so they are [rejected](Acker::reject) and skipped rather
+    /// than returned.
+    ///
     /// # Errors
     /// This function fails if the delivery cannot be successfully performed or
-    /// the payload cannot be deserialized.
+    /// an undecodable message cannot be rejected.
     pub async fn read(&mut self) -> Result<Option<(Q::Message, Acker)>> {
-        let delivery = match self.consumer.next().await {
-            Some(d) => d?,
-            None => return Ok(None),
-        };
+        loop {
+            let delivery = match self.consumer.next().await {
+                Some(d) => d?,
+                None => return Ok(None),
+            };
+
+            let data = decode(
+                &delivery.data,
+                delivery
+                    .properties
+                    .content_encoding()
+                    .as_ref()
+                    .map(ShortString::as_str),
+                &self.versions,
+            );
+
+            let acker = Acker {
+                chan: self.chan.clone(),
+                names: Arc::clone(&self.names),
+                delivery,
+            };
+
+            match data {
+                Ok(data) => break Ok(Some((data, acker))),
+                Err(e) => {
+                    log::warn!(
+                        "Rejecting undecodable message from {:?}: {:?}",
+                        self.names.queue,
+                        e
+                    );
+
+                    acker.reject(&e).await?;
+                },
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<Q: QueueType + Send + Sync + 'static> Subscriber for Consumer<Q>
+where
+    Q::Message: Send + for<'a> serde::Deserialize<'a>,
+{
+    type Acker = Acker;
+    type Message = Q::Message;
+
+    fn tag(&self) -> &str {
+        Consumer::tag(self)
+    }
+
+    async fn read(&mut self) -> Result<Option<(Q::Message, Acker)>> {
+        Consumer::read(self).await
+    }
+}
+
+/// Decompress and deserialize a message payload
+pub(crate) fn decode<M: for<'a> serde::Deserialize<'a>>(
+    data: &[u8],
+    content_encoding: Option<&str>,
+    versions: &RangeInclusive<u16>,
+) -> Result<M> {
+    deserialize(&decompress(data, content_encoding)?, versions)
+}
+
+/// Diagnostic information recorded on a message that failed to process
+#[derive(Debug, Clone, Copy)]
+pub struct Failure<'a> {
+    /// The name of the processor the message failed in
+    pub processor: &'a str,
+    /// The ID of the worker the message failed in
+    pub worker: usize,
+    /// The chain of errors the message failed with, outermost first
+    pub errors: &'a [String],
+}
+
+/// A handle for acknowledging or failing a message received from a
+/// [`Consumer`]
+#[derive(Debug)]
+pub struct Acker {
+    chan: Channel,
+    names: Arc<Names>,
+    delivery: Delivery,
+}
+
+impl Acker {
+    /// Acknowledge the message as successfully processed
+    ///
+    /// # Errors
+    /// This function fails if the acknowledgement cannot be sent.
+    pub async fn ack(self) -> Result<()> {
+        self.delivery
+            .acker
+            .ack(BasicAckOptions::default())
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Dead-letter the message, recording why it failed in its headers
+    ///
+    /// The broker cannot attach headers to a rejected message, so the message
+    /// is instead republished to the dead-letter exchange with its failure
+    /// count incremented, and acknowledged once the broker confirms the copy.
+    /// If the copy is not confirmed, the message is rejected instead, and the
+    /// broker dead-letters it without the failure headers.
+    ///
+    /// # Errors
+    /// This function fails if the message can neither be republished nor
+    /// rejected.
+    pub async fn fail(self, failure: Failure<'_>) -> Result<()> {
+        let Self {
+            chan,
+            names,
+            delivery,
+        } = self;
+        let Delivery {
+            properties,
+            data,
+            acker,
+            ..
+        } = delivery;
+        let Failure {
+            processor,
+            worker,
+            errors,
+        } = failure;
+
+        let mut headers = properties.headers().clone().unwrap_or_default();
+
+        insert_errors(&mut headers, errors);
+        headers.insert(
+            PROCESSOR_HEADER.into(),
+            AMQPValue::LongString(processor.into()),
+        );
+        headers.insert(
+            WORKER_HEADER.into(),
+            AMQPValue::LongLongInt(worker.try_into().unwrap_or(i64::MAX)),
+        );
+
+        let failures = headers
+            .inner()
+            .get(FAILURES_HEADER)
+            .and_then(AMQPValue::as_long_long_int)
+            .unwrap_or(0);
+        headers.insert(
+            FAILURES_HEADER.into(),
+            AMQPValue::LongLongInt(failures.saturating_add(1)),
+        );
+
+        dead_letter(
+            &chan,
+            &names,
+            acker,
+            DLX_TRIAGE_KEY,
+            &data,
+            properties.with_headers(headers),
+        )
+        .await
+    }
+
+    /// Move the message straight to the terminal dead-letter queue without
+    /// retrying it, recording the error in its headers along with the
+    /// [`Reason`] it was rejected
+    ///
+    /// This is used for messages which can never be processed, such as those
+    /// whose payload cannot be decoded.  As with [`fail`](Self::fail), the
+    /// message is acknowledged once the broker confirms the copy, or rejected
+    /// if it does not.
+    ///
+    /// # Errors
+    /// This function fails if the message can neither be republished nor
+    /// rejected.
+    pub async fn reject(self, error: &Error) -> Result<()> {
+        let Self {
+            chan,
+            names,
+            delivery,
+        } = self;
+        let Delivery {
+            properties,
+            data,
+            acker,
+            ..
+        } = delivery;
+
+        let errors: Vec<_> =
+            std::iter::successors(Some(error as &dyn std::error::Error), |e| e.source())
+                .map(ToString::to_string)
+                .collect();
+
+        let mut headers = properties.headers().clone().unwrap_or_default();
+
+        insert_errors(&mut headers, &errors);
+        headers.insert(
+            REASON_HEADER.into(),
+            AMQPValue::LongString(Reason::for_decode_error(error).to_string().into()),
+        );
+
+        dead_letter(
+            &chan,
+            &names,
+            acker,
+            DLX_FAILED_KEY,
+            &data,
+            properties.with_headers(headers).with_delivery_mode(2),
+        )
+        .await
+    }
+}
+
+/// Record the chain of errors a message failed with in its headers
+fn insert_errors(headers: &mut FieldTable, errors: &[String]) {
+    headers.insert(
+        ERROR_CHAIN_HEADER.into(),
+        AMQPValue::FieldArray(
+            errors
+                .iter()
+                .take(MAX_ERROR_CHAIN)
+                .map(|e| AMQPValue::LongString(truncate(e).as_ref().into()))
+                .collect::<Vec<_>>()
+                .into(),
+        ),
+    );
+
+    if let Some(root) = errors.last() {
+        headers.insert(
+            ROOT_CAUSE_HEADER.into(),
+            AMQPValue::LongString(truncate(root).as_ref().into()),
+        );
+    }
+}
 
-        let data = deserialize(std::io::Cursor::new(delivery.data))?;
+/// Republish a message to the dead-letter exchange, acknowledging it once the
+/// broker confirms the copy or rejecting it if the broker does not
+async fn dead_letter(
+    chan: &Channel,
+    names: &Names,
+    acker: lapin::acker::Acker,
+    routing_key: &str,
+    data: &[u8],
+    properties: BasicProperties,
+) -> Result<()> {
+    let confirmed = async {
+        chan.basic_publish(
+            &names.dl_exchange,
+            routing_key,
+            BasicPublishOptions::default(),
+            data,
+            properties,
+        )
+        .await?
+        .await
+    }
+    .await;
+
+    match confirmed {
+        Ok(Confirmation::Ack(_) | Confirmation::NotRequested) => {
+            acker.ack(BasicAckOptions::default()).await?;
+        },
+        res => {
+            log::warn!(
+                "Failed to republish message from {:?} to the dead-letter exchange, rejecting \
+                 it instead: {:?}",
+                names.queue,
+                res
+            );
 
-        Ok(Some((data, delivery.acker)))
+            acker.reject(BasicRejectOptions { requeue: false }).await?;
+        },
     }
+
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl Acknowledge for Acker {
+    async fn ack(self) -> Result<()> {
+        Acker::ack(self).await
+    }
+
+    async fn fail(self, failure: Failure<'_>) -> Result<()> {
+        Acker::fail(self, failure).await
+    }
+}
+
+/// Truncate an error message to at most [`MAX_ERROR_LEN`] bytes
+fn truncate(s: &str) -> Cow<str> {
+    if s.len() <= MAX_ERROR_LEN {
+        return Cow::Borrowed(s);
+    }
+
+    let mut end = MAX_ERROR_LEN;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    Cow::Owned(format!("{}...", &s[..end]))
 }
@@ -5,20 +5,23 @@ use std::{collections::BTreeMap, time::Duration};
 use futures_util::StreamExt;
 use lapin::{
     message::Delivery,
-    options::{BasicAckOptions, BasicPublishOptions},
+    options::{BasicAckOptions, BasicPublishOptions, ConfirmSelectOptions},
+    publisher_confirm::Confirmation,
     types::{AMQPValue, FieldTable, ShortString},
-    Connection,
+    BasicProperties, Channel, Connection,
 };
 use log::{debug, error, trace, warn};
 
 use crate::{
-    queue_type::{DLX_DEAD_KEY, DLX_LIVE_KEY},
-    QueueType, Result,
+    consumer::FAILURES_HEADER,
+    dlq::{Reason, REASON_HEADER},
+    queue_type::{DLX_DEAD_KEY, DLX_FAILED_KEY, DLX_LIVE_KEY},
+    Error, QueueType, Result,
 };
 
 enum RetryAction {
-    DropUnexpected,
-    DropMaxlen,
+    FailUnexpected,
+    FailMaxlen,
     Retry(u64),
     RedeliverLive,
 }
@@ -37,7 +40,12 @@ fn parse_x_death(
     live_queue: &str,
     dl_queue: &str,
 ) -> RetryAction {
-    let mut queue_deaths = 0;
+    // Failures recorded by the consumer count as deaths in the live queue
+    let mut queue_deaths = headers
+        .and_then(|h| h.get(FAILURES_HEADER))
+        .and_then(AMQPValue::as_long_long_int)
+        .and_then(|c| u64::try_from(c).ok())
+        .unwrap_or(0);
     let mut dlq_deaths = 0;
 
     for (reason, queue, count) in headers
@@ -71,8 +79,8 @@ fn parse_x_death(
                 queue_deaths += count;
             },
             DeathReason::Expired if queue == dl_queue => dlq_deaths += count,
-            DeathReason::Maxlen => return RetryAction::DropMaxlen,
-            _ => return RetryAction::DropUnexpected,
+            DeathReason::Maxlen => return RetryAction::FailMaxlen,
+            _ => return RetryAction::FailUnexpected,
         }
     }
 
@@ -83,10 +91,40 @@ fn parse_x_death(
     }
 }
 
+/// Publish a message and wait for the broker to confirm it
+async fn publish(
+    chan: &Channel,
+    exchange: &str,
+    routing_key: &str,
+    data: &[u8],
+    properties: BasicProperties,
+) -> Result<()> {
+    match chan
+        .basic_publish(
+            exchange,
+            routing_key,
+            BasicPublishOptions::default(),
+            data,
+            properties,
+        )
+        .await?
+        .await?
+    {
+        Confirmation::Ack(_) | Confirmation::NotRequested => Ok(()),
+        Confirmation::Nack(_) => Err(Error::Nacked),
+    }
+}
+
 async fn try_consume<Q: QueueType>(conn: &Connection, ty: &Q) -> Result<()> {
     let chan = conn.create_channel().await?;
     let (mut consumer, inf) = ty.info().init_dl_consumer(&chan).await?;
 
+    // Dead letters are only acknowledged once the broker has accepted the
+    // copy republished in their place.  If a copy is rejected, for instance
+    // because the failed queue is full, the consumer restarts and the dead
+    // letter is redelivered.
+    chan.confirm_select(ConfirmSelectOptions::default()).await?;
+
     while let Some(del) = consumer.next().await {
         let del = del?;
 
@@ -99,15 +137,18 @@ async fn try_consume<Q: QueueType>(conn: &Connection, ty: &Q) -> Result<()> {
 
         let headers = properties.headers().as_ref().map(FieldTable::inner);
 
-        match parse_x_death(headers, inf.queue(), inf.dl_queue()) {
-            RetryAction::DropUnexpected => {
-                warn!("Dropping unexpected message in triage queue");
+        let fail_reason = match parse_x_death(headers, inf.queue(), inf.dl_queue()) {
+            RetryAction::FailUnexpected => {
+                warn!("Failing unexpected message in triage queue");
+                Some(Reason::Unexpected)
             },
-            RetryAction::DropMaxlen => {
-                debug!("Dropping message due to maxlen death");
+            RetryAction::FailMaxlen => {
+                debug!("Failing message due to maxlen death");
+                Some(Reason::Maxlen)
             },
             RetryAction::Retry(0) => {
                 error!("Got 0-death message in triage queue");
+                Some(Reason::Unexpected)
             },
             RetryAction::Retry(r) if r < inf.max_tries() => {
                 if let Some(delay) = inf.get_delay(r) {
@@ -115,36 +156,59 @@ async fn try_consume<Q: QueueType>(conn: &Connection, ty: &Q) -> Result<()> {
 
                     properties = properties.with_expiration(delay.to_string().into());
 
-                    chan.basic_publish(
+                    publish(
+                        &chan,
                         inf.exchange(),
                         DLX_DEAD_KEY,
-                        BasicPublishOptions::default(),
                         &data,
-                        properties,
+                        properties.clone(),
                     )
                     .await?;
+
+                    None
                 } else {
-                    warn!("Discarding DL delivery due to delay arithmetic error");
+                    warn!("Failing DL delivery due to delay arithmetic error");
+                    Some(Reason::Unexpected)
                 }
             },
             RetryAction::Retry(r) => {
-                // We hit the retry limit.  Bye-bye!
-                trace!("Dropping dead letter after {} deaths", r);
+                // We hit the retry limit.  Off to the failed queue it goes.
+                trace!("Failing dead letter after {} deaths", r);
+                Some(Reason::MaxTries)
             },
             RetryAction::RedeliverLive => {
                 trace!("Redelivering dead letter");
 
-                chan.basic_publish(
+                publish(
+                    &chan,
                     inf.exchange(),
                     DLX_LIVE_KEY,
-                    BasicPublishOptions::default(),
                     &data,
-                    properties,
+                    properties.clone(),
                 )
                 .await?;
+
+                None
             },
         };
 
+        if let Some(reason) = fail_reason {
+            let mut headers = properties.headers().clone().unwrap_or_default();
+            headers.insert(
+                REASON_HEADER.into(),
+                AMQPValue::LongString(reason.to_string().into()),
+            );
+
+            publish(
+                &chan,
+                inf.exchange(),
+                DLX_FAILED_KEY,
+                &data,
+                properties.with_headers(headers).with_delivery_mode(2),
+            )
+            .await?;
+        }
+
         acker.ack(BasicAckOptions::default()).await?;
     }
 
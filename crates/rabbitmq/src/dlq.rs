@@ -0,0 +1,307 @@
+//! Inspection and replay of the terminal dead-letter queue for a
+//! [`QueueType`]
+//!
+//! Messages end up in the terminal dead-letter queue once the
+//! [dead-letter consumer](crate::dl_consumer) gives up on them, either because
+//! they exhausted their retries or because they died in an unexpected way.
+//! They remain there until an operator republishes or discards them.
+
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use lapin::{
+    acker::Acker,
+    options::{BasicAckOptions, BasicGetOptions, BasicPublishOptions},
+    types::{AMQPValue, FieldTable, ShortString},
+    BasicProperties, Channel, Connection,
+};
+
+use crate::{
+    consumer::{
+        decode, ERROR_CHAIN_HEADER, FAILURES_HEADER, PROCESSOR_HEADER, ROOT_CAUSE_HEADER,
+        WORKER_HEADER,
+    },
+    queue_type::DLX_LIVE_KEY,
+    serialize::DEFAULT_VERSIONS,
+    Error, QueueType, Result,
+};
+
+/// The header recording why a message was moved to the terminal dead-letter
+/// queue
+pub const REASON_HEADER: &str = "x-dlq-reason";
+
+/// Headers recording a message's deaths and failures, which are cleared when
+/// it is republished
+const FAILURE_HEADERS: [&str; 7] = [
+    "x-death",
+    REASON_HEADER,
+    FAILURES_HEADER,
+    ERROR_CHAIN_HEADER,
+    ROOT_CAUSE_HEADER,
+    PROCESSOR_HEADER,
+    WORKER_HEADER,
+];
+
+/// The reason a message was moved to the terminal dead-letter queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Reason {
+    /// The message failed to process too many times
+    MaxTries,
+    /// The message was dropped from a queue that overflowed
+    Maxlen,
+    /// The message died in an unexpected way
+    Unexpected,
+    /// The message's payload could not be decoded by its consumer
+    Undecodable,
+    /// The message's payload was compressed with an encoding its consumer
+    /// does not support, or could not be decompressed
+    BadEncoding,
+}
+
+impl Reason {
+    /// The reason a message was rejected because its payload could not be
+    /// decoded
+    #[must_use]
+    pub fn for_decode_error(error: &Error) -> Self {
+        match error {
+            Error::UnsupportedEncoding(_)
+            | Error::DecompressedTooLarge
+            | Error::Lz4(_)
+            | Error::Io(_) => Self::BadEncoding,
+            _ => Self::Undecodable,
+        }
+    }
+}
+
+/// A message read from the terminal dead-letter queue
+///
+/// The message is held by the queue until it is passed to
+/// [`DeadLetterQueue::republish`] or [`DeadLetterQueue::discard`], or the
+/// queue handle is closed.
+#[derive(Debug)]
+pub struct DeadLetter<Q> {
+    properties: BasicProperties,
+    data: Vec<u8>,
+    acker: Acker,
+    _p: PhantomData<fn() -> Q>,
+}
+
+impl<Q> DeadLetter<Q> {
+    /// The raw payload of this message
+    #[must_use]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// The AMQP headers of this message, if any
+    #[must_use]
+    pub fn headers(&self) -> Option<&BTreeMap<ShortString, AMQPValue>> {
+        self.properties.headers().as_ref().map(FieldTable::inner)
+    }
+
+    /// The reason this message was moved to the terminal dead-letter queue,
+    /// if known
+    #[must_use]
+    pub fn reason(&self) -> Option<Reason> {
+        self.header_str(REASON_HEADER)?.parse().ok()
+    }
+
+    /// The innermost error this message last failed to process with, if
+    /// known
+    #[must_use]
+    pub fn root_cause(&self) -> Option<String> {
+        self.header_str(ROOT_CAUSE_HEADER)
+    }
+
+    /// The name of the processor this message last failed in, if known
+    #[must_use]
+    pub fn processor(&self) -> Option<String> {
+        self.header_str(PROCESSOR_HEADER)
+    }
+
+    fn header_str(&self, key: &str) -> Option<String> {
+        self.headers()?
+            .get(key)?
+            .as_long_string()
+            .map(ToString::to_string)
+    }
+
+    /// The total number of times this message died, across all queues,
+    /// including failures recorded by the consumer
+    #[must_use]
+    pub fn deaths(&self) -> u64 {
+        let deaths: u64 = self
+            .headers()
+            .and_then(|h| h.get("x-death"))
+            .and_then(AMQPValue::as_array)
+            .into_iter()
+            .flat_map(|a| a.as_slice())
+            .filter_map(|d| {
+                d.as_field_table()?
+                    .inner()
+                    .get("count")?
+                    .as_long_long_int()?
+                    .try_into()
+                    .ok()
+            })
+            .sum();
+
+        let failures: u64 = self
+            .headers()
+            .and_then(|h| h.get(FAILURES_HEADER))
+            .and_then(AMQPValue::as_long_long_int)
+            .and_then(|c| c.try_into().ok())
+            .unwrap_or(0);
+
+        deaths + failures
+    }
+}
+
+impl<Q: QueueType> DeadLetter<Q>
+where
+    Q::Message: for<'a> serde::Deserialize<'a>,
+{
+    /// Decompress and decode the payload of this message, accepting the
+    /// [default schema versions](DEFAULT_VERSIONS)
+    ///
+    /// # Errors
+    /// This function fails if the payload cannot be decompressed or
+    /// deserialized.
+    pub fn decode(&self) -> Result<Q::Message> {
+        decode(
+            &self.data,
+            self.properties
+                .content_encoding()
+                .as_ref()
+                .map(ShortString::as_str),
+            &DEFAULT_VERSIONS,
+        )
+    }
+}
+
+/// A handle to the terminal dead-letter queue of a [`QueueType`]
+#[derive(Debug)]
+pub struct DeadLetterQueue<Q> {
+    chan: Channel,
+    exchange: String,
+    queue: String,
+    _p: PhantomData<fn() -> Q>,
+}
+
+impl<Q: QueueType> DeadLetterQueue<Q> {
+    /// Open the terminal dead-letter queue for a [`QueueType`], declaring it
+    /// if necessary
+    ///
+    /// # Errors
+    /// This function fails if the queue cannot be declared.
+    pub async fn new(conn: &Connection, ty: &Q) -> Result<Self> {
+        let chan = conn.create_channel().await?;
+        let (exchange, queue) = ty.info().init_dl_inspector(&chan).await?;
+
+        Ok(Self {
+            chan,
+            exchange,
+            queue,
+            _p: PhantomData::default(),
+        })
+    }
+
+    /// The name of the terminal dead-letter queue
+    #[must_use]
+    pub fn queue(&self) -> &str {
+        &self.queue
+    }
+
+    /// Read the next message from the queue without removing it, returning
+    /// `None` once no messages remain
+    ///
+    /// # Errors
+    /// This function fails if the message cannot be retrieved.
+    pub async fn get(&self) -> Result<Option<DeadLetter<Q>>> {
+        let msg = match self
+            .chan
+            .basic_get(&self.queue, BasicGetOptions { no_ack: false })
+            .await?
+        {
+            Some(m) => m,
+            None => return Ok(None),
+        };
+
+        let lapin::message::Delivery {
+            properties,
+            data,
+            acker,
+            ..
+        } = msg.delivery;
+
+        Ok(Some(DeadLetter {
+            properties,
+            data,
+            acker,
+            _p: PhantomData::default(),
+        }))
+    }
+
+    /// Redeliver a message to the live queue it originally died in and
+    /// remove it from the terminal dead-letter queue
+    ///
+    /// The message's death history and failure headers are cleared, so it
+    /// receives a full set of retries and a later failure is not reported
+    /// with stale errors.
+    ///
+    /// # Errors
+    /// This function fails if the message cannot be published or
+    /// acknowledged.
+    pub async fn republish(&self, msg: DeadLetter<Q>) -> Result<()> {
+        let DeadLetter {
+            mut properties,
+            data,
+            acker,
+            ..
+        } = msg;
+
+        if let Some(headers) = properties.headers() {
+            let headers: BTreeMap<_, _> = headers
+                .inner()
+                .iter()
+                .filter(|(k, _)| !FAILURE_HEADERS.contains(&k.as_str()))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+
+            properties = properties.with_headers(headers.into());
+        }
+
+        self.chan
+            .basic_publish(
+                &self.exchange,
+                DLX_LIVE_KEY,
+                BasicPublishOptions::default(),
+                &data,
+                properties,
+            )
+            .await?;
+
+        acker.ack(BasicAckOptions::default()).await?;
+
+        Ok(())
+    }
+
+    /// Permanently remove a message from the terminal dead-letter queue
+    ///
+    /// # Errors
+    /// This function fails if the message cannot be acknowledged.
+    pub async fn discard(&self, msg: DeadLetter<Q>) -> Result<()> {
+        msg.acker.ack(BasicAckOptions::default()).await?;
+
+        Ok(())
+    }
+
+    /// Close this handle, returning any messages that were read but not
+    /// republished or discarded to the queue
+    ///
+    /// # Errors
+    /// This function fails if the underlying channel cannot be closed cleanly.
+    pub async fn close(self) -> Result<()> {
+        self.chan.close(200, "Bye").await.map_err(Into::into)
+    }
+}
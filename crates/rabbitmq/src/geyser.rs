@@ -1,15 +1,16 @@
 //! Queue configuration for Solana Geyser plugins intended to communicate
 //! with `holaplex-indexer`.
 
-use std::time::Duration;
+use std::{borrow::Cow, time::Duration};
 
 use serde::{Deserialize, Serialize};
+use solana_program::pubkey;
 pub use solana_program::pubkey::Pubkey;
 
 use crate::{
     queue_type::{Binding, QueueProps, RetryProps},
     suffix::Suffix,
-    Result,
+    Error, Priority, Result,
 };
 
 /// Message data for an account update
@@ -35,6 +36,93 @@ pub struct AccountUpdate {
     pub is_startup: bool,
 }
 
+impl AccountUpdate {
+    /// Get the priority of this update, and of any work resulting from it.
+    /// Updates triggered by a validator startup are backfill work.
+    #[must_use]
+    pub fn priority(&self) -> Priority {
+        if self.is_startup {
+            Priority::Backfill
+        } else {
+            Priority::Live
+        }
+    }
+}
+
+/// An instruction executed as part of a transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionInstruction {
+    /// The program this instruction was executed with
+    pub program: Pubkey,
+    /// The binary instruction opcode
+    pub data: Vec<u8>,
+    /// The account inputs to this instruction
+    pub accounts: Vec<Pubkey>,
+    /// The position of this instruction (or its invoking instruction) within
+    /// the transaction
+    pub index: u8,
+    /// For inner instructions, the position of this instruction within the
+    /// list of instructions invoked by its top-level instruction
+    pub inner_index: Option<u32>,
+}
+
+/// A token account balance recorded before or after a transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenBalance {
+    /// The token account holding the balance
+    pub account: Pubkey,
+    /// The mint of the token
+    pub mint: Pubkey,
+    /// The owner of the token account, if known
+    pub owner: Option<Pubkey>,
+    /// The raw token amount
+    pub amount: u64,
+    /// The number of decimals used by the mint
+    pub decimals: u8,
+}
+
+/// Message data for a processed transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transaction {
+    /// The first signature of the transaction, encoded as Base58
+    pub signature: String,
+    /// The slot in which this transaction was processed
+    pub slot: u64,
+    /// The account paying the fee for this transaction
+    pub fee_payer: Pubkey,
+    /// All accounts referenced by this transaction
+    pub account_keys: Vec<Pubkey>,
+    /// All instructions executed by this transaction, including inner
+    /// instructions, in execution order
+    pub instructions: Vec<TransactionInstruction>,
+    /// The fee charged for this transaction, in lamports
+    pub fee: u64,
+    /// Lamport balances of each account before the transaction
+    pub pre_balances: Vec<u64>,
+    /// Lamport balances of each account after the transaction
+    pub post_balances: Vec<u64>,
+    /// Token balances before the transaction
+    pub pre_token_balances: Vec<TokenBalance>,
+    /// Token balances after the transaction
+    pub post_token_balances: Vec<TokenBalance>,
+    /// Log messages emitted by the transaction
+    pub log_messages: Vec<String>,
+    /// A description of the transaction error, if the transaction failed
+    pub error: Option<String>,
+}
+
+/// Commitment level of a slot, as reported by the validator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SlotStatus {
+    /// The slot has been processed by the validator but may still be
+    /// abandoned along with its fork
+    Processed,
+    /// The slot has been voted on by a supermajority of the cluster
+    Confirmed,
+    /// The slot has reached maximum vote lockout and is final
+    Rooted,
+}
+
 /// A message transmitted by a Geyser plugin
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Message {
@@ -49,6 +137,172 @@ pub enum Message {
         /// The account inputs to this instruction
         accounts: Vec<Pubkey>,
     },
+    /// Indicates a transaction including one or more selected programs was
+    /// processed
+    Transaction(Box<Transaction>),
+    /// Indicates the commitment status of a slot has changed
+    SlotStatus {
+        /// The slot being updated
+        slot: u64,
+        /// The parent of this slot, if known
+        parent: Option<u64>,
+        /// The new commitment status of the slot
+        status: SlotStatus,
+    },
+}
+
+/// Well-known programs and the names used for them in topic routing keys
+static PROGRAM_NAMES: &[(Pubkey, &str)] = &[
+    (
+        pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"),
+        "metadata",
+    ),
+    (
+        pubkey!("vau1zxA2LbssAUEF7Gpw91zMM1LvXrvpzJtmZ58rPsn"),
+        "vault",
+    ),
+    (
+        pubkey!("auctxRXPeJoc4817jDhf4HbjnhEcr1cCXenosMhK5R8"),
+        "auction",
+    ),
+    (
+        pubkey!("p1exdMJcjVao65QdewkaZRUnU6VPSXhus9n2GzWfh98"),
+        "metaplex",
+    ),
+    (
+        pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
+        "token",
+    ),
+    (
+        pubkey!("hausS13jsjafwWwGqZTUQRmWyvyxn9EQpqMwV1PBBmk"),
+        "auction-house",
+    ),
+    (
+        pubkey!("cndy3Z4yapfJBmL3ShUp5exZKqR3z33thTzeNMm2gRZ"),
+        "candy-machine",
+    ),
+    (
+        pubkey!("grphAFGNvCjLKHeEmPNa91eGJChcUhrdaYYharcZCTQ"),
+        "graph",
+    ),
+    (
+        pubkey!("namesLPneVptA9Z5rqUDD9tMTWEJwofgaYwp8cawRkX"),
+        "name-service",
+    ),
+    (
+        pubkey!("mgr99QFMYByTqGPWmNqunV7vBLmWWXdSrHUfV8Jf3JM"),
+        "cardinal-token-manager",
+    ),
+    (
+        pubkey!("tmeEDp1RgoDtZFtx6qod3HkbQmv9LMe36uqKVvsLTDE"),
+        "cardinal-time-invalidator",
+    ),
+    (
+        pubkey!("useZ65tbyvWpdYCLDJaegGK34Lnsi8S3jZdwx8122qp"),
+        "cardinal-use-invalidator",
+    ),
+    (
+        pubkey!("pcaBwhJ1YHp7UDA7HASpQsRUmUNwzgYaLQto2kSj1fR"),
+        "cardinal-paid-claim-approver",
+    ),
+    (
+        pubkey!("nameXpT2PwZ2iA6DTNYTotTmiMYusBCYqwBLN2QgF4w"),
+        "namespaces",
+    ),
+    (
+        pubkey!("GokivDYuQXPZCWRkwMhdH2h91KpDQXBEmpgBgs55bnpH"),
+        "goki-smart-wallet",
+    ),
+    (
+        pubkey!("LocktDzaV1W2Bm9DeZeiyz4J9zs4fRqNiYqQyracRXw"),
+        "tribeca-locked-voter",
+    ),
+    (
+        pubkey!("Govz1VyoyLD5BL6CSCxUJLVLsQHRwjfFj1prNsdNg5Jw"),
+        "tribeca-govern",
+    ),
+    (
+        pubkey!("TBondmkCYxaPCKG4CHYfVTcwQ8on31xnJrPzk8F8WsS"),
+        "token-bonding",
+    ),
+];
+
+fn known_program_name(program: &Pubkey) -> Option<&'static str> {
+    PROGRAM_NAMES
+        .iter()
+        .find_map(|(k, n)| (k == program).then(|| *n))
+}
+
+/// Resolve a program name given by a consumer to the name used for it in
+/// topic routing keys, accepting either a well-known name or an address
+///
+/// # Errors
+/// This function fails if `name` is neither a well-known program name nor a
+/// valid Base58-encoded address.
+pub fn parse_program_name(name: &str) -> Result<Cow<'static, str>> {
+    if let Some((_, n)) = PROGRAM_NAMES.iter().find(|(_, n)| *n == name) {
+        return Ok(Cow::Borrowed(n));
+    }
+
+    name.parse()
+        .map(|p| program_name(&p))
+        .map_err(|_| Error::UnknownProgram(name.into()))
+}
+
+/// Get the name identifying a program in topic routing keys.  Well-known
+/// programs use a short kebab-case name, and all other programs use their
+/// Base58-encoded address.
+#[must_use]
+pub fn program_name(program: &Pubkey) -> Cow<'static, str> {
+    known_program_name(program).map_or_else(|| program.to_string().into(), Cow::Borrowed)
+}
+
+impl Message {
+    /// Get the routing keys for this message on a topic exchange.  Keys have
+    /// the form `account.<program>`, `instruction.<program>`,
+    /// `transaction.<program>` or `slot`, where `<program>` is a name
+    /// returned by [`program_name`].  Transactions have one key for each
+    /// distinct program they invoke, or `transaction.other` if there are none.
+    #[must_use]
+    pub fn routing_keys(&self) -> Vec<Cow<'static, str>> {
+        match self {
+            Self::AccountUpdate(u) => vec![format!("account.{}", program_name(&u.owner)).into()],
+            Self::InstructionNotify { program, .. } => {
+                vec![format!("instruction.{}", program_name(program)).into()]
+            },
+            Self::Transaction(tx) => {
+                let mut programs: Vec<&Pubkey> = vec![];
+
+                for ins in &tx.instructions {
+                    if !programs.contains(&&ins.program) {
+                        programs.push(&ins.program);
+                    }
+                }
+
+                if programs.is_empty() {
+                    return vec!["transaction.other".into()];
+                }
+
+                programs
+                    .into_iter()
+                    .map(|p| format!("transaction.{}", program_name(p)).into())
+                    .collect()
+            },
+            Self::SlotStatus { .. } => vec!["slot".into()],
+        }
+    }
+
+    /// Get the delivery priority for this message.  Only account updates
+    /// triggered by a validator startup are delivered as backfill work.
+    #[must_use]
+    pub fn priority(&self) -> Priority {
+        match self {
+            Self::AccountUpdate(u) => u.priority(),
+            Self::InstructionNotify { .. } | Self::Transaction(_) | Self::SlotStatus { .. } => {
+                Priority::Live
+            },
+        }
+    }
 }
 
 /// AMQP configuration for Geyser plugins
@@ -100,18 +354,94 @@ impl QueueType {
     /// # Errors
     /// This function fails if the given queue suffix is invalid.
     pub fn new(network: Network, startup_type: StartupType, suffix: &Suffix) -> Result<Self> {
-        let exchange = format!("{}{}.accounts", network, match startup_type {
+        let exchange = Self::exchange_name(network, startup_type);
+        let queue = suffix.format(format!("{}.indexer", exchange))?;
+
+        Ok(Self::with_binding(
+            exchange,
+            queue,
+            Binding::Fanout,
+            startup_type,
+            suffix,
+        ))
+    }
+
+    /// Construct a new queue configuration using a topic exchange, routing
+    /// messages by the program that produced them.  Consumers receive only
+    /// messages for the given program names (see [`parse_program_name`]), or
+    /// all messages if `programs` is empty.
+    ///
+    /// # Errors
+    /// This function fails if any program name is not recognized or the given
+    /// queue suffix is invalid.
+    pub fn new_topic(
+        network: Network,
+        startup_type: StartupType,
+        suffix: &Suffix,
+        programs: &[String],
+    ) -> Result<Self> {
+        let programs = programs
+            .iter()
+            .map(|p| parse_program_name(p))
+            .collect::<Result<Vec<_>>>()?;
+
+        let exchange = format!("{}.topic", Self::exchange_name(network, startup_type));
+        let queue = if programs.is_empty() {
+            suffix.format(format!("{}.indexer", exchange))?
+        } else {
+            suffix.format(format!("{}.{}.indexer", exchange, programs.join(".")))?
+        };
+
+        let keys = programs
+            .iter()
+            .flat_map(|p| {
+                ["account", "instruction", "transaction"]
+                    .into_iter()
+                    .map(move |k| format!("{}.{}", k, p))
+            })
+            .chain((!programs.is_empty()).then(|| "slot".into()))
+            .collect();
+
+        Ok(Self::with_binding(
+            exchange,
+            queue,
+            Binding::Topic(keys),
+            startup_type,
+            suffix,
+        ))
+    }
+
+    /// Declare the queue with priority lanes, delivering live messages ahead
+    /// of backfill messages.  This is only useful for
+    /// [`StartupType::All`], the only exchange carrying both kinds of
+    /// update.  The Geyser plugin and every consumer of the queue must agree
+    /// on this setting.
+    #[must_use]
+    pub fn with_priority_lanes(mut self, priority_lanes: bool) -> Self {
+        self.props.priority_lanes = priority_lanes;
+        self
+    }
+
+    fn exchange_name(network: Network, startup_type: StartupType) -> String {
+        format!("{}{}.accounts", network, match startup_type {
             StartupType::Normal => "",
             StartupType::Startup => ".startup",
             StartupType::All => ".startup-all",
-        });
-        let queue = suffix.format(format!("{}.indexer", exchange))?;
+        })
+    }
 
-        Ok(Self {
+    fn with_binding(
+        exchange: String,
+        queue: String,
+        binding: Binding,
+        startup_type: StartupType,
+        suffix: &Suffix,
+    ) -> Self {
+        Self {
             props: QueueProps {
                 exchange,
                 queue,
-                binding: Binding::Fanout,
+                binding,
                 prefetch: 4096,
                 max_len_bytes: if suffix.is_debug() || matches!(startup_type, StartupType::Normal) {
                     100 * 1024 * 1024 // 100 MiB
@@ -119,13 +449,14 @@ impl QueueType {
                     8 * 1024 * 1024 * 1024 // 8 GiB
                 },
                 auto_delete: suffix.is_debug(),
+                priority_lanes: false,
                 retry: Some(RetryProps {
                     max_tries: 3,
                     delay_hint: Duration::from_millis(500),
                     max_delay: Duration::from_secs(10 * 60),
                 }),
             },
-        })
+        }
     }
 }
 
@@ -136,6 +467,16 @@ impl crate::QueueType for QueueType {
     fn info(&self) -> crate::queue_type::QueueInfo {
         (&self.props).into()
     }
+
+    #[inline]
+    fn routing_keys(&self, msg: &Message) -> Vec<Cow<'static, str>> {
+        msg.routing_keys()
+    }
+
+    #[inline]
+    fn priority(&self, msg: &Message) -> Priority {
+        msg.priority()
+    }
 }
 
 /// The type of a Geyser producer
@@ -144,3 +485,128 @@ pub type Producer = crate::producer::Producer<QueueType>;
 /// The type of a Geyser consumer
 #[cfg(feature = "consumer")]
 pub type Consumer = crate::consumer::Consumer<QueueType>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METADATA: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+    const TOKEN: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+    fn transaction(programs: &[Pubkey]) -> Message {
+        Message::Transaction(Box::new(Transaction {
+            signature: String::new(),
+            slot: 0,
+            fee_payer: Pubkey::default(),
+            account_keys: vec![],
+            instructions: programs
+                .iter()
+                .map(|&program| TransactionInstruction {
+                    program,
+                    data: vec![],
+                    accounts: vec![],
+                    index: 0,
+                    inner_index: None,
+                })
+                .collect(),
+            fee: 0,
+            pre_balances: vec![],
+            post_balances: vec![],
+            pre_token_balances: vec![],
+            post_token_balances: vec![],
+            log_messages: vec![],
+            error: None,
+        }))
+    }
+
+    fn account_update(is_startup: bool) -> Message {
+        Message::AccountUpdate(AccountUpdate {
+            key: Pubkey::default(),
+            lamports: 0,
+            owner: TOKEN,
+            executable: false,
+            rent_epoch: 0,
+            data: vec![],
+            write_version: 0,
+            slot: 0,
+            is_startup,
+        })
+    }
+
+    #[test]
+    fn startup_updates_are_backfill() {
+        let ty = QueueType::new(Network::Mainnet, StartupType::All, &Suffix::Production)
+            .unwrap()
+            .with_priority_lanes(true);
+
+        assert_eq!(account_update(true).priority(), Priority::Backfill);
+        assert_eq!(account_update(false).priority(), Priority::Live);
+        assert_eq!(
+            crate::QueueType::priority(&ty, &account_update(true)),
+            Priority::Backfill
+        );
+
+        // Only account updates replay existing state
+        assert_eq!(transaction(&[TOKEN]).priority(), Priority::Live);
+        assert_eq!(
+            Message::SlotStatus {
+                slot: 0,
+                parent: None,
+                status: SlotStatus::Processed,
+            }
+            .priority(),
+            Priority::Live
+        );
+    }
+
+    #[test]
+    fn transaction_keys() {
+        let other = Pubkey::new_from_array([7; 32]);
+
+        assert_eq!(
+            transaction(&[TOKEN, METADATA, TOKEN, other]).routing_keys(),
+            vec![
+                "transaction.token".to_owned(),
+                "transaction.metadata".to_owned(),
+                format!("transaction.{}", other),
+            ]
+        );
+        assert_eq!(transaction(&[]).routing_keys(), vec!["transaction.other"]);
+    }
+
+    #[test]
+    fn program_names() {
+        assert_eq!(parse_program_name("metadata").unwrap(), "metadata");
+        assert_eq!(parse_program_name(&TOKEN.to_string()).unwrap(), "token");
+        assert!(matches!(
+            parse_program_name("not-a-program"),
+            Err(Error::UnknownProgram(_))
+        ));
+    }
+
+    #[test]
+    fn topic_bindings() {
+        let suffix = Suffix::Debug("test".into());
+
+        let all =
+            QueueType::new_topic(Network::Mainnet, StartupType::Normal, &suffix, &[]).unwrap();
+        assert!(matches!(all.props.binding, Binding::Topic(ref k) if k.is_empty()));
+
+        let some = QueueType::new_topic(Network::Mainnet, StartupType::Normal, &suffix, &[
+            "metadata".into(),
+        ])
+        .unwrap();
+        assert_eq!(
+            some.props.queue,
+            "mainnet.accounts.topic.metadata.indexer.debug.test"
+        );
+        assert!(
+            matches!(some.props.binding, Binding::Topic(ref k) if k == &[
+                "account.metadata",
+                "instruction.metadata",
+                "transaction.metadata",
+                "slot",
+            ])
+        );
+    }
+}
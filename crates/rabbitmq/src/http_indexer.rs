@@ -9,7 +9,7 @@ use solana_program::pubkey::Pubkey;
 use crate::{
     queue_type::{Binding, QueueProps, RetryProps},
     suffix::Suffix,
-    Result,
+    Priority, Result,
 };
 
 /// AMQP configuration for HTTP indexers
@@ -47,6 +47,9 @@ pub trait Entity: std::fmt::Debug + Serialize + for<'a> Deserialize<'a> {
 
     /// A name to use when declaring queues and exchanges
     const ID: Self::Id;
+
+    /// The delivery priority of this entity
+    fn priority(&self) -> Priority;
 }
 
 /// Fetch the off-chain JSON for a metadata account
@@ -61,12 +64,19 @@ pub struct MetadataJson {
     /// A tuple of (slot, write_version)
     #[serde(default)]
     pub slot_info: (u64, u64),
+    /// The priority of the account update this request resulted from
+    #[serde(default)]
+    pub priority: Priority,
 }
 
 impl Entity for MetadataJson {
     type Id = EntityId;
 
     const ID: EntityId = EntityId::MetadataJson;
+
+    fn priority(&self) -> Priority {
+        self.priority
+    }
 }
 
 /// Fetch the off-chain JSON config for a storefront
@@ -76,12 +86,19 @@ pub struct StoreConfig {
     pub config_address: Pubkey,
     /// The URI to retrieve the file from
     pub uri: String,
+    /// The priority of the account update this request resulted from
+    #[serde(default)]
+    pub priority: Priority,
 }
 
 impl Entity for StoreConfig {
     type Id = EntityId;
 
     const ID: EntityId = EntityId::StoreConfig;
+
+    fn priority(&self) -> Priority {
+        self.priority
+    }
 }
 
 impl<E: Entity> QueueType<E> {
@@ -102,6 +119,7 @@ impl<E: Entity> QueueType<E> {
                 prefetch: 1024,
                 max_len_bytes: 100 * 1024 * 1024, // 100 MiB
                 auto_delete: suffix.is_debug(),
+                priority_lanes: false,
                 retry: Some(RetryProps {
                     max_tries: 8,
                     delay_hint: Duration::from_secs(2),
@@ -111,6 +129,15 @@ impl<E: Entity> QueueType<E> {
             _p: PhantomData::default(),
         })
     }
+
+    /// Declare the queue with priority lanes, delivering live messages ahead
+    /// of backfill messages.  Every producer and consumer of the queue must
+    /// agree on this setting.
+    #[must_use]
+    pub fn with_priority_lanes(mut self, priority_lanes: bool) -> Self {
+        self.props.priority_lanes = priority_lanes;
+        self
+    }
 }
 
 impl<E: Entity> crate::QueueType for QueueType<E> {
@@ -120,6 +147,11 @@ impl<E: Entity> crate::QueueType for QueueType<E> {
     fn info(&self) -> crate::queue_type::QueueInfo {
         (&self.props).into()
     }
+
+    #[inline]
+    fn priority(&self, msg: &E) -> Priority {
+        msg.priority()
+    }
 }
 
 /// The type of an HTTP indexer producer
@@ -0,0 +1,610 @@
+//! A Kafka (or Redpanda) backend configured from a [`QueueType`]
+//!
+//! Each AMQP exchange maps to a Kafka topic of the same name, and each AMQP
+//! queue to a consumer group.  Messages are keyed by their routing key, so
+//! every message for a given program lands in the same partition and is
+//! consumed in order.  Consumers commit offsets as messages are acknowledged,
+//! but never past a message still being processed, so history can be
+//! replayed by starting a new consumer group or resetting an existing one's
+//! offsets.
+//!
+//! Kafka has no dead-letter exchanges, so messages that fail to process are
+//! not retried; they are published immediately to a dead-letter topic named
+//! `failed.dlq.<queue>`, along with the headers recorded by the AMQP
+//! consumer.  Messages that cannot be decoded are published there too, with
+//! the reason recorded in the `x-dlq-reason` header.  Partitions are read strictly in order, so message
+//! [priorities](crate::Priority) are ignored.
+
+use std::time::Duration;
+#[cfg(feature = "consumer")]
+use std::{
+    collections::{BTreeSet, HashMap},
+    ops::RangeInclusive,
+    sync::{Arc, Mutex},
+};
+
+pub use rdkafka::ClientConfig;
+#[cfg(feature = "consumer")]
+use rdkafka::{
+    consumer::{CommitMode, Consumer as _, StreamConsumer},
+    message::{BorrowedMessage, Headers},
+    Message as _, Offset, TopicPartitionList,
+};
+#[cfg(any(feature = "producer", feature = "consumer"))]
+use rdkafka::{
+    message::OwnedHeaders,
+    producer::{FutureProducer, FutureRecord},
+};
+
+#[cfg(feature = "producer")]
+use crate::{broker::Publisher, compression::Policy, producer::encode, serialize::Format};
+#[cfg(feature = "consumer")]
+use crate::{
+    broker::{Acknowledge, Subscriber},
+    consumer::{
+        decode, Failure, ERROR_CHAIN_HEADER, PROCESSOR_HEADER, ROOT_CAUSE_HEADER, WORKER_HEADER,
+    },
+    dlq::{Reason, REASON_HEADER},
+    serialize::DEFAULT_VERSIONS,
+    Error,
+};
+use crate::{QueueType, Result};
+
+/// The header used in place of the AMQP `content-encoding` property
+pub const CONTENT_ENCODING_HEADER: &str = "content-encoding";
+
+/// The header listing any routing keys for a message besides its record key,
+/// separated by spaces
+pub const ROUTING_KEYS_HEADER: &str = "x-routing-keys";
+
+/// How long to wait for a message to be queued for delivery to the broker
+const SEND_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[cfg(any(feature = "producer", feature = "consumer"))]
+async fn send(
+    producer: &FutureProducer,
+    topic: &str,
+    key: Option<&str>,
+    payload: &[u8],
+    headers: OwnedHeaders,
+) -> Result<()> {
+    let mut record = FutureRecord::to(topic).payload(payload).headers(headers);
+
+    if let Some(key) = key {
+        record = record.key(key);
+    }
+
+    producer
+        .send(record, SEND_TIMEOUT)
+        .await
+        .map(|_| ())
+        .map_err(|(e, _)| e.into())
+}
+
+/// A Kafka producer publishing the messages of a [`QueueType`] to the topic
+/// named after its exchange
+#[cfg(feature = "producer")]
+#[allow(missing_debug_implementations)]
+pub struct Producer<Q> {
+    producer: FutureProducer,
+    ty: Q,
+    format: Format,
+    compression: Option<Policy>,
+}
+
+#[cfg(feature = "producer")]
+impl<Q: QueueType> Producer<Q>
+where
+    Q::Message: serde::Serialize,
+{
+    /// Construct a new producer from a [`QueueType`] and a Kafka client
+    /// configuration, which must at least set `bootstrap.servers`
+    ///
+    /// # Errors
+    /// This function fails if the producer cannot be created.
+    pub fn new(config: &ClientConfig, ty: Q) -> Result<Self> {
+        Ok(Self {
+            producer: config.create()?,
+            ty,
+            format: Format::default(),
+            compression: None,
+        })
+    }
+
+    /// Set the wire format this producer publishes messages in
+    #[must_use]
+    pub fn with_format(self, format: Format) -> Self {
+        Self { format, ..self }
+    }
+
+    /// Set the policy for compressing the payloads of published messages, or
+    /// disable compression with `None`
+    #[must_use]
+    pub fn with_compression(self, compression: Option<Policy>) -> Self {
+        Self {
+            compression,
+            ..self
+        }
+    }
+
+    /// Write a single message to this producer
+    ///
+    /// # Errors
+    /// This function fails if the value cannot be serialized or the broker
+    /// does not accept the message.
+    pub async fn write(&self, val: impl std::borrow::Borrow<Q::Message>) -> Result<()> {
+        let val = val.borrow();
+        let (payload, encoding) = encode(val, self.format, self.compression)?;
+        let keys = self.ty.routing_keys(val);
+
+        let mut headers = OwnedHeaders::new();
+
+        if let Some(encoding) = encoding {
+            headers = headers.add(CONTENT_ENCODING_HEADER, <&str>::from(encoding));
+        }
+
+        if keys.len() > 1 {
+            headers = headers.add(ROUTING_KEYS_HEADER, keys[1..].join(" ").as_str());
+        }
+
+        send(
+            &self.producer,
+            self.ty.info().exchange(),
+            keys.first().map(AsRef::as_ref).filter(|k| !k.is_empty()),
+            &payload,
+            headers,
+        )
+        .await
+    }
+
+    /// Write a batch of messages to this producer, sending every message
+    /// before waiting on any of them to be accepted
+    ///
+    /// # Errors
+    /// This function fails if any value cannot be serialized or the broker
+    /// does not accept any message.  Other messages in the batch may have
+    /// already been published.
+    pub async fn write_batch<I: IntoIterator>(&self, vals: I) -> Result<()>
+    where
+        I::Item: std::borrow::Borrow<Q::Message>,
+    {
+        futures_util::future::try_join_all(vals.into_iter().map(|v| self.write(v))).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "producer")]
+#[async_trait::async_trait]
+impl<Q: QueueType + Send + Sync> Publisher for Producer<Q>
+where
+    Q::Message: serde::Serialize + Sync,
+{
+    type Message = Q::Message;
+
+    async fn write_batch(&self, msgs: &[Q::Message]) -> Result<()> {
+        Producer::write_batch(self, msgs).await
+    }
+}
+
+/// The offsets of a partition that have been received but not yet settled
+#[cfg(feature = "consumer")]
+#[derive(Debug, Default)]
+struct Pending {
+    in_flight: BTreeSet<i64>,
+    next: i64,
+    committed: i64,
+}
+
+#[cfg(feature = "consumer")]
+impl Pending {
+    fn receive(&mut self, offset: i64) {
+        self.in_flight.insert(offset);
+    }
+
+    /// Settle an offset, returning the offset to commit if every message
+    /// before it has also been settled
+    fn settle(&mut self, offset: i64) -> Option<i64> {
+        self.in_flight.remove(&offset);
+        self.next = self.next.max(offset + 1);
+
+        // Messages settle out of order when processed concurrently, so only
+        // commit up to the lowest offset still in flight
+        let commit = self.in_flight.iter().next().copied().unwrap_or(self.next);
+
+        if commit <= self.committed {
+            return None;
+        }
+
+        self.committed = commit;
+        Some(commit)
+    }
+}
+
+#[cfg(feature = "consumer")]
+struct Shared {
+    consumer: StreamConsumer,
+    dl_producer: FutureProducer,
+    tag: String,
+    dl_topic: String,
+    pending: Mutex<HashMap<(String, i32), Pending>>,
+}
+
+#[cfg(feature = "consumer")]
+impl Shared {
+    fn receive(&self, topic: &str, partition: i32, offset: i64) {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+
+        pending
+            .entry((topic.into(), partition))
+            .or_default()
+            .receive(offset);
+    }
+
+    /// Settle an offset, returning the offset to commit for its partition if
+    /// every message before it has also been settled
+    fn settle(&self, topic: &str, partition: i32, offset: i64) -> Option<i64> {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+
+        pending.get_mut(&(topic.into(), partition))?.settle(offset)
+    }
+}
+
+/// A Kafka consumer reading the messages of a [`QueueType`] as the consumer
+/// group named after its queue
+#[cfg(feature = "consumer")]
+#[allow(missing_debug_implementations)]
+pub struct Consumer<Q> {
+    shared: Arc<Shared>,
+    ty: Q,
+    versions: RangeInclusive<u16>,
+}
+
+#[cfg(feature = "consumer")]
+impl<Q: Clone> Clone for Consumer<Q> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+            ty: self.ty.clone(),
+            versions: self.versions.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "consumer")]
+impl<Q: QueueType> Consumer<Q>
+where
+    Q::Message: for<'a> serde::Deserialize<'a>,
+{
+    /// Construct a new consumer from a [`QueueType`] and a Kafka client
+    /// configuration, which must at least set `bootstrap.servers`
+    ///
+    /// The consumer group defaults to the name of the queue, and new groups
+    /// start from the earliest retained message.  Both can be overridden by
+    /// setting `group.id` and `auto.offset.reset` in `config`.
+    ///
+    /// # Errors
+    /// This function fails if the consumer cannot be created or subscribed.
+    pub fn new(config: &ClientConfig, ty: Q, tag: impl Into<String>) -> Result<Self> {
+        let info = ty.info();
+        let mut consumer_config = config.clone();
+
+        if consumer_config.get("group.id").is_none() {
+            consumer_config.set("group.id", info.queue());
+        }
+
+        if consumer_config.get("auto.offset.reset").is_none() {
+            consumer_config.set("auto.offset.reset", "earliest");
+        }
+
+        let consumer: StreamConsumer = consumer_config
+            .set("enable.auto.commit", "false")
+            .create()?;
+        consumer.subscribe(&[info.exchange()])?;
+
+        Ok(Self {
+            shared: Arc::new(Shared {
+                consumer,
+                dl_producer: config.create()?,
+                tag: tag.into(),
+                dl_topic: format!("failed.dlq.{}", info.queue()),
+                pending: Mutex::default(),
+            }),
+            ty,
+            versions: DEFAULT_VERSIONS,
+        })
+    }
+
+    /// Set the range of message schema versions this consumer accepts
+    #[must_use]
+    pub fn accept_versions(self, versions: RangeInclusive<u16>) -> Self {
+        Self { versions, ..self }
+    }
+
+    /// The tag this consumer was created with
+    #[must_use]
+    pub fn tag(&self) -> &str {
+        &self.shared.tag
+    }
+
+    /// Receive a single message from this consumer
+    ///
+    /// Messages whose key is not bound by the queue type are skipped and
+    /// committed.  Messages whose payload cannot be decoded are
+    /// [rejected](Acker::reject) and skipped.
+    ///
+    /// # Errors
+    /// This function fails if the message cannot be received or an
+    /// undecodable message cannot be rejected.
+    pub async fn read(&mut self) -> Result<Option<(Q::Message, Acker)>> {
+        loop {
+            let msg = self.shared.consumer.recv().await?;
+            let acker = Acker::new(Arc::clone(&self.shared), &msg);
+
+            let key = msg.key().and_then(|k| std::str::from_utf8(k).ok());
+            let keys = std::iter::once(key.unwrap_or("")).chain(
+                header(&msg, ROUTING_KEYS_HEADER)
+                    .into_iter()
+                    .flat_map(str::split_whitespace),
+            );
+
+            if !self.ty.info().accepts_keys(keys) {
+                acker.commit()?;
+                continue;
+            }
+
+            // Every received offset must be settled, or commits for its
+            // partition stop advancing
+            match decode(
+                msg.payload().unwrap_or_default(),
+                header(&msg, CONTENT_ENCODING_HEADER),
+                &self.versions,
+            ) {
+                Ok(data) => break Ok(Some((data, acker))),
+                Err(e) => {
+                    log::warn!(
+                        "Rejecting undecodable message from {:?}: {:?}",
+                        self.shared.dl_topic,
+                        e
+                    );
+
+                    acker.reject(&e).await?;
+                },
+            }
+        }
+    }
+}
+
+#[cfg(feature = "consumer")]
+#[async_trait::async_trait]
+impl<Q: QueueType + Clone + Send + Sync + 'static> Subscriber for Consumer<Q>
+where
+    Q::Message: Send + for<'a> serde::Deserialize<'a>,
+{
+    type Acker = Acker;
+    type Message = Q::Message;
+
+    fn tag(&self) -> &str {
+        Consumer::tag(self)
+    }
+
+    async fn read(&mut self) -> Result<Option<(Q::Message, Acker)>> {
+        Consumer::read(self).await
+    }
+}
+
+#[cfg(feature = "consumer")]
+fn header<'a>(msg: &'a BorrowedMessage, key: &str) -> Option<&'a str> {
+    let headers = msg.headers()?;
+
+    (0..headers.count())
+        .filter_map(|i| headers.get(i))
+        .find(|(k, _)| *k == key)
+        .and_then(|(_, v)| std::str::from_utf8(v).ok())
+}
+
+/// A handle for committing or dead-lettering a message received from a Kafka
+/// [`Consumer`]
+#[cfg(feature = "consumer")]
+#[allow(missing_debug_implementations)]
+pub struct Acker {
+    shared: Arc<Shared>,
+    topic: String,
+    partition: i32,
+    offset: i64,
+    key: Option<Vec<u8>>,
+    payload: Vec<u8>,
+    headers: Vec<(String, Vec<u8>)>,
+}
+
+#[cfg(feature = "consumer")]
+impl Acker {
+    fn new(shared: Arc<Shared>, msg: &BorrowedMessage) -> Self {
+        shared.receive(msg.topic(), msg.partition(), msg.offset());
+
+        let headers = msg
+            .headers()
+            .into_iter()
+            .flat_map(|h| (0..h.count()).filter_map(move |i| h.get(i)))
+            .map(|(k, v)| (k.to_owned(), v.to_owned()))
+            .collect();
+
+        Self {
+            shared,
+            topic: msg.topic().into(),
+            partition: msg.partition(),
+            offset: msg.offset(),
+            key: msg.key().map(ToOwned::to_owned),
+            payload: msg.payload().map(ToOwned::to_owned).unwrap_or_default(),
+            headers,
+        }
+    }
+
+    fn commit(&self) -> Result<()> {
+        let offset = match self.shared.settle(&self.topic, self.partition, self.offset) {
+            Some(o) => o,
+            None => return Ok(()),
+        };
+
+        let mut tpl = TopicPartitionList::new();
+        tpl.add_partition_offset(&self.topic, self.partition, Offset::Offset(offset))?;
+
+        self.shared
+            .consumer
+            .commit(&tpl, CommitMode::Async)
+            .map_err(Into::into)
+    }
+
+    /// Commit the message as successfully processed
+    ///
+    /// # Errors
+    /// This function fails if the offset cannot be committed.
+    pub fn ack(self) -> Result<()> {
+        self.commit()
+    }
+
+    /// Publish the message to the dead-letter topic, recording why it failed
+    /// in its headers, and then commit it
+    ///
+    /// # Errors
+    /// This function fails if the message cannot be published or the offset
+    /// cannot be committed.
+    pub async fn fail(self, failure: Failure<'_>) -> Result<()> {
+        let Failure {
+            processor,
+            worker,
+            errors,
+        } = failure;
+
+        let headers = self
+            .failure_headers(errors)
+            .add(PROCESSOR_HEADER, processor)
+            .add(WORKER_HEADER, &worker.to_string());
+
+        self.dead_letter(headers).await
+    }
+
+    /// Publish a message which can never be processed, such as one whose
+    /// payload cannot be decoded, to the dead-letter topic with the error and
+    /// the [`Reason`] it was rejected, and then commit it
+    ///
+    /// # Errors
+    /// This function fails if the message cannot be published or the offset
+    /// cannot be committed.
+    pub async fn reject(self, error: &Error) -> Result<()> {
+        let errors: Vec<_> =
+            std::iter::successors(Some(error as &dyn std::error::Error), |e| e.source())
+                .map(ToString::to_string)
+                .collect();
+
+        let headers = self
+            .failure_headers(&errors)
+            .add(REASON_HEADER, &Reason::for_decode_error(error).to_string());
+
+        self.dead_letter(headers).await
+    }
+
+    /// Copy the message's headers, replacing any recorded failure with the
+    /// given chain of errors
+    fn failure_headers(&self, errors: &[String]) -> OwnedHeaders {
+        let mut headers = self
+            .headers
+            .iter()
+            .filter(|(k, _)| {
+                ![
+                    ERROR_CHAIN_HEADER,
+                    ROOT_CAUSE_HEADER,
+                    PROCESSOR_HEADER,
+                    WORKER_HEADER,
+                    REASON_HEADER,
+                ]
+                .contains(&k.as_str())
+            })
+            .fold(OwnedHeaders::new(), |h, (k, v)| h.add(k, v));
+
+        for error in errors {
+            headers = headers.add(ERROR_CHAIN_HEADER, error);
+        }
+
+        if let Some(root) = errors.last() {
+            headers = headers.add(ROOT_CAUSE_HEADER, root);
+        }
+
+        headers
+    }
+
+    async fn dead_letter(self, headers: OwnedHeaders) -> Result<()> {
+        send(
+            &self.shared.dl_producer,
+            &self.shared.dl_topic,
+            self.key
+                .as_deref()
+                .and_then(|k| std::str::from_utf8(k).ok()),
+            &self.payload,
+            headers,
+        )
+        .await?;
+
+        self.commit()
+    }
+}
+
+#[cfg(feature = "consumer")]
+#[async_trait::async_trait]
+impl Acknowledge for Acker {
+    async fn ack(self) -> Result<()> {
+        Acker::ack(self)
+    }
+
+    async fn fail(self, failure: Failure<'_>) -> Result<()> {
+        Acker::fail(self, failure).await
+    }
+}
+
+#[cfg(all(test, feature = "consumer"))]
+mod tests {
+    use super::*;
+
+    fn pending(offsets: impl IntoIterator<Item = i64>) -> Pending {
+        let mut pending = Pending::default();
+
+        for offset in offsets {
+            pending.receive(offset);
+        }
+
+        pending
+    }
+
+    #[test]
+    fn commits_in_order() {
+        let mut p = pending(0..3);
+
+        assert_eq!(p.settle(0), Some(1));
+        assert_eq!(p.settle(1), Some(2));
+        assert_eq!(p.settle(2), Some(3));
+    }
+
+    #[test]
+    fn waits_for_lowest_in_flight() {
+        let mut p = pending(10..14);
+
+        // Nothing is committed past offset 10 until it settles
+        assert_eq!(p.settle(12), Some(10));
+        assert_eq!(p.settle(11), None);
+        assert_eq!(p.settle(10), Some(13));
+
+        p.receive(14);
+        assert_eq!(p.settle(14), None);
+        assert_eq!(p.settle(13), Some(15));
+    }
+
+    #[test]
+    fn ignores_repeated_settles() {
+        let mut p = pending(0..2);
+
+        assert_eq!(p.settle(0), Some(1));
+        assert_eq!(p.settle(0), None);
+        assert_eq!(p.settle(1), Some(2));
+        assert_eq!(p.settle(1), None);
+    }
+}
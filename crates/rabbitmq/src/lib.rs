@@ -10,6 +10,8 @@
 #![warn(clippy::pedantic, clippy::cargo, missing_docs)]
 
 pub extern crate lapin;
+#[cfg(feature = "kafka")]
+pub extern crate rdkafka;
 
 /// Common traits and re-exports
 pub mod prelude {
@@ -30,29 +32,99 @@ pub enum Error {
     /// An error propagated from [`rmp_serde`] during decoding
     #[error("MsgPack decode error: {0:?}")]
     MsgDecode(#[from] rmp_serde::decode::Error),
+    /// An error propagated from [`ciborium`] during encoding
+    #[error("CBOR encode error: {0:?}")]
+    CborEncode(#[from] ciborium::ser::Error<std::io::Error>),
+    /// An error propagated from [`ciborium`] during decoding
+    #[error("CBOR decode error: {0:?}")]
+    CborDecode(#[from] ciborium::de::Error<std::io::Error>),
+    /// An I/O error occurred while compressing or decompressing a message
+    #[error("I/O error in message codec: {0:?}")]
+    Io(#[from] std::io::Error),
+    /// An error propagated from [`rdkafka`]
+    #[cfg(feature = "kafka")]
+    #[error("Kafka error: {0:?}")]
+    Kafka(#[from] rdkafka::error::KafkaError),
+    /// An in-memory channel was closed by its receivers
+    #[error("In-memory channel was closed")]
+    ChannelClosed,
+    /// A message envelope was malformed
+    #[error("Invalid message envelope: {0}")]
+    InvalidEnvelope(&'static str),
+    /// A message was encoded with a schema version this consumer does not
+    /// accept
+    #[error("Unsupported message schema version {0}")]
+    UnsupportedVersion(u16),
+    /// An error propagated from [`lz4_flex`] during decompression
+    #[error("LZ4 decompression error: {0:?}")]
+    Lz4(#[from] lz4_flex::block::DecompressError),
+    /// A compressed message would exceed
+    /// [`MAX_DECOMPRESSED_LEN`](compression::MAX_DECOMPRESSED_LEN) bytes once
+    /// decompressed
+    #[error("Decompressed message payload was too large")]
+    DecompressedTooLarge,
+    /// A message was compressed with an unrecognized content encoding
+    #[error("Unsupported content encoding {0:?}")]
+    UnsupportedEncoding(String),
+    /// A wire format string could not be parsed
+    #[error("Invalid wire format {0:?}")]
+    InvalidFormat(String),
     /// An error occurred related to a queue's configuration
     #[error("Invalid queue type for operation: {0}")]
     InvalidQueueType(&'static str),
+    /// A topic name did not refer to a well-known program or a valid program
+    /// address
+    #[error("Unknown program name {0:?}")]
+    UnknownProgram(String),
+    /// A published message was rejected by the broker
+    #[error("Message was negatively acknowledged by the broker")]
+    Nacked,
+}
+
+impl Error {
+    /// Returns true if this error was caused by the broker connection or
+    /// channel failing, rather than by a single message
+    ///
+    /// Other errors affect only the message being read or settled, and
+    /// reconnecting to the broker will not resolve them.
+    #[must_use]
+    pub fn is_connection_error(&self) -> bool {
+        match self {
+            Self::Lapin(_) | Self::ChannelClosed => true,
+            #[cfg(feature = "kafka")]
+            Self::Kafka(_) => true,
+            _ => false,
+        }
+    }
 }
 
 #[allow(dead_code)]
 pub(crate) type Result<T, E = Error> = std::result::Result<T, E>;
 
+#[cfg(any(feature = "producer", feature = "consumer"))]
+pub mod broker;
+pub mod compression;
 #[cfg(feature = "consumer")]
 pub mod consumer;
 #[cfg(feature = "consumer")]
 pub mod dl_consumer;
+#[cfg(feature = "consumer")]
+pub mod dlq;
 #[cfg(feature = "geyser")]
 pub mod geyser;
 #[cfg(feature = "http-indexer")]
 pub mod http_indexer;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+#[cfg(feature = "memory")]
+pub mod memory;
 #[cfg(feature = "producer")]
 pub mod producer;
 mod queue_type;
 #[cfg(feature = "search-indexer")]
 pub mod search_indexer;
-mod serialize;
+pub mod serialize;
 #[cfg(feature = "suffix")]
 pub mod suffix;
 
-pub use queue_type::QueueType;
+pub use queue_type::{Priority, QueueType};
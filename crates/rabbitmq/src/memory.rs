@@ -0,0 +1,172 @@
+//! An in-process transport for running indexers without a broker
+//!
+//! Messages are serialized in the [default wire format](Format::Bare) and
+//! passed through a bounded channel, so processors see exactly what they
+//! would receive from a broker.  The channel closes once every [`Producer`]
+//! is dropped, after which [`Consumer`]s hang up once they have drained it.
+//!
+//! There is no dead-letter handling; messages that fail to process are logged
+//! and discarded.
+
+use std::{fmt, marker::PhantomData, sync::Arc};
+
+use async_channel::{Receiver, Sender};
+
+use crate::{
+    broker::{Acknowledge, Failure, Publisher, Subscriber},
+    serialize::{deserialize, serialize, Format, DEFAULT_VERSIONS},
+    Result,
+};
+
+/// Create a new in-memory channel holding up to `capacity` messages, returning
+/// a producer and a consumer for it
+#[must_use]
+pub fn channel<M>(capacity: usize, tag: impl Into<String>) -> (Producer<M>, Consumer<M>) {
+    let (tx, rx) = async_channel::bounded(capacity);
+
+    (
+        Producer {
+            tx,
+            _p: PhantomData::default(),
+        },
+        Consumer {
+            rx,
+            tag: tag.into().into(),
+            _p: PhantomData::default(),
+        },
+    )
+}
+
+/// The sending half of an in-memory channel
+pub struct Producer<M> {
+    tx: Sender<Vec<u8>>,
+    _p: PhantomData<fn(M)>,
+}
+
+impl<M> fmt::Debug for Producer<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Producer").field("tx", &self.tx).finish()
+    }
+}
+
+impl<M> Clone for Producer<M> {
+    fn clone(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+            _p: PhantomData::default(),
+        }
+    }
+}
+
+impl<M: serde::Serialize> Producer<M> {
+    /// Write a single message to this producer, waiting for room in the
+    /// channel if it is full
+    ///
+    /// # Errors
+    /// This function fails if the value cannot be serialized or every
+    /// consumer has been dropped.
+    pub async fn write(&self, val: impl std::borrow::Borrow<M>) -> Result<()> {
+        let mut payload = Vec::new();
+        serialize(&mut payload, val.borrow(), Format::Bare)?;
+
+        self.tx
+            .send(payload)
+            .await
+            .map_err(|_| crate::Error::ChannelClosed)
+    }
+}
+
+#[async_trait::async_trait]
+impl<M: serde::Serialize + Sync> Publisher for Producer<M> {
+    type Message = M;
+
+    async fn write_batch(&self, msgs: &[M]) -> Result<()> {
+        for msg in msgs {
+            Producer::write(self, msg).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The receiving half of an in-memory channel
+pub struct Consumer<M> {
+    rx: Receiver<Vec<u8>>,
+    tag: Arc<str>,
+    _p: PhantomData<fn() -> M>,
+}
+
+impl<M> fmt::Debug for Consumer<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Consumer")
+            .field("rx", &self.rx)
+            .field("tag", &self.tag)
+            .finish()
+    }
+}
+
+impl<M> Clone for Consumer<M> {
+    fn clone(&self) -> Self {
+        Self {
+            rx: self.rx.clone(),
+            tag: Arc::clone(&self.tag),
+            _p: PhantomData::default(),
+        }
+    }
+}
+
+impl<M: for<'a> serde::Deserialize<'a>> Consumer<M> {
+    /// Receive a single message from this consumer, returning `None` once
+    /// every producer has been dropped and the channel is empty
+    ///
+    /// # Errors
+    /// This function fails if the payload cannot be deserialized.
+    pub async fn read(&mut self) -> Result<Option<(M, Acker)>> {
+        match self.rx.recv().await {
+            Ok(data) => Ok(Some((deserialize(&data, &DEFAULT_VERSIONS)?, Acker))),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<M: Send + for<'a> serde::Deserialize<'a> + 'static> Subscriber for Consumer<M> {
+    type Acker = Acker;
+    type Message = M;
+
+    fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    async fn read(&mut self) -> Result<Option<(M, Acker)>> {
+        Consumer::read(self).await
+    }
+}
+
+/// A handle for settling a message received from an in-memory [`Consumer`]
+#[derive(Debug, Clone, Copy)]
+pub struct Acker;
+
+#[async_trait::async_trait]
+impl Acknowledge for Acker {
+    async fn ack(self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn fail(self, failure: Failure<'_>) -> Result<()> {
+        let Failure {
+            processor,
+            worker,
+            errors,
+        } = failure;
+
+        log::error!(
+            "Discarding message that failed in {} (worker {}): {}",
+            processor,
+            worker,
+            errors.join(": ")
+        );
+
+        Ok(())
+    }
+}
@@ -1,14 +1,73 @@
 //! An AMQP producer configured from a [`QueueType`]
 
-use lapin::{Channel, Connection};
+use std::{
+    borrow::Cow,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
-use crate::{serialize::serialize, QueueType, Result};
+use lapin::{
+    options::ConfirmSelectOptions,
+    publisher_confirm::{Confirmation, PublisherConfirm},
+    Channel, Connection,
+};
+
+use crate::{
+    broker::Publisher,
+    compression::{Encoding, Policy},
+    serialize::{serialize, Format},
+    Error, Priority, QueueType, Result,
+};
+
+/// The default number of times to republish a message nacked by the broker
+pub const DEFAULT_CONFIRM_RETRIES: u32 = 3;
+
+/// Delivery guarantees requested by a [`Producer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confirms {
+    /// Consider messages delivered as soon as they are written to the channel
+    Disabled,
+    /// Put the channel in confirm mode and wait for the broker to acknowledge
+    /// each message, republishing negatively-acknowledged messages up to
+    /// `max_retries` times
+    Enabled {
+        /// The number of times to republish a nacked message before failing
+        max_retries: u32,
+    },
+}
+
+impl Confirms {
+    /// Enable publisher confirms, republishing nacked messages up to
+    /// [`DEFAULT_CONFIRM_RETRIES`] times
+    #[must_use]
+    pub fn enabled() -> Self {
+        Self::Enabled {
+            max_retries: DEFAULT_CONFIRM_RETRIES,
+        }
+    }
+}
 
 /// A producer consisting of a configured channel and additional queue config
 #[derive(Debug)]
 pub struct Producer<Q> {
     chan: Channel,
     ty: Q,
+    confirms: Confirms,
+    format: Format,
+    compression: Option<Policy>,
+    outstanding: AtomicUsize,
+}
+
+/// A message that has been published but possibly not yet confirmed by the
+/// broker
+#[derive(Debug)]
+#[must_use = "Deliveries should be awaited with confirmed()"]
+pub struct Delivery<'a, Q> {
+    producer: &'a Producer<Q>,
+    routing_keys: Vec<Cow<'static, str>>,
+    payload: Vec<u8>,
+    encoding: Option<Encoding>,
+    priority: Priority,
+    confirm: Option<PublisherConfirm>,
 }
 
 impl<Q: QueueType> Producer<Q>
@@ -21,26 +80,215 @@ where
     /// This function fails if the channel cannot be created and configured
     /// successfully.
     pub async fn new(conn: &Connection, ty: Q) -> Result<Self> {
+        Self::with_confirms(conn, ty, Confirms::Disabled).await
+    }
+
+    /// Construct a new producer from a [`QueueType`] with the given delivery
+    /// guarantees
+    ///
+    /// # Errors
+    /// This function fails if the channel cannot be created and configured
+    /// successfully.
+    pub async fn with_confirms(conn: &Connection, ty: Q, confirms: Confirms) -> Result<Self> {
         let chan = conn.create_channel().await?;
 
         ty.info().init_producer(&chan).await?;
 
-        Ok(Self { chan, ty })
+        if let Confirms::Enabled { .. } = confirms {
+            chan.confirm_select(ConfirmSelectOptions::default()).await?;
+        }
+
+        Ok(Self {
+            chan,
+            ty,
+            confirms,
+            format: Format::default(),
+            compression: None,
+            outstanding: AtomicUsize::new(0),
+        })
     }
 
-    /// Write a single message to this producer
+    /// Set the wire format this producer publishes messages in
+    ///
+    /// Producers default to [`Format::Bare`], which every consumer can read.
+    /// Other formats should only be enabled once all consumers of the queue
+    /// understand the message envelope.
+    #[must_use]
+    pub fn with_format(self, format: Format) -> Self {
+        Self { format, ..self }
+    }
+
+    /// Set the policy for compressing the payloads of published messages, or
+    /// disable compression with `None`
+    ///
+    /// Compressed messages are marked with the AMQP `content-encoding`
+    /// property, so this should only be enabled once all consumers of the
+    /// queue can decompress messages.
+    #[must_use]
+    pub fn with_compression(self, compression: Option<Policy>) -> Self {
+        Self {
+            compression,
+            ..self
+        }
+    }
+
+    /// The number of published messages whose delivery has not yet been
+    /// confirmed
+    #[must_use]
+    pub fn outstanding(&self) -> usize {
+        self.outstanding.load(Ordering::Relaxed)
+    }
+
+    /// Publish a single message without waiting for it to be confirmed
     ///
     /// # Errors
     /// This function fails if the value cannot be serialized or the serialized
     /// payload cannot be transmitted.
-    pub async fn write(&self, val: impl std::borrow::Borrow<Q::Message>) -> Result<()> {
+    pub async fn publish(
+        &self,
+        val: impl std::borrow::Borrow<Q::Message>,
+    ) -> Result<Delivery<'_, Q>> {
         let val = val.borrow();
+        let info = self.ty.info();
+
+        let (payload, encoding) = encode(val, self.format, self.compression)?;
+
+        let routing_keys = if info.is_topic() {
+            self.ty.routing_keys(val)
+        } else {
+            vec![]
+        };
+        let priority = self.ty.priority(val);
+
+        let confirm = info
+            .publish(&self.chan, &routing_keys, &payload, encoding, priority)
+            .await?;
+        self.outstanding.fetch_add(1, Ordering::Relaxed);
+
+        Ok(Delivery {
+            producer: self,
+            routing_keys,
+            payload,
+            encoding,
+            priority,
+            confirm: Some(confirm),
+        })
+    }
+
+    /// Write a single message to this producer
+    ///
+    /// # Errors
+    /// This function fails if the value cannot be serialized, the serialized
+    /// payload cannot be transmitted, or the broker rejects the message.
+    pub async fn write(&self, val: impl std::borrow::Borrow<Q::Message>) -> Result<()> {
+        self.publish(val).await?.confirmed().await
+    }
+
+    /// Write a batch of messages to this producer, publishing every message
+    /// before waiting on any of the resulting confirmations
+    ///
+    /// # Errors
+    /// This function fails if any value cannot be serialized, any serialized
+    /// payload cannot be transmitted, or the broker rejects any message.
+    /// Messages preceding the failed one may have already been published.
+    pub async fn write_batch<I: IntoIterator>(&self, vals: I) -> Result<()>
+    where
+        I::Item: std::borrow::Borrow<Q::Message>,
+    {
+        let mut deliveries = Vec::new();
 
-        let mut vec = Vec::new();
-        serialize(&mut vec, val)?;
+        for val in vals {
+            deliveries.push(self.publish(val).await?);
+        }
 
-        self.ty.info().publish(&self.chan, &vec).await?.await?;
+        for delivery in deliveries {
+            delivery.confirmed().await?;
+        }
 
         Ok(())
     }
 }
+
+#[async_trait::async_trait]
+impl<Q: QueueType + Send + Sync> Publisher for Producer<Q>
+where
+    Q::Message: serde::Serialize + Sync,
+{
+    type Message = Q::Message;
+
+    async fn write_batch(&self, msgs: &[Q::Message]) -> Result<()> {
+        Producer::write_batch(self, msgs).await
+    }
+}
+
+/// Serialize and optionally compress a message payload, returning the
+/// encoding that was applied, if any
+pub(crate) fn encode<M: serde::Serialize>(
+    val: &M,
+    format: Format,
+    compression: Option<Policy>,
+) -> Result<(Vec<u8>, Option<Encoding>)> {
+    let mut payload = Vec::new();
+    serialize(&mut payload, val, format)?;
+
+    match compression {
+        Some(c) => c.compress(payload),
+        None => Ok((payload, None)),
+    }
+}
+
+impl<'a, Q: QueueType> Delivery<'a, Q> {
+    /// Wait until this message has been durably accepted by the broker,
+    /// republishing it if it is negatively acknowledged.  If confirms are
+    /// disabled for the producer this resolves immediately.
+    ///
+    /// # Errors
+    /// This function fails if the message cannot be republished or is still
+    /// rejected after the producer's maximum number of retries.
+    pub async fn confirmed(mut self) -> Result<()> {
+        let max_retries = match self.producer.confirms {
+            Confirms::Disabled => 0,
+            Confirms::Enabled { max_retries } => max_retries,
+        };
+        let mut tries = 0;
+
+        loop {
+            let confirm = match self.confirm.take() {
+                Some(c) => c,
+                None => return Ok(()),
+            };
+
+            match confirm.await? {
+                Confirmation::Ack(_) | Confirmation::NotRequested => return Ok(()),
+                Confirmation::Nack(_) if tries < max_retries => {
+                    tries += 1;
+                    log::warn!(
+                        "Message was nacked by the broker, republishing (attempt {})",
+                        tries
+                    );
+
+                    self.confirm = Some(
+                        self.producer
+                            .ty
+                            .info()
+                            .publish(
+                                &self.producer.chan,
+                                &self.routing_keys,
+                                &self.payload,
+                                self.encoding,
+                                self.priority,
+                            )
+                            .await?,
+                    );
+                },
+                Confirmation::Nack(_) => return Err(Error::Nacked),
+            }
+        }
+    }
+}
+
+impl<'a, Q> Drop for Delivery<'a, Q> {
+    fn drop(&mut self) {
+        self.producer.outstanding.fetch_sub(1, Ordering::Relaxed);
+    }
+}
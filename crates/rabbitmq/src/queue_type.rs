@@ -1,7 +1,14 @@
-use std::time::Duration;
+use std::{borrow::Cow, time::Duration};
 
+use serde::{Deserialize, Serialize};
 #[cfg(feature = "producer")]
-use lapin::{options::BasicPublishOptions, publisher_confirm::PublisherConfirm, BasicProperties};
+use {
+    crate::compression::Encoding,
+    lapin::{
+        options::BasicPublishOptions, publisher_confirm::PublisherConfirm, types::AMQPValue,
+        BasicProperties,
+    },
+};
 #[cfg(feature = "consumer")]
 use {
     crate::Error,
@@ -26,20 +33,62 @@ pub trait QueueType {
 
     /// Expose the underlying queue info for this queue
     fn info(&self) -> QueueInfo;
+
+    /// Get the routing keys to publish a message with.  This is only used for
+    /// queues with a topic binding.
+    ///
+    /// A message is published once, but routed by every key returned, so
+    /// queues bound to more than one of them still receive a single copy.
+    fn routing_keys(&self, _msg: &Self::Message) -> Vec<Cow<'static, str>> {
+        vec![Cow::Borrowed("")]
+    }
+
+    /// Get the priority to publish a message with.  This is only used for
+    /// queues with priority lanes.
+    fn priority(&self, _msg: &Self::Message) -> Priority {
+        Priority::Live
+    }
+}
+
+/// The delivery priority of a message.  Consumers of queues with priority
+/// lanes receive all waiting live messages before any backfill messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum Priority {
+    /// Work replaying existing state, such as validator startup updates
+    Backfill = 0,
+    /// Work reflecting a fresh on-chain change
+    Live = 1,
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Self::Live
+    }
+}
+
+impl From<Priority> for u8 {
+    fn from(priority: Priority) -> Self {
+        priority as u8
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum Binding {
     Fanout,
     Direct(String),
+    /// A topic exchange, with the given binding patterns for consumers
+    Topic(Vec<String>),
 }
 
 #[cfg(feature = "consumer")]
 impl Binding {
-    fn routing_key(&self) -> &str {
+    fn routing_keys(&self) -> Vec<&str> {
         match self {
-            Self::Fanout => "",
-            Self::Direct(k) => k.as_ref(),
+            Self::Fanout => vec![""],
+            Self::Direct(k) => vec![k.as_ref()],
+            Self::Topic(k) if k.is_empty() => vec!["#"],
+            Self::Topic(k) => k.iter().map(AsRef::as_ref).collect(),
         }
     }
 }
@@ -59,6 +108,9 @@ pub struct QueueProps {
     pub prefetch: u16,
     pub max_len_bytes: i64,
     pub auto_delete: bool,
+    /// Declare the queue with the `x-max-priority` argument, delivering
+    /// messages in order of their [`Priority`]
+    pub priority_lanes: bool,
     pub retry: Option<RetryProps>,
 }
 
@@ -78,6 +130,8 @@ pub const DLX_DEAD_KEY: &str = "dead";
 pub const DLX_LIVE_KEY: &str = "live";
 #[cfg(feature = "consumer")]
 pub const DLX_TRIAGE_KEY: &str = "triage";
+#[cfg(feature = "consumer")]
+pub const DLX_FAILED_KEY: &str = "failed";
 
 #[cfg(any(feature = "producer", feature = "consumer"))]
 impl<'a> QueueInfo<'a> {
@@ -87,6 +141,7 @@ impl<'a> QueueInfo<'a> {
             match self.0.binding {
                 Binding::Fanout => ExchangeKind::Fanout,
                 Binding::Direct(_) => ExchangeKind::Direct,
+                Binding::Topic(_) => ExchangeKind::Topic,
             },
             ExchangeDeclareOptions::default(),
             FieldTable::default(),
@@ -105,25 +160,111 @@ impl<'a> QueueInfo<'a> {
         Ok(())
     }
 
-    pub(crate) async fn publish(self, chan: &Channel, data: &[u8]) -> Result<PublisherConfirm> {
+    #[inline]
+    pub(crate) fn is_topic(self) -> bool {
+        matches!(self.0.binding, Binding::Topic(_))
+    }
+
+    /// Get the routing key to publish a message with, along with a header
+    /// table routing it by any remaining keys
+    fn routing<'k>(self, routing_keys: &'k [Cow<'static, str>]) -> (&'k str, Option<FieldTable>)
+    where
+        'a: 'k,
+    {
+        let routing_key = match self.0.binding {
+            Binding::Fanout => "",
+            Binding::Direct(ref s) => s,
+            Binding::Topic(_) => routing_keys.first().map_or("", AsRef::as_ref),
+        };
+
+        // RabbitMQ also routes the message by any keys in the BCC header, but
+        // delivers at most one copy to each queue and strips the header
+        if !matches!(self.0.binding, Binding::Topic(_)) || routing_keys.len() < 2 {
+            return (routing_key, None);
+        }
+
+        let mut headers = FieldTable::default();
+        headers.insert(
+            "BCC".into(),
+            AMQPValue::FieldArray(
+                routing_keys[1..]
+                    .iter()
+                    .map(|k| AMQPValue::LongString(k.as_ref().into()))
+                    .collect::<Vec<_>>()
+                    .into(),
+            ),
+        );
+
+        (routing_key, Some(headers))
+    }
+
+    /// Get the properties to publish a message with, tagging it with its
+    /// priority if this queue has priority lanes
+    fn properties(self, encoding: Option<Encoding>, priority: Priority) -> BasicProperties {
+        let mut props = BasicProperties::default();
+
+        if self.0.priority_lanes {
+            props = props.with_priority(priority.into());
+        }
+
+        if let Some(encoding) = encoding {
+            props = props.with_content_encoding(<&str>::from(encoding).into());
+        }
+
+        props
+    }
+
+    pub(crate) async fn publish(
+        self,
+        chan: &Channel,
+        routing_keys: &[Cow<'static, str>],
+        data: &[u8],
+        encoding: Option<Encoding>,
+        priority: Priority,
+    ) -> Result<PublisherConfirm> {
+        let mut props = self.properties(encoding, priority);
+        let (routing_key, headers) = self.routing(routing_keys);
+
+        if let Some(headers) = headers {
+            props = props.with_headers(headers);
+        }
+
         chan.basic_publish(
             self.0.exchange.as_ref(),
-            match self.0.binding {
-                Binding::Fanout => "",
-                Binding::Direct(ref s) => s,
-            },
+            routing_key,
             BasicPublishOptions::default(),
             data,
-            BasicProperties::default(),
+            props,
         )
         .await
         .map_err(Into::into)
     }
 }
 
+#[cfg(feature = "kafka")]
+impl<'a> QueueInfo<'a> {
+    pub(crate) fn exchange(self) -> &'a str {
+        &self.0.exchange
+    }
+
+    /// Returns true if a message with any of the given routing keys would be
+    /// routed to this queue
+    #[cfg(feature = "consumer")]
+    pub(crate) fn accepts_keys<'k>(self, mut keys: impl Iterator<Item = &'k str>) -> bool {
+        match self.0.binding {
+            Binding::Fanout | Binding::Direct(_) => true,
+            Binding::Topic(ref k) => k.is_empty() || keys.any(|key| k.iter().any(|k| k == key)),
+        }
+    }
+}
+
 #[cfg(feature = "consumer")]
 impl<'a> QueueInfo<'a> {
-    fn dl_exchange(self) -> String {
+    pub(crate) fn queue(self) -> &'a str {
+        &self.0.queue
+    }
+
+    pub(crate) fn dl_exchange(self) -> String {
         format!("dlx.{}", self.0.queue)
     }
 
@@ -135,6 +276,10 @@ impl<'a> QueueInfo<'a> {
         format!("triage.dlq.{}", self.0.queue)
     }
 
+    fn dl_failed_queue(self) -> String {
+        format!("failed.dlq.{}", self.0.queue)
+    }
+
     async fn queue_declare(self, chan: &Channel) -> Result<()> {
         let mut queue_fields = FieldTable::default();
 
@@ -153,6 +298,13 @@ impl<'a> QueueInfo<'a> {
             AMQPValue::LongString(DLX_TRIAGE_KEY.into()),
         );
 
+        if self.0.priority_lanes {
+            queue_fields.insert(
+                "x-max-priority".into(),
+                AMQPValue::ShortShortUInt(Priority::Live.into()),
+            );
+        }
+
         chan.queue_declare(
             self.0.queue.as_ref(),
             QueueDeclareOptions {
@@ -209,14 +361,16 @@ impl<'a> QueueInfo<'a> {
         self.exchange_declare(chan).await?;
         self.queue_declare(chan).await?;
 
-        chan.queue_bind(
-            self.0.queue.as_ref(),
-            self.0.exchange.as_ref(),
-            self.0.binding.routing_key(),
-            QueueBindOptions::default(),
-            FieldTable::default(),
-        )
-        .await?;
+        for key in self.0.binding.routing_keys() {
+            chan.queue_bind(
+                self.0.queue.as_ref(),
+                self.0.exchange.as_ref(),
+                key,
+                QueueBindOptions::default(),
+                FieldTable::default(),
+            )
+            .await?;
+        }
 
         chan.basic_qos(self.0.prefetch, BasicQosOptions::default())
             .await?;
@@ -231,6 +385,56 @@ impl<'a> QueueInfo<'a> {
         .map_err(Into::into)
     }
 
+    /// Declare the terminal dead-letter queue, which holds messages the
+    /// dead-letter consumer has given up on until an operator inspects them
+    async fn dl_failed_queue_declare(self, chan: &Channel, exchange: &str) -> Result<String> {
+        let queue = self.dl_failed_queue();
+        let mut queue_fields = FieldTable::default();
+
+        queue_fields.insert(
+            "x-max-length-bytes".into(),
+            // Top out length at 100 MiB
+            AMQPValue::LongLongInt(self.0.max_len_bytes.min(100 * 1024 * 1024)),
+        );
+
+        // Refuse new messages once full rather than dropping the oldest, so
+        // the dead-letter consumer's publish is nacked and it keeps the
+        // message
+        queue_fields.insert(
+            "x-overflow".into(),
+            AMQPValue::LongString("reject-publish".into()),
+        );
+
+        chan.queue_declare(
+            queue.as_ref(),
+            QueueDeclareOptions {
+                auto_delete: self.0.auto_delete,
+                ..QueueDeclareOptions::default()
+            },
+            queue_fields,
+        )
+        .await?;
+
+        chan.queue_bind(
+            queue.as_ref(),
+            exchange,
+            DLX_FAILED_KEY,
+            QueueBindOptions::default(),
+            FieldTable::default(),
+        )
+        .await?;
+
+        Ok(queue)
+    }
+
+    /// Returns (`dl_exchange`, `dl_failed_queue`)
+    pub(crate) async fn init_dl_inspector(self, chan: &Channel) -> Result<(String, String)> {
+        let (exchange, ..) = self.dl_exchange_declare(chan).await?;
+        let queue = self.dl_failed_queue_declare(chan, &exchange).await?;
+
+        Ok((exchange, queue))
+    }
+
     pub(crate) async fn init_dl_consumer(
         self,
         chan: &Channel,
@@ -283,8 +487,6 @@ impl<'a> QueueInfo<'a> {
                 AMQPValue::LongLongInt(self.0.max_len_bytes.min(100 * 1024 * 1024)),
             );
 
-            // TODO: add a true DL queue
-
             chan.queue_declare(
                 triage_queue.as_ref(),
                 QueueDeclareOptions {
@@ -305,6 +507,8 @@ impl<'a> QueueInfo<'a> {
             .await?;
         }
 
+        self.dl_failed_queue_declare(chan, &exchange).await?;
+
         self.queue_declare(chan).await?;
         chan.queue_bind(
             self.0.queue.as_ref(),
@@ -376,3 +580,110 @@ impl DlConsumerInfo {
         millis.try_into().ok()
     }
 }
+
+#[cfg(all(test, feature = "producer"))]
+mod tests {
+    use lapin::types::ShortString;
+
+    use super::*;
+
+    fn props(binding: Binding) -> QueueProps {
+        QueueProps {
+            exchange: "exchange".into(),
+            queue: "queue".into(),
+            binding,
+            prefetch: 1,
+            max_len_bytes: 1024,
+            auto_delete: true,
+            priority_lanes: false,
+            retry: None,
+        }
+    }
+
+    #[test]
+    fn priority_requires_lanes() {
+        let mut lanes = props(Binding::Fanout);
+
+        let published = QueueInfo(&lanes).properties(None, Priority::Backfill);
+        assert_eq!(*published.priority(), None);
+
+        lanes.priority_lanes = true;
+
+        for priority in [Priority::Backfill, Priority::Live] {
+            let published = QueueInfo(&lanes).properties(Some(Encoding::Zstd), priority);
+
+            assert_eq!(*published.priority(), Some(priority.into()));
+            assert_eq!(
+                published
+                    .content_encoding()
+                    .as_ref()
+                    .map(ShortString::as_str),
+                Some("zstd")
+            );
+        }
+
+        // Queues are declared with the highest priority as their maximum
+        assert!(Priority::Backfill < Priority::Live);
+        assert_eq!(u8::from(Priority::Backfill), 0);
+        assert_eq!(u8::from(Priority::Live), 1);
+        assert_eq!(Priority::default(), Priority::Live);
+    }
+
+    #[test]
+    fn routing_uses_bcc_for_extra_keys() {
+        let keys: Vec<Cow<'static, str>> = vec!["a.x".into(), "a.y".into(), "a.z".into()];
+
+        let topic = props(Binding::Topic(vec![]));
+        let (key, headers) = QueueInfo(&topic).routing(&keys);
+        assert_eq!(key, "a.x");
+
+        let mut expected = FieldTable::default();
+        expected.insert(
+            "BCC".into(),
+            AMQPValue::FieldArray(
+                vec![
+                    AMQPValue::LongString("a.y".into()),
+                    AMQPValue::LongString("a.z".into()),
+                ]
+                .into(),
+            ),
+        );
+        assert_eq!(headers, Some(expected));
+
+        let (key, headers) = QueueInfo(&topic).routing(&keys[..1]);
+        assert_eq!((key, headers), ("a.x", None));
+
+        let (key, headers) = QueueInfo(&topic).routing(&[]);
+        assert_eq!((key, headers), ("", None));
+    }
+
+    #[test]
+    fn routing_ignores_keys_without_topics() {
+        let keys: Vec<Cow<'static, str>> = vec!["a.x".into(), "a.y".into()];
+
+        let fanout = props(Binding::Fanout);
+        assert_eq!(QueueInfo(&fanout).routing(&keys), ("", None));
+
+        let direct = props(Binding::Direct("key".into()));
+        assert_eq!(QueueInfo(&direct).routing(&keys), ("key", None));
+    }
+
+    #[cfg(all(feature = "kafka", feature = "consumer"))]
+    #[test]
+    fn accepts_bound_keys() {
+        let topic = props(Binding::Topic(vec!["a.x".into(), "slot".into()]));
+        let info = QueueInfo(&topic);
+
+        assert!(info.accepts_keys(["a.x"].into_iter()));
+        assert!(info.accepts_keys(["b.y", "slot"].into_iter()));
+        assert!(!info.accepts_keys(["b.y", "a.y"].into_iter()));
+        assert!(!info.accepts_keys(std::iter::empty()));
+
+        // An unfiltered topic queue, or any other binding, accepts everything
+        let all = props(Binding::Topic(vec![]));
+        assert!(QueueInfo(&all).accepts_keys(["b.y"].into_iter()));
+
+        let fanout = props(Binding::Fanout);
+        assert!(QueueInfo(&fanout).accepts_keys(std::iter::empty()));
+    }
+}
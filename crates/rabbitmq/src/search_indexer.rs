@@ -8,7 +8,7 @@ use serde_json::Value;
 use crate::{
     queue_type::{Binding, QueueProps, RetryProps},
     suffix::Suffix,
-    Result,
+    Priority, Result,
 };
 
 /// Message data for a document upsert request
@@ -31,9 +31,22 @@ pub enum Message {
         /// MeiliSearch Document
         /// contains primary key and content
         document: Document,
+        /// The priority of the work this upsert resulted from
+        #[serde(default)]
+        priority: Priority,
     },
 }
 
+impl Message {
+    /// Get the delivery priority for this message
+    #[must_use]
+    pub fn priority(&self) -> Priority {
+        match self {
+            Self::Upsert { priority, .. } => *priority,
+        }
+    }
+}
+
 /// AMQP configuration for search indexers
 #[derive(Debug, Clone)]
 pub struct QueueType {
@@ -58,6 +71,7 @@ impl QueueType {
                 prefetch: 4096,
                 max_len_bytes: 100 * 1024 * 1024, // 100 MiB
                 auto_delete: suffix.is_debug(),
+                priority_lanes: false,
                 retry: Some(RetryProps {
                     max_tries: 3,
                     delay_hint: Duration::from_millis(500),
@@ -66,6 +80,15 @@ impl QueueType {
             },
         })
     }
+
+    /// Declare the queue with priority lanes, delivering live messages ahead
+    /// of backfill messages.  Every producer and consumer of the queue must
+    /// agree on this setting.
+    #[must_use]
+    pub fn with_priority_lanes(mut self, priority_lanes: bool) -> Self {
+        self.props.priority_lanes = priority_lanes;
+        self
+    }
 }
 
 impl crate::QueueType for QueueType {
@@ -75,6 +98,11 @@ impl crate::QueueType for QueueType {
     fn info(&self) -> crate::queue_type::QueueInfo {
         (&self.props).into()
     }
+
+    #[inline]
+    fn priority(&self, msg: &Message) -> Priority {
+        msg.priority()
+    }
 }
 
 /// The type of an search indexer producer
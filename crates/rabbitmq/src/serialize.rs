@@ -1,33 +1,306 @@
-#[cfg(feature = "consumer")]
-use std::io::Read;
+//! Wire format for messages sent over AMQP
+//!
+//! Messages are either encoded bare, as MessagePack with struct maps, or
+//! wrapped in a small versioned envelope:
+//!
+//! | Offset | Size | Field                                                  |
+//! |--------|------|--------------------------------------------------------|
+//! | 0      | 1    | Magic byte `0xc1`, which never begins valid MessagePack |
+//! | 1      | 1    | Envelope version, currently `1`                        |
+//! | 2      | 2    | Big-endian message schema version                      |
+//! | 4      | 1    | [`Codec`] of the payload                               |
+//! | 5      | 1    | Reserved, always `0`                                   |
+//! | 6      | ...  | Payload                                                |
+//!
+//! Consumers detect the format of each message, so producers can switch
+//! formats once every consumer understands the new one.  Bare messages are
+//! treated as schema version 0.  Payloads are never compressed here; see
+//! [`compression`](crate::compression) instead.
+
 #[cfg(feature = "producer")]
 use std::io::Write;
+use std::{fmt, str::FromStr};
+#[cfg(feature = "consumer")]
+use std::{io::Read, ops::RangeInclusive};
+
+use crate::{Error, Result};
+
+/// The schema version of the messages defined by this crate.  This should be
+/// bumped for any change to a message type that older consumers cannot decode.
+pub const SCHEMA_VERSION: u16 = 1;
+
+/// The range of schema versions accepted by consumers by default
+#[cfg(feature = "consumer")]
+pub const DEFAULT_VERSIONS: RangeInclusive<u16> = 0..=SCHEMA_VERSION;
+
+const MAGIC: u8 = 0xc1;
+const ENVELOPE_VERSION: u8 = 1;
+const HEADER_LEN: usize = 6;
+
+/// An encoding for the payload of an enveloped message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "kebab-case")]
+#[repr(u8)]
+pub enum Codec {
+    /// MessagePack with struct maps
+    #[strum(serialize = "msgpack")]
+    MsgPack = 0,
+    /// CBOR
+    Cbor = 1,
+}
+
+impl Codec {
+    fn from_u8(b: u8) -> Result<Self> {
+        Ok(match b {
+            0 => Self::MsgPack,
+            1 => Self::Cbor,
+            _ => return Err(Error::InvalidEnvelope("Unknown codec")),
+        })
+    }
+}
+
+/// The wire format a producer writes messages in
+///
+/// Formats are written as `bare` or as the name of a codec, i.e. `msgpack` or
+/// `cbor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// Bare MessagePack, readable by consumers predating the envelope
+    Bare,
+    /// A versioned envelope around a payload in the given codec
+    Envelope {
+        /// The encoding of the payload
+        codec: Codec,
+    },
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Self::Bare
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Bare => f.write_str("bare"),
+            Self::Envelope { codec } => write!(f, "{}", codec),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s == "bare" {
+            return Ok(Self::Bare);
+        }
+
+        Ok(Self::Envelope {
+            codec: s.parse().map_err(|_| Error::InvalidFormat(s.into()))?,
+        })
+    }
+}
+
+#[cfg(feature = "producer")]
+fn encode<M: serde::Serialize>(w: impl Write, msg: &M, codec: Codec) -> Result<()> {
+    match codec {
+        Codec::MsgPack => {
+            let mut ser = rmp_serde::Serializer::new(w)
+                .with_binary()
+                .with_struct_map();
+
+            msg.serialize(&mut ser)?;
+        },
+        Codec::Cbor => ciborium::ser::into_writer(msg, w)?,
+    }
+
+    Ok(())
+}
 
 /// Serialize a message into a [`Write`] stream
 ///
 /// # Errors
 /// This function fails if an I/O error occurs or a wire format error occurs.
 #[cfg(feature = "producer")]
-pub fn serialize<M: serde::Serialize>(
-    w: impl Write,
-    msg: &M,
-) -> Result<(), rmp_serde::encode::Error> {
-    let mut ser = rmp_serde::Serializer::new(w)
-        .with_binary()
-        .with_struct_map();
+pub fn serialize<M: serde::Serialize>(mut w: impl Write, msg: &M, format: Format) -> Result<()> {
+    let codec = match format {
+        Format::Bare => return encode(w, msg, Codec::MsgPack),
+        Format::Envelope { codec } => codec,
+    };
+
+    let [v0, v1] = SCHEMA_VERSION.to_be_bytes();
+    w.write_all(&[MAGIC, ENVELOPE_VERSION, v0, v1, codec as u8, 0])?;
+
+    encode(w, msg, codec)
+}
+
+#[cfg(feature = "consumer")]
+fn decode<M: for<'a> serde::Deserialize<'a>>(r: impl Read, codec: Codec) -> Result<M> {
+    Ok(match codec {
+        Codec::MsgPack => {
+            let mut de = rmp_serde::Deserializer::new(r).with_binary();
 
-    msg.serialize(&mut ser)
+            M::deserialize(&mut de)?
+        },
+        Codec::Cbor => ciborium::de::from_reader(r)?,
+    })
 }
 
-/// Deserialize a message from a [`Read`] stream
+/// Deserialize a message, accepting only the given range of schema versions
 ///
 /// # Errors
-/// This function fails if an I/O error occurs or a wire format error occurs.
+/// This function fails if the envelope is malformed, the message's schema
+/// version is not accepted, or a wire format error occurs.
 #[cfg(feature = "consumer")]
 pub fn deserialize<M: for<'a> serde::Deserialize<'a>>(
-    r: impl Read,
-) -> Result<M, rmp_serde::decode::Error> {
-    let mut de = rmp_serde::Deserializer::new(r).with_binary();
+    data: &[u8],
+    versions: &RangeInclusive<u16>,
+) -> Result<M> {
+    if data.first() != Some(&MAGIC) {
+        if !versions.contains(&0) {
+            return Err(Error::UnsupportedVersion(0));
+        }
+
+        return decode(data, Codec::MsgPack);
+    }
+
+    if data.len() < HEADER_LEN {
+        return Err(Error::InvalidEnvelope("Envelope header was truncated"));
+    }
+
+    let (header, payload) = data.split_at(HEADER_LEN);
+
+    if header[1] != ENVELOPE_VERSION {
+        return Err(Error::InvalidEnvelope("Unknown envelope version"));
+    }
+
+    let version = u16::from_be_bytes([header[2], header[3]]);
+
+    if !versions.contains(&version) {
+        return Err(Error::UnsupportedVersion(version));
+    }
+
+    let codec = Codec::from_u8(header[4])?;
+
+    if header[5] != 0 {
+        return Err(Error::InvalidEnvelope("Reserved header byte was set"));
+    }
+
+    decode(payload, codec)
+}
+
+#[cfg(all(test, feature = "producer", feature = "consumer"))]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Msg {
+        id: u64,
+        name: String,
+        data: Vec<u8>,
+    }
+
+    fn msg() -> Msg {
+        Msg {
+            id: 42,
+            name: "test".into(),
+            data: vec![7; 256],
+        }
+    }
+
+    fn round_trip(format: Format) -> Vec<u8> {
+        let mut buf = vec![];
+        serialize(&mut buf, &msg(), format).unwrap();
+
+        assert_eq!(deserialize::<Msg>(&buf, &DEFAULT_VERSIONS).unwrap(), msg());
+
+        buf
+    }
+
+    #[test]
+    fn round_trip_formats() {
+        let bare = round_trip(Format::Bare);
+        assert_ne!(bare[0], MAGIC);
+
+        for codec in [Codec::MsgPack, Codec::Cbor] {
+            let buf = round_trip(Format::Envelope { codec });
+
+            assert_eq!(buf[0], MAGIC);
+            assert_eq!(buf[4], codec as u8);
+            assert_eq!(buf[5], 0);
+        }
+    }
+
+    #[test]
+    fn parse_formats() {
+        for s in ["bare", "msgpack", "cbor"] {
+            assert_eq!(s.parse::<Format>().unwrap().to_string(), s);
+        }
+
+        assert!("json".parse::<Format>().is_err());
+        assert!("msgpack+zstd".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn reject_versions() {
+        let bare = round_trip(Format::Bare);
+        assert!(matches!(
+            deserialize::<Msg>(&bare, &(1..=1)),
+            Err(Error::UnsupportedVersion(0))
+        ));
+
+        let mut buf = round_trip(Format::Envelope {
+            codec: Codec::MsgPack,
+        });
+        assert!(matches!(
+            deserialize::<Msg>(&buf, &(0..=0)),
+            Err(Error::UnsupportedVersion(SCHEMA_VERSION))
+        ));
+
+        buf[1] = ENVELOPE_VERSION + 1;
+        assert!(matches!(
+            deserialize::<Msg>(&buf, &DEFAULT_VERSIONS),
+            Err(Error::InvalidEnvelope(_))
+        ));
+    }
+
+    #[test]
+    fn reject_truncated_header() {
+        let buf = round_trip(Format::Envelope { codec: Codec::Cbor });
+
+        for len in 1..HEADER_LEN {
+            assert!(matches!(
+                deserialize::<Msg>(&buf[..len], &DEFAULT_VERSIONS),
+                Err(Error::InvalidEnvelope(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn reject_unknown_codec() {
+        let mut buf = round_trip(Format::Envelope {
+            codec: Codec::MsgPack,
+        });
+
+        buf[4] = 0xff;
+        assert!(matches!(
+            deserialize::<Msg>(&buf, &DEFAULT_VERSIONS),
+            Err(Error::InvalidEnvelope(_))
+        ));
+    }
+
+    #[test]
+    fn reject_reserved_byte() {
+        let mut buf = round_trip(Format::Envelope { codec: Codec::Cbor });
 
-    M::deserialize(&mut de)
+        buf[5] = 1;
+        assert!(matches!(
+            deserialize::<Msg>(&buf, &DEFAULT_VERSIONS),
+            Err(Error::InvalidEnvelope(_))
+        ));
+    }
 }